@@ -0,0 +1,268 @@
+//! Command handlers: read the on-chain state an instruction needs, send it and report the result.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use open_venture_client::accounts::{
+    CompanyProfile, FundingRound, InvestorPortfolio, InvestorPosition, OwnerRegistry, PortfolioEntry,
+    RoundVault, TreasuryLedger,
+};
+use open_venture_client::{accounts, instructions, pda, RoundSeed};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::output::{
+    self, CompanyCreated, PortfolioView, PositionView, RoundCreated, RoundFinalized, RoundView, Transfer,
+};
+use crate::rpc::RpcClient;
+use crate::{Cli, Command, CompanyCommand, CreateRound, Result, RoundCommand};
+
+struct Context {
+    rpc: RpcClient,
+    keypair_path: PathBuf,
+    json: bool,
+}
+
+impl Context {
+    fn signer(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|err| format!("could not read keypair {}: {err}", self.keypair_path.display()).into())
+    }
+
+    fn send(&self, signer: &Keypair, instruction: Instruction) -> Result<String> {
+        let blockhash = self.rpc.latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signer.pubkey()),
+            &[signer],
+            blockhash,
+        );
+        self.rpc.send_and_confirm(&transaction)
+    }
+
+    fn round(&self, address: &Pubkey) -> Result<FundingRound> {
+        self.rpc.expect_account(address, "funding round")
+    }
+}
+
+pub fn run(cli: Cli) -> Result<()> {
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => default_keypair_path()?,
+    };
+    let ctx = Context {
+        rpc: RpcClient::new(cli.url),
+        keypair_path,
+        json: cli.json,
+    };
+
+    match cli.command {
+        Command::Company(CompanyCommand::Create { name, bio }) => create_company(&ctx, &name, &bio),
+        Command::Round(RoundCommand::Create(args)) => create_round(&ctx, args),
+        Command::Round(RoundCommand::Fund { round, amount, allowlist_proof }) => {
+            fund_round(&ctx, &round, amount, allowlist_proof)
+        }
+        Command::Round(RoundCommand::Withdraw { round, amount }) => withdraw(&ctx, &round, amount),
+        Command::Round(RoundCommand::Repay { round, amount }) => repay(&ctx, &round, amount),
+        Command::Round(RoundCommand::Finalize { round }) => finalize(&ctx, &round),
+        Command::Round(RoundCommand::ClaimRefund { round }) => claim_refund(&ctx, &round),
+        Command::Round(RoundCommand::ClaimRepayment { round }) => claim_repayment(&ctx, &round),
+        Command::Round(RoundCommand::Show { round }) => show_round(&ctx, &round),
+        Command::Portfolio { investor } => portfolio(&ctx, investor),
+    }
+}
+
+fn create_company(ctx: &Context, name: &str, bio: &str) -> Result<()> {
+    let owner = ctx.signer()?;
+    let company_count = ctx.rpc
+        .account::<OwnerRegistry>(&pda::find_owner_registry(&owner.pubkey()).0)?
+        .map_or(0, |registry| registry.company_count);
+
+    let instruction = instructions::create_company_profile(&owner.pubkey(), name, bio, company_count);
+    let signature = ctx.send(&owner, instruction)?;
+
+    let company_profile = pda::find_company_profile(&owner.pubkey(), name).0;
+    output::print(
+        &CompanyCreated {
+            signature,
+            company_profile: company_profile.to_string(),
+            company_treasury: pda::find_company_treasury(&owner.pubkey(), &company_profile).0.to_string(),
+        },
+        ctx.json,
+    )
+}
+
+fn create_round(ctx: &Context, args: CreateRound) -> Result<()> {
+    let owner = ctx.signer()?;
+    let company: CompanyProfile = ctx.rpc.expect_account(&args.company, "company profile")?;
+    let index = company.round_count;
+    let funding_start = match args.funding_start {
+        Some(funding_start) => funding_start,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+    };
+
+    let instruction = instructions::create_funding_round(
+        &owner.pubkey(),
+        &args.company,
+        index,
+        &args.label,
+        args.target_amount,
+        args.interest_rate,
+        args.repayment_deadline,
+        funding_start,
+        args.funding_end,
+    );
+    let signature = ctx.send(&owner, instruction)?;
+
+    output::print(
+        &RoundCreated {
+            signature,
+            funding_round: pda::find_funding_round(&args.company, RoundSeed::Index(index)).0.to_string(),
+            index,
+        },
+        ctx.json,
+    )
+}
+
+fn fund_round(ctx: &Context, address: &Pubkey, amount: u64, allowlist_proof: Vec<[u8; 32]>) -> Result<()> {
+    let investor = ctx.signer()?;
+    let round = ctx.round(address)?;
+
+    // a repeat deposit reuses the position's portfolio slot, a first one takes the next free slot
+    let position = ctx.rpc
+        .account::<InvestorPosition>(&pda::find_investor_position(address, &investor.pubkey()).0)?;
+    let portfolio = ctx.rpc
+        .account::<InvestorPortfolio>(&pda::find_investor_portfolio(&investor.pubkey()).0)?;
+    let portfolio_slot = accounts::portfolio_slot(position.as_ref(), portfolio.as_ref());
+
+    let instruction = instructions::fund_company(
+        &investor.pubkey(),
+        &round.company,
+        RoundSeed::from(&round),
+        portfolio_slot,
+        amount,
+        allowlist_proof,
+        round.kyc_verifier,
+    );
+    let signature = ctx.send(&investor, instruction)?;
+    print_transfer(ctx, "Funded", signature, address, amount)
+}
+
+fn withdraw(ctx: &Context, address: &Pubkey, amount: u64) -> Result<()> {
+    let owner = ctx.signer()?;
+    let round = ctx.round(address)?;
+    let instruction = instructions::withdraw_funds(&owner.pubkey(), &round.company, RoundSeed::from(&round), amount);
+    let signature = ctx.send(&owner, instruction)?;
+    print_transfer(ctx, "Withdrew", signature, address, amount)
+}
+
+fn repay(ctx: &Context, address: &Pubkey, amount: u64) -> Result<()> {
+    let owner = ctx.signer()?;
+    let round = ctx.round(address)?;
+    let instruction = instructions::repay_funding_round(&owner.pubkey(), &round.company, RoundSeed::from(&round), amount);
+    let signature = ctx.send(&owner, instruction)?;
+    print_transfer(ctx, "Repaid", signature, address, amount)
+}
+
+fn finalize(ctx: &Context, address: &Pubkey) -> Result<()> {
+    // anyone may finalize a round, the keypair only pays the fee
+    let payer = ctx.signer()?;
+    let round = ctx.round(address)?;
+    let instruction = instructions::finalize_round(&round.company, RoundSeed::from(&round));
+    let signature = ctx.send(&payer, instruction)?;
+
+    let round = ctx.round(address)?;
+    output::print(
+        &RoundFinalized {
+            signature,
+            funding_round: address.to_string(),
+            status: format!("{:?}", round.status),
+        },
+        ctx.json,
+    )
+}
+
+fn claim_refund(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let investor = ctx.signer()?;
+    let round = ctx.round(address)?;
+    let seed = RoundSeed::from(&round);
+    let vault = pda::find_funding_round_vault(&round.company, seed).0;
+    let instruction = instructions::claim_refund(&investor.pubkey(), &round.company, seed);
+    let (signature, amount) = send_claim(ctx, &investor, instruction, &vault)?;
+    print_transfer(ctx, "Refunded", signature, address, amount)
+}
+
+fn claim_repayment(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let investor = ctx.signer()?;
+    let round = ctx.round(address)?;
+    let seed = RoundSeed::from(&round);
+    let vault = pda::find_repayment_vault(&round.company, seed).0;
+    let instruction = instructions::claim_repayment(&investor.pubkey(), &round.company, seed);
+    let (signature, amount) = send_claim(ctx, &investor, instruction, &vault)?;
+    print_transfer(ctx, "Claimed", signature, address, amount)
+}
+
+/// Sends a claim paid out of `vault`, returning the amount paid as the drop in the vault's
+/// tracked balance.
+fn send_claim(ctx: &Context, investor: &Keypair, instruction: Instruction, vault: &Pubkey) -> Result<(String, u64)> {
+    let before: RoundVault = ctx.rpc.expect_account(vault, "round vault")?;
+    let signature = ctx.send(investor, instruction)?;
+    let after: RoundVault = ctx.rpc.expect_account(vault, "round vault")?;
+    Ok((signature, before.tracked_balance.saturating_sub(after.tracked_balance)))
+}
+
+fn print_transfer(ctx: &Context, action: &'static str, signature: String, round: &Pubkey, amount: u64) -> Result<()> {
+    output::print(
+        &Transfer {
+            action,
+            signature,
+            funding_round: round.to_string(),
+            amount,
+        },
+        ctx.json,
+    )
+}
+
+fn show_round(ctx: &Context, address: &Pubkey) -> Result<()> {
+    let round = ctx.round(address)?;
+    // rounds created before the treasury kept a ledger have none until they are migrated
+    let ledger = ctx.rpc.account::<TreasuryLedger>(&pda::find_treasury_ledger(address).0)?;
+    output::print(&RoundView::new(address, &round, ledger.as_ref()), ctx.json)
+}
+
+fn portfolio(ctx: &Context, investor: Option<Pubkey>) -> Result<()> {
+    let investor = match investor {
+        Some(investor) => investor,
+        None => ctx.signer()?.pubkey(),
+    };
+    let position_count = ctx.rpc
+        .account::<InvestorPortfolio>(&pda::find_investor_portfolio(&investor).0)?
+        .map_or(0, |portfolio| portfolio.position_count);
+
+    let mut positions = Vec::new();
+    for index in 0..position_count {
+        let entry: PortfolioEntry = ctx.rpc
+            .expect_account(&pda::find_portfolio_entry(&investor, index).0, "portfolio entry")?;
+        let Some(position) = ctx.rpc.account::<InvestorPosition>(&entry.investor_position)? else {
+            continue;
+        };
+        let round = ctx.rpc.account::<FundingRound>(&entry.funding_round)?;
+        positions.push(PositionView::new(index, &entry.investor_position, &position, round.as_ref()));
+    }
+
+    output::print(
+        &PortfolioView {
+            investor: investor.to_string(),
+            positions,
+        },
+        ctx.json,
+    )
+}
+
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or("HOME is not set, pass --keypair")?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
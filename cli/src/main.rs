@@ -0,0 +1,153 @@
+//! `open-venture`: command-line tool for founders and investors.
+//!
+//! Signs with a Solana keypair file and talks to any RPC endpoint, a local test validator by
+//! default. Amounts are in lamports and timestamps in unix seconds.
+
+mod commands;
+mod output;
+mod rpc;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anchor_lang::prelude::Pubkey;
+use clap::{Args, Parser, Subcommand};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(name = "open-venture", version, about = "Raise and invest in open_venture funding rounds")]
+pub struct Cli {
+    /// Keypair signing and paying for transactions [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', global = true)]
+    pub keypair: Option<PathBuf>,
+    /// RPC endpoint of the cluster
+    #[arg(long, short = 'u', global = true, default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Manage company profiles
+    #[command(subcommand)]
+    Company(CompanyCommand),
+    /// Manage and invest in funding rounds
+    #[command(subcommand)]
+    Round(RoundCommand),
+    /// List an investor's positions
+    Portfolio {
+        /// Investor to list, the keypair's wallet by default
+        #[arg(long)]
+        investor: Option<Pubkey>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CompanyCommand {
+    /// Create a company profile owned by the keypair's wallet
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long, default_value = "")]
+        bio: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RoundCommand {
+    /// Open the company's next funding round
+    Create(CreateRound),
+    /// Deposit into a round
+    Fund {
+        #[arg(long)]
+        round: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// Hex-encoded merkle proof node for allowlisted rounds, repeated in order
+        #[arg(long = "proof", value_parser = parse_hash)]
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    /// Move raised capital from the round vault into the company treasury
+    Withdraw {
+        #[arg(long)]
+        round: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Repay investors from the company treasury
+    Repay {
+        #[arg(long)]
+        round: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Close a round's funding window once it has ended, marking the round funded or failed
+    Finalize {
+        #[arg(long)]
+        round: Pubkey,
+    },
+    /// Reclaim a deposit from a failed or cancelled round
+    ClaimRefund {
+        #[arg(long)]
+        round: Pubkey,
+    },
+    /// Claim the investor's share of a repaid or defaulted round
+    ClaimRepayment {
+        #[arg(long)]
+        round: Pubkey,
+    },
+    /// Print a round's state
+    Show {
+        #[arg(long)]
+        round: Pubkey,
+    },
+}
+
+#[derive(Args)]
+pub struct CreateRound {
+    /// Company profile opening the round
+    #[arg(long)]
+    pub company: Pubkey,
+    #[arg(long, default_value = "")]
+    pub label: String,
+    #[arg(long)]
+    pub target_amount: u64,
+    /// Interest owed to investors, in percent of the target amount
+    #[arg(long)]
+    pub interest_rate: u64,
+    #[arg(long)]
+    pub repayment_deadline: u64,
+    /// Start of the funding window [default: now]
+    #[arg(long)]
+    pub funding_start: Option<u64>,
+    #[arg(long)]
+    pub funding_end: u64,
+}
+
+fn parse_hash(value: &str) -> std::result::Result<[u8; 32], String> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    if value.len() != 64 || !value.is_ascii() {
+        return Err("expected 32 hex-encoded bytes".to_string());
+    }
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&value[2 * i..2 * i + 2], 16).map_err(|err| err.to_string())?;
+    }
+    Ok(hash)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match commands::run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Command results, printed either as text or as JSON with `--json`.
+
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use open_venture_client::accounts::{FundingRound, InvestorPosition, TreasuryLedger};
+use serde::Serialize;
+
+use crate::Result;
+
+pub fn print<T: Serialize + fmt::Display>(output: &T, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(output)?);
+    } else {
+        println!("{output}");
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct CompanyCreated {
+    pub signature: String,
+    pub company_profile: String,
+    pub company_treasury: String,
+}
+
+impl fmt::Display for CompanyCreated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Created company {}", self.company_profile)?;
+        writeln!(f, "Treasury:  {}", self.company_treasury)?;
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+pub struct RoundCreated {
+    pub signature: String,
+    pub funding_round: String,
+    pub index: u64,
+}
+
+impl fmt::Display for RoundCreated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Created round #{} {}", self.index, self.funding_round)?;
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+/// Result of moving lamports into or out of a round.
+#[derive(Serialize)]
+pub struct Transfer {
+    #[serde(skip)]
+    pub action: &'static str,
+    pub signature: String,
+    pub funding_round: String,
+    pub amount: u64,
+}
+
+impl fmt::Display for Transfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {} lamports, round {}", self.action, self.amount, self.funding_round)?;
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+pub struct RoundFinalized {
+    pub signature: String,
+    pub funding_round: String,
+    pub status: String,
+}
+
+impl fmt::Display for RoundFinalized {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Finalized round {}: {}", self.funding_round, self.status)?;
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+pub struct RoundView {
+    pub address: String,
+    pub company: String,
+    pub index: Option<u64>,
+    pub label: String,
+    pub status: String,
+    pub target_amount: u64,
+    pub total_raised: u64,
+    pub interest_rate: u64,
+    pub funding_start: u64,
+    pub funding_end: u64,
+    pub repayment_deadline: u64,
+    pub total_withdrawn: u64,
+    pub total_repaid: u64,
+    pub total_repayment_due: Option<u64>,
+    /// Round capital the company treasury holds and has not repaid, None for rounds without a
+    /// treasury ledger
+    pub treasury_outstanding: Option<u64>,
+    pub swept: Option<u64>,
+    pub collateral_amount: u64,
+    pub collateral_mint: Option<String>,
+}
+
+impl RoundView {
+    pub fn new(address: &Pubkey, round: &FundingRound, ledger: Option<&TreasuryLedger>) -> Self {
+        Self {
+            address: address.to_string(),
+            company: round.company.to_string(),
+            index: round.indexed.then_some(round.index),
+            label: round.id.clone(),
+            status: format!("{:?}", round.status),
+            target_amount: round.target_amount,
+            total_raised: round.total_raised,
+            interest_rate: round.interest_rate,
+            funding_start: round.funding_start,
+            funding_end: round.funding_end,
+            repayment_deadline: round.repayment_deadline,
+            total_withdrawn: round.total_withdrawn,
+            total_repaid: round.total_repaid,
+            total_repayment_due: round.total_repayment_due().ok(),
+            treasury_outstanding: ledger.map(TreasuryLedger::outstanding),
+            swept: ledger.map(|ledger| ledger.swept),
+            collateral_amount: round.collateral_amount,
+            collateral_mint: round.collateral_mint.map(|mint| mint.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for RoundView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Round               {}", self.address)?;
+        writeln!(f, "Company             {}", self.company)?;
+        match self.index {
+            Some(index) => writeln!(f, "Index               {index}")?,
+            None => writeln!(f, "Index               - (legacy round)")?,
+        }
+        if !self.label.is_empty() {
+            writeln!(f, "Label               {}", self.label)?;
+        }
+        writeln!(f, "Status              {}", self.status)?;
+        writeln!(f, "Raised              {} / {} lamports", self.total_raised, self.target_amount)?;
+        writeln!(f, "Interest rate       {}%", self.interest_rate)?;
+        writeln!(f, "Funding window      {} - {}", self.funding_start, self.funding_end)?;
+        writeln!(f, "Repayment deadline  {}", self.repayment_deadline)?;
+        writeln!(f, "Withdrawn           {} lamports", self.total_withdrawn)?;
+        match self.total_repayment_due {
+            Some(due) => writeln!(f, "Repaid              {} / {} lamports", self.total_repaid, due)?,
+            None => writeln!(f, "Repaid              {} lamports", self.total_repaid)?,
+        }
+        if let (Some(outstanding), Some(swept)) = (self.treasury_outstanding, self.swept) {
+            writeln!(f, "Outstanding         {outstanding} lamports in the company treasury")?;
+            writeln!(f, "Swept               {swept} lamports of stray transfers")?;
+        }
+        match &self.collateral_mint {
+            Some(mint) => write!(f, "Collateral          {} of mint {}", self.collateral_amount, mint),
+            None => write!(f, "Collateral          {} lamports", self.collateral_amount),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PositionView {
+    pub index: u64,
+    pub funding_round: String,
+    pub investor_position: String,
+    pub company: Option<String>,
+    pub status: Option<String>,
+    pub amount: u64,
+    pub refunded: bool,
+    pub repayment_claimed: u64,
+}
+
+impl PositionView {
+    pub fn new(
+        index: u64,
+        investor_position: &Pubkey,
+        position: &InvestorPosition,
+        round: Option<&FundingRound>,
+    ) -> Self {
+        Self {
+            index,
+            funding_round: position.funding_round.to_string(),
+            investor_position: investor_position.to_string(),
+            company: round.map(|round| round.company.to_string()),
+            status: round.map(|round| format!("{:?}", round.status)),
+            amount: position.amount,
+            refunded: position.refunded,
+            repayment_claimed: position.repayment_claimed,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PortfolioView {
+    pub investor: String,
+    pub positions: Vec<PositionView>,
+}
+
+impl fmt::Display for PortfolioView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Portfolio of {}: {} position(s)", self.investor, self.positions.len())?;
+        for position in &self.positions {
+            write!(
+                f,
+                "\n#{:<3} round {}  {:<9}  invested {} lamports, repayment claimed {}{}",
+                position.index,
+                position.funding_round,
+                position.status.as_deref().unwrap_or("Closed"),
+                position.amount,
+                position.repayment_claimed,
+                if position.refunded { ", refunded" } else { "" },
+            )?;
+        }
+        Ok(())
+    }
+}
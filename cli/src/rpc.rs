@@ -0,0 +1,110 @@
+//! Minimal JSON-RPC client covering the calls the CLI makes.
+
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::AccountDeserialize;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+
+use crate::Result;
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const CONFIRMATION_ATTEMPTS: u32 = 60;
+
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    /// Fetches and decodes a program account, `None` when it does not exist.
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let Some(data) = self.account_data(address)? else {
+            return Ok(None);
+        };
+        let account = open_venture_client::accounts::decode(&data)
+            .map_err(|err| format!("account {address} could not be decoded: {err}"))?;
+        Ok(Some(account))
+    }
+
+    /// Like [`RpcClient::account`], failing when the account does not exist.
+    pub fn expect_account<T: AccountDeserialize>(&self, address: &Pubkey, kind: &str) -> Result<T> {
+        self.account(address)?
+            .ok_or_else(|| format!("{kind} {address} does not exist").into())
+    }
+
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let result = self.request(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        let value = &result["value"];
+        if value.is_null() {
+            return Ok(None);
+        }
+        let encoded = value["data"][0].as_str().ok_or("malformed getAccountInfo response")?;
+        Ok(Some(STANDARD.decode(encoded)?))
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.request("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let blockhash = result["value"]["blockhash"].as_str().ok_or("malformed getLatestBlockhash response")?;
+        Ok(Hash::from_str(blockhash)?)
+    }
+
+    /// Sends a signed transaction and waits until it is confirmed, returning its signature.
+    pub fn send_and_confirm(&self, transaction: &Transaction) -> Result<String> {
+        let encoded = STANDARD.encode(bincode::serialize(transaction)?);
+        let signature = self.request(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature.as_str().ok_or("malformed sendTransaction response")?.to_string();
+
+        for _ in 0..CONFIRMATION_ATTEMPTS {
+            let result = self.request("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(format!("transaction {signature} failed: {}", status["err"]).into());
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(CONFIRMATION_POLL_INTERVAL);
+        }
+        Err(format!("transaction {signature} was not confirmed in time").into())
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .map_err(|err| format!("{method} request to {} failed: {err}", self.url))?
+            .into_json()?;
+
+        if let Some(error) = response.get("error") {
+            let mut message = format!(
+                "{method} failed: {}",
+                error["message"].as_str().unwrap_or("unknown error"),
+            );
+            // preflight failures carry the program logs, which name the program error
+            if let Some(logs) = error["data"]["logs"].as_array() {
+                for log in logs.iter().filter_map(Value::as_str) {
+                    message.push_str("\n  ");
+                    message.push_str(log);
+                }
+            }
+            return Err(message.into());
+        }
+        Ok(response["result"].clone())
+    }
+}
@@ -0,0 +1,43 @@
+use std::process::{Command, Output};
+
+use anchor_lang::prelude::Pubkey;
+
+fn open_venture(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_open-venture"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn lists_the_subcommands() {
+    let output = open_venture(&["--help"]);
+    assert!(output.status.success());
+    let help = String::from_utf8(output.stdout).unwrap();
+    for command in ["company", "round", "portfolio"] {
+        assert!(help.contains(command), "{command} missing from:\n{help}");
+    }
+
+    let output = open_venture(&["round", "--help"]);
+    let help = String::from_utf8(output.stdout).unwrap();
+    for command in ["create", "fund", "withdraw", "repay", "finalize", "claim-refund", "claim-repayment", "show"] {
+        assert!(help.contains(command), "{command} missing from:\n{help}");
+    }
+}
+
+#[test]
+fn rejects_a_malformed_allowlist_proof() {
+    let round = Pubkey::new_unique().to_string();
+    let output = open_venture(&["round", "fund", "--round", &round, "--amount", "1", "--proof", "abc"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("expected 32 hex-encoded bytes"));
+}
+
+#[test]
+fn reports_an_unreachable_rpc_endpoint() {
+    let round = Pubkey::new_unique().to_string();
+    let output = open_venture(&["--url", "http://127.0.0.1:9", "round", "show", "--round", &round]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: getAccountInfo request to http://127.0.0.1:9 failed"), "{stderr}");
+}
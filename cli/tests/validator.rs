@@ -0,0 +1,242 @@
+//! End-to-end run of the CLI against `solana-test-validator` with the built program.
+//!
+//! Ignored by default since it needs the Solana tool suite on `PATH` and the program built
+//! (`anchor build`); `make test-cli` builds the program and runs it.
+//! `OPEN_VENTURE_SO` points it at another build of the program.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{hash::Hash, instruction::Instruction};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use open_venture_client::instructions;
+use serde_json::{json, Value};
+use solana_keypair::{write_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+const RPC_PORT: u16 = 18899;
+const SOL: u64 = 1_000_000_000;
+const FUNDING_WINDOW: u64 = 20;
+
+/// A test validator running for the length of the test, with its ledger and the test's wallets
+/// in a scratch directory.
+struct Validator {
+    process: Child,
+    scratch: PathBuf,
+    url: String,
+}
+
+impl Validator {
+    fn start(upgrade_authority: &Pubkey) -> Self {
+        let program = env::var_os("OPEN_VENTURE_SO").map(PathBuf::from).unwrap_or_else(|| {
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy/open_venture.so")
+        });
+        assert!(program.exists(), "{} not found, build the program first", program.display());
+
+        let scratch = env::temp_dir().join(format!("open-venture-validator-{}", std::process::id()));
+        std::fs::create_dir_all(&scratch).unwrap();
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(scratch.join("ledger"))
+            .args(["--rpc-port", &RPC_PORT.to_string()])
+            .arg("--upgradeable-program")
+            .arg(open_venture_client::ID.to_string())
+            .arg(&program)
+            .arg(upgrade_authority.to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("solana-test-validator not found on PATH");
+        let validator = Self {
+            process,
+            scratch,
+            url: format!("http://127.0.0.1:{RPC_PORT}"),
+        };
+
+        let started = Instant::now();
+        while validator.try_request("getHealth", json!([])).as_ref().and_then(Value::as_str) != Some("ok") {
+            assert!(started.elapsed() < Duration::from_secs(60), "validator did not start");
+            thread::sleep(Duration::from_millis(500));
+        }
+        validator
+    }
+
+    fn try_request(&self, method: &str, params: Value) -> Option<Value> {
+        let response: Value = ureq::post(&self.url)
+            .send_json(json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .ok()?
+            .into_json()
+            .ok()?;
+        response.get("result").cloned()
+    }
+
+    fn request(&self, method: &str, params: Value) -> Value {
+        self.try_request(method, params).unwrap_or_else(|| panic!("{method} failed"))
+    }
+
+    fn balance(&self, address: &Pubkey) -> u64 {
+        self.request("getBalance", json!([address.to_string(), { "commitment": "confirmed" }]))["value"]
+            .as_u64()
+            .unwrap()
+    }
+
+    fn airdrop(&self, address: &Pubkey, lamports: u64) {
+        let balance = self.balance(address);
+        self.request("requestAirdrop", json!([address.to_string(), lamports]));
+        self.wait_for(|| self.balance(address) >= balance + lamports);
+    }
+
+    /// Sends an instruction the CLI has no command for, `None` when it fails.
+    fn send(&self, signer: &Keypair, instruction: Instruction) -> Option<()> {
+        let blockhash = self.request("getLatestBlockhash", json!([{ "commitment": "confirmed" }]));
+        let blockhash: Hash = blockhash["value"]["blockhash"].as_str().unwrap().parse().unwrap();
+        let transaction =
+            Transaction::new_signed_with_payer(&[instruction], Some(&signer.pubkey()), &[signer], blockhash);
+        let encoded = STANDARD.encode(bincode::serialize(&transaction).unwrap());
+        let signature = self.try_request(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let status = || self.request("getSignatureStatuses", json!([[signature]]))["value"][0].clone();
+        self.wait_for(|| matches!(status()["confirmationStatus"].as_str(), Some("confirmed" | "finalized")));
+        status()["err"].is_null().then_some(())
+    }
+
+    fn wait_for(&self, condition: impl Fn() -> bool) {
+        let started = Instant::now();
+        while !condition() {
+            assert!(started.elapsed() < Duration::from_secs(30), "timed out waiting for the validator");
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+
+    /// Runs the CLI signing with `keypair`, returning its `--json` output.
+    fn cli(&self, keypair: &Path, args: &[&str]) -> Value {
+        self.try_cli(keypair, args)
+            .unwrap_or_else(|stderr| panic!("open-venture {args:?} failed:\n{stderr}"))
+    }
+
+    /// Like [`Validator::cli`], returning the CLI's error output when it fails.
+    fn try_cli(&self, keypair: &Path, args: &[&str]) -> Result<Value, String> {
+        let output = Command::new(env!("CARGO_BIN_EXE_open-venture"))
+            .args(["--url", &self.url, "--json", "--keypair"])
+            .arg(keypair)
+            .args(args)
+            .output()
+            .unwrap();
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+        }
+        Ok(serde_json::from_slice(&output.stdout).unwrap())
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.scratch);
+    }
+}
+
+fn wallet(validator: &Validator, name: &str) -> (Keypair, PathBuf) {
+    let keypair = Keypair::new();
+    let path = validator.scratch.join(format!("{name}.json"));
+    write_keypair_file(&keypair, &path).unwrap();
+    validator.airdrop(&keypair.pubkey(), 10 * SOL);
+    (keypair, path)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn address(value: &Value) -> String {
+    value.as_str().unwrap().to_string()
+}
+
+#[test]
+#[ignore = "needs solana-test-validator and the built program"]
+fn runs_a_round_end_to_end() {
+    let admin = Keypair::new();
+    let validator = Validator::start(&admin.pubkey());
+    validator.airdrop(&admin.pubkey(), SOL);
+    validator.send(&admin, instructions::initialize_config(&admin.pubkey(), 0, 0, 0, 0, 0, 5)).unwrap();
+    let (founder, founder_keypair) = wallet(&validator, "founder");
+    let (investor, investor_keypair) = wallet(&validator, "investor");
+
+    let company = validator.cli(&founder_keypair, &["company", "create", "--name", "Acme"]);
+    let company_profile = address(&company["company_profile"]);
+    let company_profile_key: Pubkey = company_profile.parse().unwrap();
+    let treasury: Pubkey = address(&company["company_treasury"]).parse().unwrap();
+    // the CLI has no command for raising the company's round limit
+    let round_limit = instructions::set_active_round_limit(&founder.pubkey(), &company_profile_key, 2);
+    validator.send(&founder, round_limit).unwrap();
+
+    let funding_end = (now() + FUNDING_WINDOW).to_string();
+    let repayment_deadline = (now() + 3600).to_string();
+    let create_round = |label: &str| {
+        let round = validator.cli(&founder_keypair, &[
+            "round", "create",
+            "--company", &company_profile,
+            "--label", label,
+            "--target-amount", &(2 * SOL).to_string(),
+            "--interest-rate", "10",
+            "--repayment-deadline", &repayment_deadline,
+            "--funding-end", &funding_end,
+        ]);
+        address(&round["funding_round"])
+    };
+    let seed = create_round("seed");
+    let bridge = create_round("bridge");
+
+    // the first round takes portfolio slot 0 and keeps it for the repeat deposit, the second
+    // round takes slot 1
+    let sol = SOL.to_string();
+    for round in [&seed, &bridge, &seed] {
+        validator.cli(&investor_keypair, &["round", "fund", "--round", round, "--amount", &sol]);
+    }
+    let portfolio = validator.cli(&investor_keypair, &["portfolio"]);
+    let positions = portfolio["positions"].as_array().unwrap();
+    assert_eq!(portfolio["investor"], json!(investor.pubkey().to_string()));
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[0]["funding_round"], json!(seed));
+    assert_eq!(positions[0]["amount"], json!(2 * SOL));
+    assert_eq!(positions[1]["funding_round"], json!(bridge));
+    assert_eq!(positions[1]["amount"], json!(SOL));
+
+    // anyone may finalize the rounds once their funding window has closed; the seed round
+    // reached its target, the bridge round fell short and refunds its investor
+    validator.wait_for(|| validator.try_cli(&investor_keypair, &["round", "finalize", "--round", &seed]).is_ok());
+    let finalized = validator.cli(&investor_keypair, &["round", "finalize", "--round", &bridge]);
+    assert_eq!(finalized["status"], json!("Failed"));
+    let refund = validator.cli(&investor_keypair, &["round", "claim-refund", "--round", &bridge]);
+    assert_eq!(refund["amount"], json!(SOL));
+
+    let withdrawn = 2 * SOL;
+    validator.cli(&founder_keypair, &["round", "withdraw", "--round", &seed, "--amount", &withdrawn.to_string()]);
+    let round = validator.cli(&founder_keypair, &["round", "show", "--round", &seed]);
+    assert_eq!(round["status"], json!("Funded"));
+    assert_eq!(round["total_withdrawn"], json!(withdrawn));
+    assert_eq!(round["treasury_outstanding"], json!(withdrawn));
+
+    let due = round["total_repayment_due"].as_u64().unwrap();
+    validator.airdrop(&treasury, due - withdrawn);
+    validator.cli(&founder_keypair, &["round", "repay", "--round", &seed, "--amount", &due.to_string()]);
+    let round = validator.cli(&founder_keypair, &["round", "show", "--round", &seed]);
+    assert_eq!(round["status"], json!("Repaid"));
+    assert_eq!(round["total_repaid"], json!(due));
+    assert_eq!(round["treasury_outstanding"], json!(0));
+
+    let claimed = validator.cli(&investor_keypair, &["round", "claim-repayment", "--round", &seed]);
+    assert_eq!(claimed["amount"], json!(due));
+    let portfolio = validator.cli(&investor_keypair, &["portfolio"]);
+    assert_eq!(portfolio["positions"][0]["repayment_claimed"], json!(due));
+    assert_eq!(portfolio["positions"][1]["refunded"], json!(true));
+}
@@ -0,0 +1,21 @@
+//! Decoding of the program's accounts from raw account data.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use open_venture::state::*;
+
+/// Decodes an account fetched over RPC, checking its discriminator against `T`.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Portfolio slot an investor's deposit into a round lists the position at, as the program
+/// derives it: the position's own slot once it is opened, otherwise the next free slot of the
+/// investor's portfolio. Either account is `None` while it does not exist.
+pub fn portfolio_slot(position: Option<&InvestorPosition>, portfolio: Option<&InvestorPortfolio>) -> u64 {
+    match (position, portfolio) {
+        (Some(position), Some(portfolio)) => position.portfolio_slot(portfolio),
+        (Some(position), None) if position.is_opened() => position.portfolio_index,
+        (_, portfolio) => portfolio.map_or(0, |portfolio| portfolio.position_count),
+    }
+}
@@ -0,0 +1,702 @@
+//! One builder per program entrypoint.
+//!
+//! Rounds are identified by their company profile and [`RoundSeed`], from which the round and
+//! its vaults are derived. Arguments the program reads from on-chain state to pick a PDA, such as
+//! an owner's company count, are passed in by the caller.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use open_venture::state::{CompanyCategory, VaultKind};
+use open_venture::{accounts, instruction};
+
+use crate::pda::*;
+
+pub fn initialize_config(
+    admin: &Pubkey,
+    max_auto_extension: u64,
+    max_auto_extensions: u8,
+    liquidation_grace_period: u64,
+    keeper_reward_bps: u16,
+    unproven_round_cap: u64,
+    max_active_rounds: u8,
+) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin: *admin,
+            config: find_config().0,
+            program: open_venture::ID,
+            program_data: find_program_data().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            max_auto_extension,
+            max_auto_extensions,
+            liquidation_grace_period,
+            keeper_reward_bps,
+            unproven_round_cap,
+            max_active_rounds,
+        },
+    )
+}
+
+pub fn update_config(
+    admin: &Pubkey,
+    max_auto_extension: u64,
+    max_auto_extensions: u8,
+    liquidation_grace_period: u64,
+    keeper_reward_bps: u16,
+    unproven_round_cap: u64,
+    max_active_rounds: u8,
+) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            admin: *admin,
+            config: find_config().0,
+        },
+        instruction::UpdateConfig {
+            max_auto_extension,
+            max_auto_extensions,
+            liquidation_grace_period,
+            keeper_reward_bps,
+            unproven_round_cap,
+            max_active_rounds,
+        },
+    )
+}
+
+/// `company_count` is the owner's `OwnerRegistry::company_count`, 0 when the registry does not exist yet.
+pub fn create_company_profile(owner: &Pubkey, name: &str, bio: &str, company_count: u64) -> Instruction {
+    let company_profile = find_company_profile(owner, name).0;
+    build(
+        accounts::CreateCompanyProfile {
+            owner: *owner,
+            company_profile,
+            company_treasury: find_company_treasury(owner, &company_profile).0,
+            company_reputation: find_company_reputation(&company_profile).0,
+            owner_registry: find_owner_registry(owner).0,
+            company_index: find_company_index(owner, company_count).0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateCompanyProfile {
+            name: name.to_string(),
+            bio: bio.to_string(),
+        },
+    )
+}
+
+/// `company_count` is the owner's `OwnerRegistry::company_count`, 0 when the registry does not exist yet.
+pub fn register_company_profile(owner: &Pubkey, company_profile: &Pubkey, company_count: u64) -> Instruction {
+    build(
+        accounts::RegisterCompanyProfile {
+            owner: *owner,
+            company_profile: *company_profile,
+            owner_registry: find_owner_registry(owner).0,
+            company_index: find_company_index(owner, company_count).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterCompanyProfile {},
+    )
+}
+
+/// `company_index` is the entry listing the profile in the owner's registry and `company_count`
+/// the registry's current count; the last entry is moved into the freed slot. `verifier` is the
+/// verifier that paid for the company's verification, refunded when it is closed along with the
+/// profile, and is omitted for unverified companies.
+pub fn close_company_profile(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    company_index: u64,
+    company_count: u64,
+    verifier: Option<Pubkey>,
+) -> Instruction {
+    let last_index = company_count.saturating_sub(1);
+    build(
+        accounts::CloseCompanyProfile {
+            owner: *owner,
+            company_profile: *company_profile,
+            company_treasury: find_company_treasury(owner, company_profile).0,
+            company_reputation: find_company_reputation(company_profile).0,
+            owner_registry: find_owner_registry(owner).0,
+            company_index: find_company_index(owner, company_index).0,
+            last_company_index: (company_index != last_index).then(|| find_company_index(owner, last_index).0),
+            company_verification: find_company_verification(company_profile).0,
+            verifier,
+        },
+        instruction::CloseCompanyProfile {},
+    )
+}
+
+pub fn update_company_metadata(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    metadata_uri: &str,
+    category: Option<CompanyCategory>,
+    country_code: Option<[u8; 2]>,
+    document_hash: Option<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::UpdateCompanyMetadata {
+            owner: *owner,
+            company_profile: *company_profile,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateCompanyMetadata {
+            metadata_uri: metadata_uri.to_string(),
+            category,
+            country_code,
+            document_hash,
+        },
+    )
+}
+
+pub fn migrate_company_profile(authority: &Pubkey, company_profile: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateCompanyProfile {
+            authority: *authority,
+            config: find_config().0,
+            company_profile: *company_profile,
+            company_reputation: find_company_reputation(company_profile).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateCompanyProfile {},
+    )
+}
+
+pub fn migrate_funding_round(authority: &Pubkey, company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::MigrateFundingRound {
+            authority: *authority,
+            config: find_config().0,
+            company_profile: *company_profile,
+            funding_round,
+            vault: find_funding_round_vault(company_profile, round).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateFundingRound {},
+    )
+}
+
+pub fn set_active_round_limit(authority: &Pubkey, company_profile: &Pubkey, max_active_rounds: u8) -> Instruction {
+    build(
+        accounts::SetActiveRoundLimit {
+            authority: *authority,
+            config: find_config().0,
+            company_profile: *company_profile,
+        },
+        instruction::SetActiveRoundLimit { max_active_rounds },
+    )
+}
+
+/// `round_index` is the profile's `CompanyProfile::round_count`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_funding_round(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round_index: u64,
+    label: &str,
+    target_amount: u64,
+    interest_rate: u64,
+    repayment_deadline: u64,
+    funding_start: u64,
+    funding_end: u64,
+) -> Instruction {
+    let round = RoundSeed::Index(round_index);
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::CreateFundingRound {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            vault: find_funding_round_vault(company_profile, round).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            company_reputation: find_company_reputation(company_profile).0,
+            config: find_config().0,
+            system_program: system_program::ID,
+        },
+        instruction::CreateFundingRound {
+            label: label.to_string(),
+            target_amount,
+            interest_rate,
+            repayment_deadline,
+            funding_start,
+            funding_end,
+        },
+    )
+}
+
+pub fn set_contribution_limits(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    min_contribution: u64,
+    max_contribution: u64,
+) -> Instruction {
+    build(
+        accounts::SetContributionLimits {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round: find_funding_round(company_profile, round).0,
+        },
+        instruction::SetContributionLimits {
+            min_contribution,
+            max_contribution,
+        },
+    )
+}
+
+pub fn set_investor_gate(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    allowlist_root: Option<[u8; 32]>,
+    kyc_verifier: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetInvestorGate {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round: find_funding_round(company_profile, round).0,
+        },
+        instruction::SetInvestorGate {
+            allowlist_root,
+            kyc_verifier,
+        },
+    )
+}
+
+pub fn issue_attestation(verifier: &Pubkey, investor: &Pubkey, expires_at: i64) -> Instruction {
+    build(
+        accounts::IssueAttestation {
+            verifier: *verifier,
+            investor: *investor,
+            attestation: find_investor_attestation(verifier, investor).0,
+            system_program: system_program::ID,
+        },
+        instruction::IssueAttestation { expires_at },
+    )
+}
+
+pub fn revoke_attestation(verifier: &Pubkey, investor: &Pubkey) -> Instruction {
+    build(
+        accounts::RevokeAttestation {
+            verifier: *verifier,
+            attestation: find_investor_attestation(verifier, investor).0,
+        },
+        instruction::RevokeAttestation {},
+    )
+}
+
+pub fn add_company_verifier(admin: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AddCompanyVerifier {
+            admin: *admin,
+            config: find_config().0,
+            authority: *authority,
+            company_verifier: find_company_verifier(authority).0,
+            system_program: system_program::ID,
+        },
+        instruction::AddCompanyVerifier {},
+    )
+}
+
+pub fn remove_company_verifier(admin: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveCompanyVerifier {
+            admin: *admin,
+            config: find_config().0,
+            company_verifier: find_company_verifier(authority).0,
+        },
+        instruction::RemoveCompanyVerifier {},
+    )
+}
+
+/// `delegated` is false when the config admin verifies the company itself.
+pub fn verify_company(
+    verifier: &Pubkey,
+    company_profile: &Pubkey,
+    delegated: bool,
+    legal_name: &str,
+    jurisdiction: &str,
+    registration_hash: [u8; 32],
+) -> Instruction {
+    build(
+        accounts::VerifyCompany {
+            verifier: *verifier,
+            config: find_config().0,
+            company_verifier: delegated.then(|| find_company_verifier(verifier).0),
+            company_profile: *company_profile,
+            company_verification: find_company_verification(company_profile).0,
+            system_program: system_program::ID,
+        },
+        instruction::VerifyCompany {
+            legal_name: legal_name.to_string(),
+            jurisdiction: jurisdiction.to_string(),
+            registration_hash,
+        },
+    )
+}
+
+/// `delegated` is false when the config admin revokes the verification itself.
+pub fn revoke_company_verification(verifier: &Pubkey, company_profile: &Pubkey, delegated: bool) -> Instruction {
+    build(
+        accounts::RevokeCompanyVerification {
+            verifier: *verifier,
+            config: find_config().0,
+            company_verifier: delegated.then(|| find_company_verifier(verifier).0),
+            company_verification: find_company_verification(company_profile).0,
+        },
+        instruction::RevokeCompanyVerification {},
+    )
+}
+
+/// `portfolio_slot` is the position's `portfolio_index` once the investor has deposited into the
+/// round, otherwise the investor's `InvestorPortfolio::position_count` (0 without a portfolio),
+/// see [`crate::accounts::portfolio_slot`].
+/// `kyc_verifier` is the round's verifier, whose attestation of the investor is passed along.
+pub fn fund_company(
+    investor: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    portfolio_slot: u64,
+    amount: u64,
+    allowlist_proof: Vec<[u8; 32]>,
+    kyc_verifier: Option<Pubkey>,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::FundCompany {
+            investor: *investor,
+            company_profile: *company_profile,
+            funding_round,
+            investor_position: find_investor_position(&funding_round, investor).0,
+            investor_portfolio: find_investor_portfolio(investor).0,
+            portfolio_entry: find_portfolio_entry(investor, portfolio_slot).0,
+            attestation: kyc_verifier.map(|verifier| find_investor_attestation(&verifier, investor).0),
+            vault: find_funding_round_vault(company_profile, round).0,
+            system_program: system_program::ID,
+        },
+        instruction::FundCompany {
+            amount,
+            allowlist_proof,
+        },
+    )
+}
+
+pub fn finalize_round(company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    build(
+        accounts::FinalizeRound {
+            company_profile: *company_profile,
+            funding_round: find_funding_round(company_profile, round).0,
+        },
+        instruction::FinalizeRound {},
+    )
+}
+
+/// `amendment_index` is the round's current amendment count, the index the proposal is stored
+/// at; the round's latest amendment before it is passed so a pending one can be superseded.
+pub fn amend_funding_round(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    amendment_index: u32,
+    target_amount: u64,
+    interest_rate: u64,
+    repayment_deadline: u64,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::AmendFundingRound {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            round_amendments: find_round_amendments(&funding_round).0,
+            amendment: find_amendment(&funding_round, amendment_index).0,
+            latest_amendment: amendment_index.checked_sub(1).map(|index| find_amendment(&funding_round, index).0),
+            company_reputation: find_company_reputation(company_profile).0,
+            config: find_config().0,
+            system_program: system_program::ID,
+        },
+        instruction::AmendFundingRound {
+            target_amount,
+            interest_rate,
+            repayment_deadline,
+        },
+    )
+}
+
+pub fn approve_amendment(
+    investor: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    amendment_index: u32,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::ApproveAmendment {
+            investor: *investor,
+            funding_round,
+            amendment: find_amendment(&funding_round, amendment_index).0,
+            investor_position: find_investor_position(&funding_round, investor).0,
+            amendment_approval: find_amendment_approval(&funding_round, amendment_index, investor).0,
+            system_program: system_program::ID,
+        },
+        instruction::ApproveAmendment { amendment_index },
+    )
+}
+
+/// `amendment_index` is the round's current amendment count, the index the request is stored at.
+pub fn request_extension(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    amendment_index: u32,
+    new_repayment_deadline: u64,
+    extension_fee: u64,
+    rate_bump: u64,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::RequestExtension {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            round_amendments: find_round_amendments(&funding_round).0,
+            amendment: find_amendment(&funding_round, amendment_index).0,
+            latest_amendment: amendment_index.checked_sub(1).map(|index| find_amendment(&funding_round, index).0),
+            config: find_config().0,
+            system_program: system_program::ID,
+        },
+        instruction::RequestExtension {
+            new_repayment_deadline,
+            extension_fee,
+            rate_bump,
+        },
+    )
+}
+
+pub fn cancel_funding_round(owner: &Pubkey, company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    build(
+        accounts::CancelFundingRound {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round: find_funding_round(company_profile, round).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+        },
+        instruction::CancelFundingRound {},
+    )
+}
+
+pub fn claim_refund(investor: &Pubkey, company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::ClaimRefund {
+            investor: *investor,
+            funding_round,
+            investor_position: find_investor_position(&funding_round, investor).0,
+            vault: find_funding_round_vault(company_profile, round).0,
+        },
+        instruction::ClaimRefund {},
+    )
+}
+
+pub fn deposit_collateral(owner: &Pubkey, company_profile: &Pubkey, round: RoundSeed, amount: u64) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::DepositCollateral {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            system_program: system_program::ID,
+        },
+        instruction::DepositCollateral { amount },
+    )
+}
+
+pub fn deposit_token_collateral(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    collateral_mint: &Pubkey,
+    owner_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::DepositTokenCollateral {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            collateral_mint: *collateral_mint,
+            owner_token_account: *owner_token_account,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DepositTokenCollateral { amount },
+    )
+}
+
+/// `owner_token_account` receives token collateral and is omitted for SOL collateral.
+pub fn release_collateral(
+    owner: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    owner_token_account: Option<Pubkey>,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::ReleaseCollateral {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            owner_token_account,
+            token_program: owner_token_account.map(|_| token::ID),
+        },
+        instruction::ReleaseCollateral {},
+    )
+}
+
+/// `investor_token_account` receives token collateral and is omitted for SOL collateral.
+pub fn claim_collateral(
+    investor: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    investor_token_account: Option<Pubkey>,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::ClaimCollateral {
+            investor: *investor,
+            funding_round,
+            investor_position: find_investor_position(&funding_round, investor).0,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            investor_token_account,
+            token_program: investor_token_account.map(|_| token::ID),
+        },
+        instruction::ClaimCollateral {},
+    )
+}
+
+/// `keeper_token_account` receives the keeper reward for token collateral and is omitted for SOL collateral.
+pub fn liquidate_collateral(
+    keeper: &Pubkey,
+    company_profile: &Pubkey,
+    round: RoundSeed,
+    keeper_token_account: Option<Pubkey>,
+) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::LiquidateCollateral {
+            keeper: *keeper,
+            company_profile: *company_profile,
+            funding_round,
+            collateral_vault: find_collateral_vault(&funding_round).0,
+            vault: find_funding_round_vault(company_profile, round).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            company_reputation: find_company_reputation(company_profile).0,
+            config: find_config().0,
+            keeper_token_account,
+            token_program: keeper_token_account.map(|_| token::ID),
+        },
+        instruction::LiquidateCollateral {},
+    )
+}
+
+pub fn mark_round_defaulted(company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::MarkRoundDefaulted {
+            company_profile: *company_profile,
+            funding_round,
+            vault: find_funding_round_vault(company_profile, round).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            company_reputation: find_company_reputation(company_profile).0,
+            config: find_config().0,
+        },
+        instruction::MarkRoundDefaulted {},
+    )
+}
+
+pub fn claim_repayment(investor: &Pubkey, company_profile: &Pubkey, round: RoundSeed) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::ClaimRepayment {
+            investor: *investor,
+            funding_round,
+            investor_position: find_investor_position(&funding_round, investor).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+        },
+        instruction::ClaimRepayment {},
+    )
+}
+
+pub fn withdraw_funds(owner: &Pubkey, company_profile: &Pubkey, round: RoundSeed, amount: u64) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::WithdrawFunds {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            vault: find_funding_round_vault(company_profile, round).0,
+            company_treasury: find_company_treasury(owner, company_profile).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawFunds { amount },
+    )
+}
+
+pub fn repay_funding_round(owner: &Pubkey, company_profile: &Pubkey, round: RoundSeed, amount: u64) -> Instruction {
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::RepayFundingRound {
+            owner: *owner,
+            company_profile: *company_profile,
+            funding_round,
+            company_treasury: find_company_treasury(owner, company_profile).0,
+            repayment_vault: find_repayment_vault(company_profile, round).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+            company_reputation: find_company_reputation(company_profile).0,
+        },
+        instruction::RepayFundingRound { amount },
+    )
+}
+
+/// `owner` is the company profile's owner, whose treasury receives the surplus.
+pub fn sweep_vault(owner: &Pubkey, company_profile: &Pubkey, round: RoundSeed, kind: VaultKind) -> Instruction {
+    let vault = match kind {
+        VaultKind::Funding => find_funding_round_vault(company_profile, round),
+        VaultKind::Repayment => find_repayment_vault(company_profile, round),
+    };
+    let funding_round = find_funding_round(company_profile, round).0;
+    build(
+        accounts::SweepVault {
+            company_profile: *company_profile,
+            funding_round,
+            vault: vault.0,
+            company_treasury: find_company_treasury(owner, company_profile).0,
+            treasury_ledger: find_treasury_ledger(&funding_round).0,
+        },
+        instruction::SweepVault {},
+    )
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: open_venture::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
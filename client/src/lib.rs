@@ -0,0 +1,12 @@
+//! Off-chain helpers for the open_venture program: PDA finders, one instruction builder per
+//! entrypoint and decoding of the program's accounts.
+//!
+//! Builders derive every PDA an instruction touches, so callers only pass the wallets involved
+//! and the instruction's arguments.
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+
+pub use open_venture::ID;
+pub use pda::RoundSeed;
@@ -0,0 +1,151 @@
+//! Finders for the program's PDAs, mirroring the seeds its account constraints check.
+
+use anchor_lang::prelude::{ProgramData, Pubkey};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::Owner;
+use open_venture::constants::*;
+use open_venture::state::FundingRound;
+
+/// Tells a company's rounds apart in the seeds of the round and its vaults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundSeed<'a> {
+    /// Position of the round among the company's rounds.
+    Index(u64),
+    /// Id of a round created before rounds were numbered, seeded by `hash(id)`.
+    Legacy(&'a str),
+}
+
+impl RoundSeed<'_> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            RoundSeed::Index(index) => index.to_le_bytes().to_vec(),
+            RoundSeed::Legacy(id) => hash(id.as_bytes()).to_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<u64> for RoundSeed<'_> {
+    fn from(index: u64) -> Self {
+        RoundSeed::Index(index)
+    }
+}
+
+impl<'a> From<&'a FundingRound> for RoundSeed<'a> {
+    fn from(round: &'a FundingRound) -> Self {
+        if round.indexed {
+            RoundSeed::Index(round.index)
+        } else {
+            RoundSeed::Legacy(&round.id)
+        }
+    }
+}
+
+pub fn find_config() -> (Pubkey, u8) {
+    find(&[PROGRAM_CONFIG_SEED])
+}
+
+/// Program data account of the upgradeable program, whose upgrade authority may initialize the config.
+pub fn find_program_data() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[open_venture::ID.as_ref()], &ProgramData::owner())
+}
+
+pub fn find_company_profile(owner: &Pubkey, name: &str) -> (Pubkey, u8) {
+    find(&[
+        COMPANY_PROFILE_SEED,
+        owner.as_ref(),
+        hash(name.as_bytes()).as_ref(),
+    ])
+}
+
+pub fn find_company_treasury(owner: &Pubkey, company_profile: &Pubkey) -> (Pubkey, u8) {
+    find(&[
+        COMPANY_TREASURY_SEED,
+        owner.as_ref(),
+        company_profile.as_ref(),
+    ])
+}
+
+pub fn find_company_reputation(company_profile: &Pubkey) -> (Pubkey, u8) {
+    find(&[COMPANY_REPUTATION_SEED, company_profile.as_ref()])
+}
+
+pub fn find_owner_registry(owner: &Pubkey) -> (Pubkey, u8) {
+    find(&[OWNER_REGISTRY_SEED, owner.as_ref()])
+}
+
+pub fn find_company_index(owner: &Pubkey, index: u64) -> (Pubkey, u8) {
+    find(&[COMPANY_INDEX_SEED, owner.as_ref(), &index.to_le_bytes()])
+}
+
+pub fn find_company_verifier(authority: &Pubkey) -> (Pubkey, u8) {
+    find(&[COMPANY_VERIFIER_SEED, authority.as_ref()])
+}
+
+pub fn find_company_verification(company_profile: &Pubkey) -> (Pubkey, u8) {
+    find(&[COMPANY_VERIFICATION_SEED, company_profile.as_ref()])
+}
+
+pub fn find_funding_round(company_profile: &Pubkey, round: RoundSeed) -> (Pubkey, u8) {
+    find(&[FUNDING_ROUND_SEED, company_profile.as_ref(), &round.to_bytes()])
+}
+
+pub fn find_funding_round_vault(company_profile: &Pubkey, round: RoundSeed) -> (Pubkey, u8) {
+    find(&[FUNDING_ROUND_VAULT_SEED, company_profile.as_ref(), &round.to_bytes()])
+}
+
+pub fn find_repayment_vault(company_profile: &Pubkey, round: RoundSeed) -> (Pubkey, u8) {
+    find(&[REPAYMENT_VAULT_SEED, company_profile.as_ref(), &round.to_bytes()])
+}
+
+pub fn find_treasury_ledger(funding_round: &Pubkey) -> (Pubkey, u8) {
+    find(&[TREASURY_LEDGER_SEED, funding_round.as_ref()])
+}
+
+pub fn find_collateral_vault(funding_round: &Pubkey) -> (Pubkey, u8) {
+    find(&[COLLATERAL_VAULT_SEED, funding_round.as_ref()])
+}
+
+pub fn find_round_amendments(funding_round: &Pubkey) -> (Pubkey, u8) {
+    find(&[ROUND_AMENDMENTS_SEED, funding_round.as_ref()])
+}
+
+pub fn find_amendment(funding_round: &Pubkey, index: u32) -> (Pubkey, u8) {
+    find(&[AMENDMENT_SEED, funding_round.as_ref(), &index.to_le_bytes()])
+}
+
+pub fn find_amendment_approval(funding_round: &Pubkey, index: u32, investor: &Pubkey) -> (Pubkey, u8) {
+    find(&[
+        AMENDMENT_APPROVAL_SEED,
+        funding_round.as_ref(),
+        &index.to_le_bytes(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn find_investor_position(funding_round: &Pubkey, investor: &Pubkey) -> (Pubkey, u8) {
+    find(&[
+        INVESTOR_POSITION_SEED,
+        funding_round.as_ref(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn find_investor_attestation(verifier: &Pubkey, investor: &Pubkey) -> (Pubkey, u8) {
+    find(&[
+        INVESTOR_ATTESTATION_SEED,
+        verifier.as_ref(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn find_investor_portfolio(investor: &Pubkey) -> (Pubkey, u8) {
+    find(&[INVESTOR_PORTFOLIO_SEED, investor.as_ref()])
+}
+
+pub fn find_portfolio_entry(investor: &Pubkey, index: u64) -> (Pubkey, u8) {
+    find(&[PORTFOLIO_ENTRY_SEED, investor.as_ref(), &index.to_le_bytes()])
+}
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &open_venture::ID)
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::Discriminator;
+use open_venture_client::{accounts, instructions, pda, RoundSeed};
+
+fn legacy_round(id: &str) -> accounts::FundingRound {
+    accounts::FundingRound {
+        id: id.to_string(),
+        company: Pubkey::new_unique(),
+        target_amount: 0,
+        interest_rate: 0,
+        repayment_deadline: 0,
+        is_active: true,
+        min_contribution: 0,
+        max_contribution: 0,
+        total_raised: 0,
+        allowlist_root: None,
+        kyc_verifier: None,
+        funding_start: 0,
+        funding_end: 0,
+        status: accounts::FundingRoundStatus::Open,
+        total_withdrawn: 0,
+        extension_fees: 0,
+        auto_extensions: 0,
+        collateral_mint: None,
+        collateral_amount: 0,
+        total_repaid: 0,
+        collateral_liquidated: false,
+        liquidation_proceeds: 0,
+        version: 0,
+        index: 0,
+        indexed: false,
+        bump: 0,
+        vault_bump: 0,
+        repayment_vault_bump: 0,
+        collateral_vault_bump: 0,
+        treasury_ledger_bump: 0,
+        released_capital: 0,
+        reserved: [0; open_venture::FUNDING_ROUND_RESERVED_SPACE],
+    }
+}
+
+#[test]
+fn round_seed_matches_the_on_chain_seed() {
+    let mut round = legacy_round("seed-round");
+    assert_eq!(RoundSeed::from(&round), RoundSeed::Legacy("seed-round"));
+    assert_eq!(RoundSeed::from(&round).to_bytes(), round.round_seed());
+    assert_eq!(RoundSeed::from(&round).to_bytes(), hash(b"seed-round").to_bytes().to_vec());
+
+    round.indexed = true;
+    round.index = 3;
+    assert_eq!(RoundSeed::from(&round), RoundSeed::Index(3));
+    assert_eq!(RoundSeed::from(&round).to_bytes(), round.round_seed());
+}
+
+#[test]
+fn builders_agree_on_round_accounts() {
+    let owner = Pubkey::new_unique();
+    let investor = Pubkey::new_unique();
+    let company_profile = pda::find_company_profile(&owner, "Acme").0;
+    let round = RoundSeed::Index(2);
+    let funding_round = pda::find_funding_round(&company_profile, round).0;
+    let vault = pda::find_funding_round_vault(&company_profile, round).0;
+
+    let create = instructions::create_funding_round(&owner, &company_profile, 2, "", 1, 10, 3, 1, 2);
+    let fund = instructions::fund_company(&investor, &company_profile, round, 0, 1, vec![], None);
+
+    assert_eq!(create.program_id, open_venture_client::ID);
+    assert_eq!(create.accounts[2].pubkey, funding_round);
+    assert_eq!(create.accounts[3].pubkey, vault);
+    assert_eq!(create.accounts[5].pubkey, pda::find_treasury_ledger(&funding_round).0);
+    assert_eq!(fund.accounts[2].pubkey, funding_round);
+    assert_eq!(fund.accounts[7].pubkey, vault);
+    assert_eq!(
+        fund.accounts[3].pubkey,
+        pda::find_investor_position(&funding_round, &investor).0,
+    );
+    // an omitted optional account is passed as the program id
+    assert_eq!(fund.accounts[6].pubkey, open_venture_client::ID);
+}
+
+#[test]
+fn sweep_vault_targets_the_chosen_vault() {
+    let owner = Pubkey::new_unique();
+    let company_profile = pda::find_company_profile(&owner, "Acme").0;
+    let round = RoundSeed::Index(0);
+
+    let sweep = instructions::sweep_vault(&owner, &company_profile, round, accounts::VaultKind::Repayment);
+    assert_eq!(sweep.accounts[2].pubkey, pda::find_repayment_vault(&company_profile, round).0);
+    assert_eq!(
+        sweep.accounts[3].pubkey,
+        pda::find_company_treasury(&owner, &company_profile).0,
+    );
+}
+
+#[test]
+fn closing_the_last_listed_company_omits_the_last_index() {
+    let owner = Pubkey::new_unique();
+    let company_profile = pda::find_company_profile(&owner, "Acme").0;
+
+    let last = instructions::close_company_profile(&owner, &company_profile, 1, 2, None);
+    assert_eq!(last.accounts[5].pubkey, pda::find_company_index(&owner, 1).0);
+    assert_eq!(last.accounts[6].pubkey, open_venture_client::ID);
+
+    let first = instructions::close_company_profile(&owner, &company_profile, 0, 2, None);
+    assert_eq!(first.accounts[5].pubkey, pda::find_company_index(&owner, 0).0);
+    assert_eq!(first.accounts[6].pubkey, pda::find_company_index(&owner, 1).0);
+    assert_eq!(first.accounts[7].pubkey, pda::find_company_verification(&company_profile).0);
+}
+
+#[test]
+fn decode_checks_the_discriminator() {
+    let portfolio = accounts::InvestorPortfolio {
+        investor: Pubkey::new_unique(),
+        position_count: 4,
+    };
+    let mut data = Vec::new();
+    anchor_lang::AccountSerialize::try_serialize(&portfolio, &mut data).unwrap();
+    assert_eq!(&data[..8], accounts::InvestorPortfolio::DISCRIMINATOR);
+
+    let decoded: accounts::InvestorPortfolio = accounts::decode(&data).unwrap();
+    assert_eq!(decoded.investor, portfolio.investor);
+    assert_eq!(decoded.position_count, 4);
+    assert!(accounts::decode::<accounts::OwnerRegistry>(&data).is_err());
+}
+
+#[test]
+fn portfolio_slot_follows_the_program() {
+    let investor = Pubkey::new_unique();
+    let portfolio = accounts::InvestorPortfolio {
+        investor,
+        position_count: 3,
+    };
+    let position = accounts::InvestorPosition {
+        investor,
+        funding_round: Pubkey::new_unique(),
+        amount: 1,
+        refunded: false,
+        collateral_claimed: false,
+        repayment_claimed: 0,
+        portfolio_index: 1,
+    };
+    // a position that has not received a deposit yet is listed at the next free slot
+    let unopened = accounts::InvestorPosition {
+        investor: Pubkey::default(),
+        funding_round: Pubkey::default(),
+        amount: 0,
+        refunded: false,
+        collateral_claimed: false,
+        repayment_claimed: 0,
+        portfolio_index: 0,
+    };
+
+    assert_eq!(accounts::portfolio_slot(None, None), 0);
+    assert_eq!(accounts::portfolio_slot(None, Some(&portfolio)), 3);
+    assert_eq!(accounts::portfolio_slot(Some(&position), Some(&portfolio)), 1);
+    assert_eq!(accounts::portfolio_slot(Some(&unopened), Some(&portfolio)), 3);
+    assert_eq!(accounts::portfolio_slot(Some(&unopened), None), 0);
+}
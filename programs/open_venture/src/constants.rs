@@ -3,3 +3,36 @@ use anchor_lang::prelude::*;
 #[constant]
 pub const SEED: &str = "anchor";
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
+pub const BASIS_POINTS: u64 = 10_000;
+pub const SECONDS_PER_DAY: u64 = 86_400;
+pub const COMPANY_PROFILE_VERSION: u8 = 3;
+pub const FUNDING_ROUND_VERSION: u8 = 4;
+// zeroed bytes kept at the end of versioned accounts; new fields are carved out of them so the
+// account size, and therefore existing accounts, stay valid
+pub const COMPANY_PROFILE_RESERVED_SPACE: usize = 43;
+pub const FUNDING_ROUND_RESERVED_SPACE: usize = 42;
+// size of a funding round written by the first program version, which only kept the round's
+// terms: `id`, `company`, `target_amount`, `interest_rate`, `repayment_deadline` and `is_active`
+pub const LEGACY_FUNDING_ROUND_SPACE: usize = 4 + 36 + 32 + 8 + 8 + 8 + 1;
+
+// PDA seed prefixes, the first seed of every account the program derives
+pub const PROGRAM_CONFIG_SEED: &[u8] = b"program_config";
+pub const COMPANY_PROFILE_SEED: &[u8] = b"company_profile";
+pub const COMPANY_TREASURY_SEED: &[u8] = b"company_treasury";
+pub const TREASURY_LEDGER_SEED: &[u8] = b"treasury_ledger";
+pub const COMPANY_REPUTATION_SEED: &[u8] = b"company_reputation";
+pub const OWNER_REGISTRY_SEED: &[u8] = b"owner_registry";
+pub const COMPANY_INDEX_SEED: &[u8] = b"company_index";
+pub const COMPANY_VERIFIER_SEED: &[u8] = b"company_verifier";
+pub const COMPANY_VERIFICATION_SEED: &[u8] = b"company_verification";
+pub const FUNDING_ROUND_SEED: &[u8] = b"funding_round";
+pub const FUNDING_ROUND_VAULT_SEED: &[u8] = b"funding_round_vault";
+pub const REPAYMENT_VAULT_SEED: &[u8] = b"funding_round_repayment";
+pub const COLLATERAL_VAULT_SEED: &[u8] = b"collateral_vault";
+pub const ROUND_AMENDMENTS_SEED: &[u8] = b"round_amendments";
+pub const AMENDMENT_SEED: &[u8] = b"amendment";
+pub const AMENDMENT_APPROVAL_SEED: &[u8] = b"amendment_approval";
+pub const INVESTOR_POSITION_SEED: &[u8] = b"investor_position";
+pub const INVESTOR_ATTESTATION_SEED: &[u8] = b"investor_attestation";
+pub const INVESTOR_PORTFOLIO_SEED: &[u8] = b"investor_portfolio";
+pub const PORTFOLIO_ENTRY_SEED: &[u8] = b"portfolio_entry";
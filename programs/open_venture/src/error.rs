@@ -18,10 +18,10 @@ pub enum ErrorCode {
     InterestRateMustBeGreaterThanZero,
     #[msg("Funding round id is required")]
     FundingRoundIdRequired,
-    #[msg("Funding round id cannot be longer than 36 characters")]
+    #[msg("Funding round label cannot be longer than 36 characters")]
     FundingRoundIdTooLong,
-    #[msg("An active funding round already exists for this company")]
-    ActiveFundingRoundExists,
+    #[msg("Company already has as many active funding rounds as it is allowed")]
+    ActiveRoundLimitReached,
     #[msg("Funding round is not active")]
     FundingRoundNotActive,
     #[msg("Funding round company does not match the provided company profile")]
@@ -40,4 +40,126 @@ pub enum ErrorCode {
     RepaymentAmountExceedsTargetAmount,
     #[msg("Math overflow")]
     MathOverflow,
+    #[msg("Deposit amount is below the funding round minimum contribution")]
+    ContributionBelowMinimum,
+    #[msg("Deposit would exceed the funding round maximum contribution per investor")]
+    ContributionExceedsMaximum,
+    #[msg("Maximum contribution must be greater than or equal to minimum contribution")]
+    InvalidContributionLimits,
+    #[msg("Funding round already has deposits")]
+    FundingRoundHasDeposits,
+    #[msg("Investor is not on the funding round allowlist and holds no valid attestation")]
+    InvestorNotEligible,
+    #[msg("Attestation expiry must be in the future")]
+    AttestationExpiryInThePast,
+    #[msg("Funding window start must be before its end, and it must close by the repayment deadline")]
+    InvalidFundingWindow,
+    #[msg("Funding window end must be greater than current timestamp")]
+    FundingWindowEndInThePast,
+    #[msg("Funding window has not opened yet")]
+    FundingWindowNotOpen,
+    #[msg("Funding window has closed")]
+    FundingWindowClosed,
+    #[msg("Funding window is still open")]
+    FundingWindowStillOpen,
+    #[msg("Funding round is not refundable")]
+    FundingRoundNotRefundable,
+    #[msg("Investor has nothing to refund")]
+    NothingToRefund,
+    #[msg("Funding round cannot be cancelled after funds have been withdrawn")]
+    FundsAlreadyWithdrawn,
+    #[msg("Repayment deadline must not be before the end of the funding window")]
+    RepaymentDeadlineBeforeFundingEnd,
+    #[msg("Amendment is not pending")]
+    AmendmentNotPending,
+    #[msg("Only investors with capital in the funding round can approve amendments")]
+    NotAnInvestor,
+    #[msg("Only the program upgrade authority can initialize the program config")]
+    UnauthorizedConfigInitialization,
+    #[msg("Only the program admin can perform this action")]
+    UnauthorizedAdmin,
+    #[msg("Extension must move the repayment deadline later")]
+    ExtensionMustExtendDeadline,
+    #[msg("Collateral amount must be greater than zero")]
+    CollateralAmountMustBeGreaterThanZero,
+    #[msg("Funding round collateral is already held in a different asset")]
+    CollateralKindMismatch,
+    #[msg("Funding round has no collateral")]
+    NoCollateral,
+    #[msg("Collateral can only be released once the round is repaid, failed or cancelled")]
+    CollateralNotReleasable,
+    #[msg("Funding round is not in default")]
+    FundingRoundNotInDefault,
+    #[msg("Investor has already claimed collateral")]
+    CollateralAlreadyClaimed,
+    #[msg("Token accounts are required for SPL token collateral")]
+    MissingTokenAccounts,
+    #[msg("Token account mint does not match the collateral mint")]
+    CollateralMintMismatch,
+    #[msg("Keeper reward cannot exceed 10000 basis points")]
+    InvalidKeeperReward,
+    #[msg("Liquidation grace period has not elapsed")]
+    LiquidationGracePeriodActive,
+    #[msg("Funding round collateral has already been liquidated")]
+    CollateralAlreadyLiquidated,
+    #[msg("Funding round collateral has not been liquidated")]
+    CollateralNotLiquidated,
+    #[msg("SOL collateral is distributed through repayment claims")]
+    CollateralClaimedThroughRepayment,
+    #[msg("Investor has nothing to claim")]
+    NothingToClaim,
+    #[msg("Target amount exceeds what the company's repayment track record allows")]
+    TargetAmountExceedsReputationCap,
+    #[msg("Signer is neither the admin nor a delegated company verifier")]
+    UnauthorizedVerifier,
+    #[msg("Legal name is required")]
+    LegalNameRequired,
+    #[msg("Legal name cannot be longer than 64 characters")]
+    LegalNameTooLong,
+    #[msg("Jurisdiction is required")]
+    JurisdictionRequired,
+    #[msg("Jurisdiction cannot be longer than 32 characters")]
+    JurisdictionTooLong,
+    #[msg("Metadata URI cannot be longer than 200 characters")]
+    MetadataUriTooLong,
+    #[msg("Country code must be two uppercase ISO 3166-1 letters")]
+    InvalidCountryCode,
+    #[msg("Account is already on the current layout version")]
+    AccountAlreadyMigrated,
+    #[msg("Only the company owner or the admin can migrate this account")]
+    UnauthorizedMigration,
+    #[msg("Company profile is already listed in the owner registry")]
+    CompanyAlreadyRegistered,
+    #[msg("Company profile is not listed in the owner registry")]
+    CompanyNotRegistered,
+    #[msg("Company index entry does not point to the provided company profile")]
+    CompanyIndexMismatch,
+    #[msg("Companies that have opened funding rounds cannot be closed")]
+    CompanyHasFundingRounds,
+    #[msg("Account must be migrated to the current layout version first")]
+    AccountNotMigrated,
+    #[msg("Vault holds less than its tracked balance")]
+    VaultBalanceDeficit,
+    #[msg("Vault holds nothing beyond its tracked balance")]
+    NothingToSweep,
+    #[msg("Active round limit must be at least 1 and no more than the program allows")]
+    InvalidActiveRoundLimit,
+    #[msg("Only the company owner or the admin can change the active round limit")]
+    UnauthorizedRoundLimit,
+    #[msg("Repayment would spend capital withdrawn from the company's other funding rounds")]
+    TreasuryFundsCommittedToOtherRounds,
+    #[msg("Capital can only be withdrawn or repaid once the round has been funded")]
+    FundingRoundNotFunded,
+    #[msg("Extensions must be requested before the repayment deadline passes")]
+    RepaymentDeadlinePassed,
+    #[msg("Deposit would raise more than the round's target amount")]
+    ContributionExceedsTarget,
+    #[msg("Target amount must not be below the capital already raised")]
+    TargetBelowAmountRaised,
+    #[msg("Closing a verified company refunds the verifier that paid for its verification")]
+    VerifierRefundMismatch,
+    #[msg("Capital was raised since the amendment was proposed, it has to be proposed again")]
+    AmendmentVotingBaseChanged,
+    #[msg("The round's latest amendment must be passed so a pending proposal can be superseded")]
+    LatestAmendmentMismatch,
 }
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, PROGRAM_CONFIG_SEED, COMPANY_VERIFIER_SEED},
+    error::ErrorCode,
+    state::{CompanyVerifier, ProgramConfig},
+};
+
+pub(crate) fn handler(ctx: Context<AddCompanyVerifier>) -> Result<()> {
+    let company_verifier = &mut ctx.accounts.company_verifier;
+    company_verifier.authority = ctx.accounts.authority.key();
+    company_verifier.added_at = Clock::get()?.unix_timestamp;
+    company_verifier.bump = ctx.bumps.company_verifier;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddCompanyVerifier<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    /// CHECK: The delegated verifier only contributes its key to the verifier seeds
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR + CompanyVerifier::INIT_SPACE,
+        seeds = [COMPANY_VERIFIER_SEED, authority.key().as_ref()],
+        bump,
+    )]
+    pub company_verifier: Account<'info, CompanyVerifier>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        AMENDMENT_SEED, ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION,
+        ROUND_AMENDMENTS_SEED, COMPANY_REPUTATION_SEED, PROGRAM_CONFIG_SEED,
+    },
+    error::ErrorCode,
+    state::{
+        Amendment, AmendmentKind, AmendmentStatus, CompanyProfile, CompanyReputation,
+        FundingRound, FundingRoundStatus, ProgramConfig, RoundAmendments,
+    },
+    utils::validate_round_size,
+};
+
+pub(crate) fn handler(
+    ctx: Context<AmendFundingRound>,
+    target_amount: u64,
+    interest_rate: u64,
+    repayment_deadline: u64,
+) -> Result<()> {
+    require!(
+        matches!(
+            ctx.accounts.funding_round.status,
+            FundingRoundStatus::Open | FundingRoundStatus::Funded
+        ),
+        ErrorCode::FundingRoundNotActive
+    );
+    // amended terms follow the same rules as the ones the round was created with
+    require!(
+        target_amount > 0,
+        ErrorCode::TargetAmountMustBeGreaterThanZero
+    );
+    require!(
+        target_amount >= ctx.accounts.funding_round.total_raised,
+        ErrorCode::TargetBelowAmountRaised
+    );
+    validate_round_size(
+        &ctx.accounts.config,
+        &ctx.accounts.company_reputation,
+        target_amount,
+    )?;
+    require!(
+        interest_rate > 0,
+        ErrorCode::InterestRateMustBeGreaterThanZero
+    );
+    require!(
+        repayment_deadline > Clock::get()?.unix_timestamp as u64,
+        ErrorCode::RepaymentDeadlineInThePast
+    );
+    require!(
+        repayment_deadline >= ctx.accounts.funding_round.funding_end,
+        ErrorCode::RepaymentDeadlineBeforeFundingEnd
+    );
+
+    let funding_round = &mut ctx.accounts.funding_round;
+    let amendment = &mut ctx.accounts.amendment;
+    amendment.kind = AmendmentKind::Terms;
+    amendment.target_amount = target_amount;
+    amendment.interest_rate = interest_rate;
+    amendment.repayment_deadline = repayment_deadline;
+    amendment.extension_fee = 0;
+    amendment.proposed_at = Clock::get()?.unix_timestamp;
+    amendment.voting_base = funding_round.total_raised;
+    amendment.approved_amount = 0;
+    amendment.status = AmendmentStatus::Pending;
+
+    // nobody has committed capital under the current terms yet, so the owner can change them freely
+    if funding_round.total_raised == 0 {
+        funding_round.apply_amendment(amendment)?;
+        amendment.status = AmendmentStatus::Applied;
+    }
+
+    let round_amendments = &mut ctx.accounts.round_amendments;
+    round_amendments.funding_round = funding_round.key();
+    round_amendments.propose(amendment, ctx.accounts.latest_amendment.as_deref_mut())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AmendFundingRound<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + RoundAmendments::INIT_SPACE,
+        seeds = [ROUND_AMENDMENTS_SEED, funding_round.key().as_ref()],
+        bump,
+    )]
+    pub round_amendments: Account<'info, RoundAmendments>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + Amendment::INIT_SPACE,
+        seeds = [
+            AMENDMENT_SEED,
+            funding_round.key().as_ref(),
+            round_amendments.amendment_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub amendment: Account<'info, Amendment>,
+    /// The round's latest amendment, superseded by this one if investors have not approved it yet
+    #[account(mut)]
+    pub latest_amendment: Option<Account<'info, Amendment>>,
+    #[account(
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    pub system_program: Program<'info, System>,
+}
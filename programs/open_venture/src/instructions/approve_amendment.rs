@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, AMENDMENT_APPROVAL_SEED, AMENDMENT_SEED, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{
+        Amendment, AmendmentApproval, AmendmentStatus, FundingRound, FundingRoundStatus,
+        InvestorPosition,
+    },
+};
+
+pub(crate) fn handler(ctx: Context<ApproveAmendment>, amendment_index: u32) -> Result<()> {
+    require!(
+        matches!(
+            ctx.accounts.funding_round.status,
+            FundingRoundStatus::Open | FundingRoundStatus::Funded
+        ),
+        ErrorCode::FundingRoundNotActive
+    );
+    let investor_position = &ctx.accounts.investor_position;
+    require!(
+        investor_position.amount > 0 && !investor_position.refunded,
+        ErrorCode::NotAnInvestor
+    );
+
+    let amendment = &mut ctx.accounts.amendment;
+    require!(
+        amendment.status == AmendmentStatus::Pending,
+        ErrorCode::AmendmentNotPending
+    );
+    // the vote is held among the capital present at the proposal, later investors never saw it
+    require!(
+        ctx.accounts.funding_round.total_raised == amendment.voting_base,
+        ErrorCode::AmendmentVotingBaseChanged
+    );
+
+    // approvals are weighted by contributed capital, the approval account prevents double voting
+    amendment.approved_amount = amendment.approved_amount
+        .checked_add(investor_position.amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let approved_by_majority = (amendment.approved_amount as u128)
+        .checked_mul(2)
+        .ok_or(ErrorCode::MathOverflow)?
+        > amendment.voting_base as u128;
+    if approved_by_majority {
+        amendment.status = AmendmentStatus::Applied;
+        ctx.accounts.funding_round.apply_amendment(amendment)?;
+    }
+
+    let approval = &mut ctx.accounts.amendment_approval;
+    approval.investor = ctx.accounts.investor.key();
+    approval.funding_round = ctx.accounts.funding_round.key();
+    approval.amendment_index = amendment_index;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(amendment_index: u32)]
+pub struct ApproveAmendment<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        mut,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        mut,
+        has_one = funding_round,
+        seeds = [AMENDMENT_SEED, funding_round.key().as_ref(), amendment_index.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub amendment: Account<'info, Amendment>,
+    #[account(
+        has_one = investor,
+        has_one = funding_round,
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+    #[account(
+        init,
+        payer = investor,
+        space = ANCHOR_DISCRIMINATOR + AmendmentApproval::INIT_SPACE,
+        seeds = [
+            AMENDMENT_APPROVAL_SEED,
+            funding_round.key().as_ref(),
+            amendment_index.to_le_bytes().as_ref(),
+            investor.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub amendment_approval: Account<'info, AmendmentApproval>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION, REPAYMENT_VAULT_SEED},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, FundingRoundStatus, RoundVault},
+};
+
+pub(crate) fn handler(ctx: Context<CancelFundingRound>) -> Result<()> {
+    require!(
+        matches!(
+            ctx.accounts.funding_round.status,
+            FundingRoundStatus::Open | FundingRoundStatus::Funded
+        ),
+        ErrorCode::FundingRoundNotActive
+    );
+    // once capital has left the vault investors can no longer be made whole by a refund
+    require!(
+        ctx.accounts.funding_round.total_withdrawn == 0,
+        ErrorCode::FundsAlreadyWithdrawn
+    );
+
+    ctx.accounts.funding_round.status = FundingRoundStatus::Cancelled;
+    ctx.accounts.funding_round.is_active = false;
+    ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelFundingRound<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// Repayment vault, closed to the owner since a cancelled round is never repaid
+    #[account(
+        mut,
+        close = owner,
+        seeds = [REPAYMENT_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.repayment_vault_bump,
+    )]
+    pub repayment_vault: Account<'info, RoundVault>,
+}
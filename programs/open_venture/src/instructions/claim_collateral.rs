@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::{COLLATERAL_VAULT_SEED, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{FundingRound, InvestorPosition},
+    utils::transfer_collateral_tokens,
+};
+
+pub(crate) fn handler(ctx: Context<ClaimCollateral>) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    require!(funding_round.collateral_amount > 0, ErrorCode::NoCollateral);
+    // defaulted collateral is only distributed once a liquidation has run
+    require!(
+        funding_round.collateral_liquidated,
+        ErrorCode::CollateralNotLiquidated
+    );
+    let investor_position = &ctx.accounts.investor_position;
+    require!(
+        investor_position.amount > 0 && !investor_position.refunded,
+        ErrorCode::NotAnInvestor
+    );
+    require!(
+        !investor_position.collateral_claimed,
+        ErrorCode::CollateralAlreadyClaimed
+    );
+
+    // each investor receives the liquidated collateral in proportion to their share of the capital raised
+    let share = (funding_round.collateral_amount as u128)
+        .checked_mul(investor_position.amount as u128)
+        .and_then(|v| v.checked_div(funding_round.total_raised as u128))
+        .and_then(|v| u64::try_from(v).ok())
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    match funding_round.collateral_mint {
+        Some(collateral_mint) => {
+            let (Some(investor_token_account), Some(token_program)) = (
+                ctx.accounts.investor_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingTokenAccounts);
+            };
+            require!(
+                investor_token_account.mint == collateral_mint,
+                ErrorCode::CollateralMintMismatch
+            );
+            transfer_collateral_tokens(
+                funding_round,
+                &ctx.accounts.collateral_vault.to_account_info(),
+                &investor_token_account.to_account_info(),
+                &token_program.to_account_info(),
+                share,
+            )?;
+        }
+        // liquidation moved SOL collateral into the repayment vault
+        None => return err!(ErrorCode::CollateralClaimedThroughRepayment),
+    }
+
+    ctx.accounts.investor_position.collateral_claimed = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimCollateral<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        mut,
+        has_one = investor,
+        has_one = funding_round,
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+    /// CHECK: Collateral token vault PDA, validated by seeds and by the token program on transfer
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
+        bump = funding_round.collateral_vault_bump,
+    )]
+    pub collateral_vault: AccountInfo<'info>,
+    #[account(mut)]
+    pub investor_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FUNDING_ROUND_VAULT_SEED, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{FundingRound, FundingRoundStatus, InvestorPosition, RoundVault},
+};
+
+pub(crate) fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    require!(
+        matches!(
+            funding_round.status,
+            FundingRoundStatus::Failed | FundingRoundStatus::Cancelled
+        ),
+        ErrorCode::FundingRoundNotRefundable
+    );
+    require!(
+        !ctx.accounts.investor_position.refunded && ctx.accounts.investor_position.amount > 0,
+        ErrorCode::NothingToRefund
+    );
+
+    // capital already withdrawn by the company is lost to every investor equally,
+    // so each refund is the investor's share of what stayed in the vault
+    let remaining = funding_round.total_raised
+        .checked_sub(funding_round.total_withdrawn)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let refund = (ctx.accounts.investor_position.amount as u128)
+        .checked_mul(remaining as u128)
+        .and_then(|v| v.checked_div(funding_round.total_raised as u128))
+        .and_then(|v| u64::try_from(v).ok())
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    ctx.accounts.vault.debit(refund)?;
+    **ctx.accounts
+        .vault
+        .to_account_info()
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= refund;
+    **ctx.accounts
+        .investor
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += refund;
+
+    ctx.accounts.investor_position.refunded = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        mut,
+        has_one = investor,
+        has_one = funding_round,
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+    /// Round vault the refund is paid from
+    #[account(
+        mut,
+        seeds = [FUNDING_ROUND_VAULT_SEED, funding_round.company.as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
+}
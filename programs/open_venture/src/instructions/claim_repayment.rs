@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FUNDING_ROUND_VERSION, REPAYMENT_VAULT_SEED},
+    error::ErrorCode,
+    state::{FundingRound, FundingRoundStatus, InvestorPosition, RoundVault},
+};
+
+pub(crate) fn handler(ctx: Context<ClaimRepayment>) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    require!(
+        matches!(
+            funding_round.status,
+            FundingRoundStatus::Repaid | FundingRoundStatus::Defaulted
+        ),
+        ErrorCode::NothingToClaim
+    );
+
+    // investors are entitled to their share of everything paid into the repayment vault,
+    // whether it came from the company, from liquidated collateral or from the round's own vault
+    let distributable = funding_round.distributable()?;
+    let investor_position = &ctx.accounts.investor_position;
+    let entitlement = (distributable as u128)
+        .checked_mul(investor_position.amount as u128)
+        .and_then(|v| v.checked_div(funding_round.total_raised as u128))
+        .and_then(|v| u64::try_from(v).ok())
+        .ok_or(ErrorCode::MathOverflow)?;
+    let claimable = entitlement.saturating_sub(investor_position.repayment_claimed);
+    require!(claimable > 0, ErrorCode::NothingToClaim);
+
+    ctx.accounts.repayment_vault.debit(claimable)?;
+    **ctx.accounts
+        .repayment_vault
+        .to_account_info()
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= claimable;
+    **ctx.accounts
+        .investor
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += claimable;
+
+    ctx.accounts.investor_position.repayment_claimed = entitlement;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRepayment<'info> {
+    #[account(mut)]
+    pub investor: Signer<'info>,
+    #[account(
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        mut,
+        has_one = investor,
+        has_one = funding_round,
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+    /// Repayment vault the claim is paid from
+    #[account(
+        mut,
+        seeds = [REPAYMENT_VAULT_SEED, funding_round.company.as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.repayment_vault_bump,
+    )]
+    pub repayment_vault: Account<'info, RoundVault>,
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    constants::{
+        COMPANY_PROFILE_VERSION, COMPANY_REPUTATION_SEED, COMPANY_TREASURY_SEED,
+        OWNER_REGISTRY_SEED, COMPANY_INDEX_SEED, COMPANY_VERIFICATION_SEED,
+    },
+    error::ErrorCode,
+    state::{CompanyIndex, CompanyProfile, CompanyReputation, CompanyVerification, OwnerRegistry},
+};
+
+pub(crate) fn handler(ctx: Context<CloseCompanyProfile>) -> Result<()> {
+    // a company that has raised keeps its profile, it is what investors and its track record point to
+    require!(
+        ctx.accounts.company_profile.round_count == 0
+            && ctx.accounts.company_profile.active_round_count == 0,
+        ErrorCode::CompanyHasFundingRounds
+    );
+    require!(
+        ctx.accounts.company_profile.registered,
+        ErrorCode::CompanyNotRegistered
+    );
+    require!(
+        ctx.accounts.company_index.company == ctx.accounts.company_profile.key(),
+        ErrorCode::CompanyIndexMismatch
+    );
+
+    // swap-remove: the last entry takes the closed company's slot so indices stay contiguous
+    let owner = ctx.accounts.owner.to_account_info();
+    match &ctx.accounts.last_company_index {
+        Some(last_company_index) => {
+            ctx.accounts.company_index.company = last_company_index.company;
+            last_company_index.close(owner.clone())?;
+        }
+        None => {
+            require!(
+                ctx.accounts.company_index.index == ctx.accounts.owner_registry.last_index()?,
+                ErrorCode::CompanyIndexMismatch
+            );
+            ctx.accounts.company_index.close(owner.clone())?;
+        }
+    }
+    ctx.accounts.owner_registry.company_count = ctx.accounts.owner_registry.company_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // a profile recreated under the same name is a new company, so it must not inherit the badge
+    if ctx.accounts.company_verification.owner == &crate::ID {
+        close_verification(&ctx.accounts.company_verification, ctx.accounts.verifier.as_ref())?;
+    }
+
+    // drain the treasury so the runtime reclaims it and the name can be registered again
+    let treasury_lamports = ctx.accounts.company_treasury.lamports();
+    **ctx.accounts
+        .company_treasury
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= treasury_lamports;
+    **owner
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += treasury_lamports;
+
+    Ok(())
+}
+
+/// Closes the company's verification, refunding its rent to the verifier that paid for it.
+fn close_verification<'info>(
+    company_verification: &UncheckedAccount<'info>,
+    verifier: Option<&UncheckedAccount<'info>>,
+) -> Result<()> {
+    let verification =
+        CompanyVerification::try_deserialize(&mut &company_verification.try_borrow_data()?[..])?;
+    let Some(verifier) = verifier.filter(|verifier| verifier.key() == verification.verifier) else {
+        return err!(ErrorCode::VerifierRefundMismatch);
+    };
+
+    let lamports = company_verification.lamports();
+    **company_verification
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= lamports;
+    **verifier
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += lamports;
+    company_verification.assign(&system_program::ID);
+    company_verification.resize(0)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseCompanyProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    /// CHECK: Company treasury PDA, drained to the owner when the profile is closed
+    #[account(
+        mut,
+        seeds = [COMPANY_TREASURY_SEED, owner.key().as_ref(), company_profile.key().as_ref()],
+        bump = company_profile.treasury_bump,
+    )]
+    pub company_treasury: AccountInfo<'info>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(
+        mut,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump = owner_registry.bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        mut,
+        seeds = [COMPANY_INDEX_SEED, owner.key().as_ref(), company_index.index.to_le_bytes().as_ref()],
+        bump = company_index.bump,
+    )]
+    pub company_index: Account<'info, CompanyIndex>,
+    /// Last entry of the owner's list, moved into `company_index`; omitted when the closed
+    /// company is itself the last entry
+    #[account(
+        mut,
+        seeds = [COMPANY_INDEX_SEED, owner.key().as_ref(), owner_registry.last_index()?.to_le_bytes().as_ref()],
+        bump = last_company_index.bump,
+    )]
+    pub last_company_index: Option<Account<'info, CompanyIndex>>,
+    /// CHECK: The company's verification, closed with the profile when the company is verified.
+    /// It may not exist, so it is checked against its seeds with the canonical bump
+    #[account(
+        mut,
+        seeds = [COMPANY_VERIFICATION_SEED, company_profile.key().as_ref()],
+        bump,
+    )]
+    pub company_verification: UncheckedAccount<'info>,
+    /// CHECK: Verifier that paid for the company's verification, refunded its rent; only
+    /// required when the company is verified and checked against the verification
+    #[account(mut)]
+    pub verifier: Option<UncheckedAccount<'info>>,
+}
@@ -1,22 +1,47 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
 
-use crate::{constants::ANCHOR_DISCRIMINATOR, error::ErrorCode, state::CompanyProfile};
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_VERSION, COMPANY_PROFILE_SEED, COMPANY_TREASURY_SEED,
+        COMPANY_REPUTATION_SEED, OWNER_REGISTRY_SEED, COMPANY_INDEX_SEED,
+    },
+    error::ErrorCode,
+    state::{CompanyIndex, CompanyProfile, CompanyReputation, OwnerRegistry},
+};
 
 
-pub fn handler(ctx: Context<CreateCompanyProfile>, name: String, bio: String, ) -> Result<()> {
-    require!(name.len() > 0, ErrorCode::CompanyNameRequired);
+pub(crate) fn handler(ctx: Context<CreateCompanyProfile>, name: String, bio: String, ) -> Result<()> {
+    require!(!name.is_empty(), ErrorCode::CompanyNameRequired);
     require!(name.len() <= 32, ErrorCode::CompanyNameTooLong);
     // bio is not required, but if it is provided, it must be less than 280 characters long
     require!(bio.len() <= 280, ErrorCode::CompanyBioTooLong);
     ctx.accounts.company_profile.owner = ctx.accounts.owner.key();
     ctx.accounts.company_profile.name = name;
     ctx.accounts.company_profile.active_funding_round = None;
+    ctx.accounts.company_profile.active_round_count = 0;
+    // one round at a time until the owner or admin allows more
+    ctx.accounts.company_profile.max_active_rounds = 1;
+    ctx.accounts.company_profile.version = COMPANY_PROFILE_VERSION;
+    ctx.accounts.company_profile.bump = ctx.bumps.company_profile;
+    ctx.accounts.company_profile.treasury_bump = ctx.bumps.company_treasury;
 
-    if bio.len() > 0 {
+    if !bio.is_empty() {
         ctx.accounts.company_profile.bio = bio;
     }
 
+    // every company starts with an empty track record
+    ctx.accounts.company_reputation.company = ctx.accounts.company_profile.key();
+    ctx.accounts.company_reputation.bump = ctx.bumps.company_reputation;
+
+    // list the company under its owner so wallets can enumerate their companies
+    let owner_registry = &mut ctx.accounts.owner_registry;
+    owner_registry.owner = ctx.accounts.owner.key();
+    owner_registry.bump = ctx.bumps.owner_registry;
+    owner_registry.register(&mut ctx.accounts.company_index, ctx.accounts.company_profile.key())?;
+    ctx.accounts.company_index.bump = ctx.bumps.company_index;
+    ctx.accounts.company_profile.registered = true;
+
     Ok(())
 }
 
@@ -29,7 +54,7 @@ pub struct CreateCompanyProfile<'info> {
         init,
         payer = owner,
         space = ANCHOR_DISCRIMINATOR + CompanyProfile::INIT_SPACE,
-        seeds = ["company_profile".as_bytes(), owner.key().as_ref(), {hash(name.as_bytes()).to_bytes().as_ref()}],
+        seeds = [COMPANY_PROFILE_SEED, owner.key().as_ref(), {hash(name.as_bytes()).to_bytes().as_ref()}],
         bump,
     )]
     pub company_profile: Account<'info, CompanyProfile>,
@@ -39,15 +64,41 @@ pub struct CreateCompanyProfile<'info> {
     /// The company treasury vault is a Program Derived Address (PDA), which means:
     /// - Only the program can sign for vault transfers (no external keypair can control it)
     /// - The vault seeds include the company profile key, ensuring vaults are unique per company
-    /// - Only the company owner can authorize access to this vault (via `validate_company_treasury_access` utility)
+    /// - Instructions that move treasury funds check it against these seeds and the stored `treasury_bump`,
+    ///   with the signer as the owner seed
+    ///
     /// This ensures only the company owner can operate on the company treasury vault.
     #[account(
         init,
         payer = owner,
         space = 0,
-        seeds = ["company_treasury".as_bytes(), owner.key().as_ref(), company_profile.key().as_ref()],
+        seeds = [COMPANY_TREASURY_SEED, owner.key().as_ref(), company_profile.key().as_ref()],
         bump,
     )]
     pub company_treasury: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + CompanyReputation::INIT_SPACE,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + CompanyIndex::INIT_SPACE,
+        seeds = [COMPANY_INDEX_SEED, owner.key().as_ref(), owner_registry.company_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub company_index: Account<'info, CompanyIndex>,
     pub system_program: Program<'info, System>,
 }
@@ -1,14 +1,24 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
 
-use crate::{constants::ANCHOR_DISCRIMINATOR, error::ErrorCode, CompanyProfile, FundingRound};
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION, FUNDING_ROUND_SEED,
+        FUNDING_ROUND_VAULT_SEED, REPAYMENT_VAULT_SEED, COLLATERAL_VAULT_SEED, COMPANY_REPUTATION_SEED,
+        PROGRAM_CONFIG_SEED, TREASURY_LEDGER_SEED,
+    },
+    error::ErrorCode, utils::validate_round_size, CompanyProfile,
+    CompanyReputation, FundingRound, FundingRoundStatus, ProgramConfig, RoundVault, TreasuryLedger,
+    VaultKind,
+};
 
-pub fn handler(
+pub(crate) fn handler(
     ctx: Context<CreateFundingRound>,
-    round_id: String,
+    label: String,
     target_amount: u64,
     interest_rate: u64,
     repayment_deadline: u64,
+    funding_start: u64,
+    funding_end: u64,
 ) -> Result<()> {
     // require that owner must be the same as the company profile owner
     require!(
@@ -16,82 +26,161 @@ pub fn handler(
         ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner
     );
 
-    // cannot create funding round if there's another active funding round for the same company
+    // the company's own limit applies up to the program-wide one, which the admin may have lowered since
+    let active_round_limit = ctx.accounts.company_profile.max_active_rounds
+        .min(ctx.accounts.config.max_active_rounds);
     require!(
-        ctx.accounts.company_profile.active_funding_round.is_none(),
-        ErrorCode::ActiveFundingRoundExists
+        ctx.accounts.company_profile.active_round_count < active_round_limit,
+        ErrorCode::ActiveRoundLimitReached
     );
 
-    require!(!round_id.is_empty(), ErrorCode::FundingRoundIdRequired);
-    require!(round_id.len() <= 36, ErrorCode::FundingRoundIdTooLong);
+    require!(label.len() <= 36, ErrorCode::FundingRoundIdTooLong);
     require!(
         target_amount > 0,
         ErrorCode::TargetAmountMustBeGreaterThanZero
     );
+    validate_round_size(
+        &ctx.accounts.config,
+        &ctx.accounts.company_reputation,
+        target_amount,
+    )?;
     require!(
         interest_rate > 0,
         ErrorCode::InterestRateMustBeGreaterThanZero
     );
     // repayment deadline must be greater than current timestamp
+    let now = Clock::get()?.unix_timestamp as u64;
     require!(
-        repayment_deadline > Clock::get()?.unix_timestamp as u64,
+        repayment_deadline > now,
         ErrorCode::RepaymentDeadlineInThePast
     );
+    // the funding window may already be open, but it must still close in the future
+    require!(
+        funding_start < funding_end && funding_end <= repayment_deadline,
+        ErrorCode::InvalidFundingWindow
+    );
+    require!(funding_end > now, ErrorCode::FundingWindowEndInThePast);
 
     let funding_round_key = ctx.accounts.funding_round.key();
 
-    ctx.accounts.company_profile.active_funding_round = Some(funding_round_key);
-    ctx.accounts.funding_round.id = round_id;
+    let round_index = ctx.accounts.company_profile.round_count;
+    ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.company_profile.round_count = round_index
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.funding_round.id = label;
     ctx.accounts.funding_round.company = ctx.accounts.company_profile.key();
     ctx.accounts.funding_round.target_amount = target_amount;
     ctx.accounts.funding_round.interest_rate = interest_rate;
     ctx.accounts.funding_round.repayment_deadline = repayment_deadline;
     ctx.accounts.funding_round.is_active = true;
+    ctx.accounts.funding_round.min_contribution = 0;
+    ctx.accounts.funding_round.max_contribution = 0;
+    ctx.accounts.funding_round.total_raised = 0;
+    ctx.accounts.funding_round.allowlist_root = None;
+    ctx.accounts.funding_round.kyc_verifier = None;
+    ctx.accounts.funding_round.funding_start = funding_start;
+    ctx.accounts.funding_round.funding_end = funding_end;
+    ctx.accounts.funding_round.status = FundingRoundStatus::Open;
+    ctx.accounts.funding_round.total_withdrawn = 0;
+    ctx.accounts.funding_round.extension_fees = 0;
+    ctx.accounts.funding_round.auto_extensions = 0;
+    ctx.accounts.funding_round.collateral_mint = None;
+    ctx.accounts.funding_round.collateral_amount = 0;
+    ctx.accounts.funding_round.total_repaid = 0;
+    ctx.accounts.funding_round.collateral_liquidated = false;
+    ctx.accounts.funding_round.liquidation_proceeds = 0;
+    ctx.accounts.funding_round.version = FUNDING_ROUND_VERSION;
+    ctx.accounts.funding_round.index = round_index;
+    ctx.accounts.funding_round.indexed = true;
+    ctx.accounts.funding_round.bump = ctx.bumps.funding_round;
+    ctx.accounts.funding_round.vault_bump = ctx.bumps.vault;
+    ctx.accounts.funding_round.repayment_vault_bump = ctx.bumps.repayment_vault;
+    ctx.accounts.funding_round.treasury_ledger_bump = ctx.bumps.treasury_ledger;
+    ctx.accounts.funding_round.collateral_vault_bump = ctx.bumps.collateral_vault;
+
+    ctx.accounts.vault.funding_round = funding_round_key;
+    ctx.accounts.vault.kind = VaultKind::Funding;
+    ctx.accounts.vault.tracked_balance = 0;
+    ctx.accounts.repayment_vault.funding_round = funding_round_key;
+    ctx.accounts.repayment_vault.kind = VaultKind::Repayment;
+    ctx.accounts.repayment_vault.tracked_balance = 0;
+    ctx.accounts.treasury_ledger.company = ctx.accounts.company_profile.key();
+    ctx.accounts.treasury_ledger.funding_round = funding_round_key;
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(round_id: String)]
 pub struct CreateFundingRound<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
     pub company_profile: Account<'info, CompanyProfile>,
     #[account(
         init,
         payer = owner,
         space = ANCHOR_DISCRIMINATOR + FundingRound::INIT_SPACE,
-        seeds = ["funding_round".as_bytes(), company_profile.key().as_ref(), {hash(round_id.as_bytes()).to_bytes().as_ref()}],
+        seeds = [FUNDING_ROUND_SEED, company_profile.key().as_ref(), company_profile.round_count.to_le_bytes().as_ref()],
         bump,
     )]
     pub funding_round: Account<'info, FundingRound>,
-    /// CHECK: Vault PDA is derived from company profile and round_id seeds, ensuring uniqueness.
+    /// Vault PDA is derived from company profile and round index seeds, ensuring uniqueness.
     ///
     /// # Security Model
     /// The vault is a Program Derived Address (PDA), which means:
     /// - Only the program can sign for vault transfers (no external keypair can control it)
     /// - The vault seeds include the company profile key, ensuring vaults are unique per company
     /// - To access vault funds, any instruction must:
-    ///   1. Verify the signer is the company owner (via `validate_vault_access` utility)
-    ///   2. Use the correct seeds to derive the vault PDA
+    ///   1. Verify the signer is the company owner
+    ///   2. Check the vault against these seeds and the round's stored `vault_bump`
+    ///
     /// This ensures only the company owner can authorize access to their vault funds.
     #[account(
         init,
         payer = owner,
-        space = 0,
-        seeds = ["funding_round_vault".as_bytes(), company_profile.key().as_ref(), {hash(round_id.as_bytes()).to_bytes().as_ref()}],
+        space = ANCHOR_DISCRIMINATOR + RoundVault::INIT_SPACE,
+        seeds = [FUNDING_ROUND_VAULT_SEED, company_profile.key().as_ref(), company_profile.round_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
+    /// Repayment vault PDA stores repaid funds and is derived from company profile + round index
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + RoundVault::INIT_SPACE,
+        seeds = [REPAYMENT_VAULT_SEED, company_profile.key().as_ref(), company_profile.round_count.to_le_bytes().as_ref()],
         bump,
     )]
-    pub vault: AccountInfo<'info>,
-    /// CHECK: Repayment vault PDA stores repaid funds and is derived from company profile + round id
+    pub repayment_vault: Account<'info, RoundVault>,
+    /// The treasury's record of what moves between it and this round
     #[account(
         init,
         payer = owner,
-        space = 0,
-        seeds = ["funding_round_repayment".as_bytes(), company_profile.key().as_ref(), {hash(round_id.as_bytes()).to_bytes().as_ref()}],
+        space = ANCHOR_DISCRIMINATOR + TreasuryLedger::INIT_SPACE,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+    /// CHECK: Collateral vault PDA, created on the first collateral deposit. Only its bump is
+    /// needed here, so it is stored with the round's other bumps
+    #[account(
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
         bump,
     )]
-    pub repayment_vault: AccountInfo<'info>,
+    pub collateral_vault: AccountInfo<'info>,
+    #[account(
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    constants::{COLLATERAL_VAULT_SEED, COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, FundingRoundStatus},
+};
+
+pub(crate) fn handler(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::CollateralAmountMustBeGreaterThanZero);
+    // collateral is part of the terms investors commit to, so it is locked before the first deposit
+    require!(
+        ctx.accounts.funding_round.status == FundingRoundStatus::Open,
+        ErrorCode::FundingRoundNotActive
+    );
+    require!(
+        ctx.accounts.funding_round.total_raised == 0,
+        ErrorCode::FundingRoundHasDeposits
+    );
+    require!(
+        ctx.accounts.funding_round.collateral_mint.is_none(),
+        ErrorCode::CollateralKindMismatch
+    );
+
+    let transfer_accounts = system_program::Transfer {
+        from: ctx.accounts.owner.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_accounts);
+    system_program::transfer(cpi_ctx, amount)?;
+
+    ctx.accounts.funding_round.collateral_amount = ctx.accounts.funding_round.collateral_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositCollateral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// CHECK: Collateral vault PDA holding SOL collateral, derived from the funding round.
+    ///
+    /// Only the program can move lamports out of it, on release to the owner or on claims by investors.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 0,
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
+        bump,
+    )]
+    pub collateral_vault: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
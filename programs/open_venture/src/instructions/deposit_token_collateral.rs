@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+
+use crate::{
+    constants::{COLLATERAL_VAULT_SEED, COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, FundingRoundStatus},
+};
+
+pub(crate) fn handler(ctx: Context<DepositTokenCollateral>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::CollateralAmountMustBeGreaterThanZero);
+    // collateral is part of the terms investors commit to, so it is locked before the first deposit
+    require!(
+        ctx.accounts.funding_round.status == FundingRoundStatus::Open,
+        ErrorCode::FundingRoundNotActive
+    );
+    require!(
+        ctx.accounts.funding_round.total_raised == 0,
+        ErrorCode::FundingRoundHasDeposits
+    );
+    // a round holds a single collateral asset, SOL collateral is tracked with no mint
+    let collateral_mint = ctx.accounts.collateral_mint.key();
+    match ctx.accounts.funding_round.collateral_mint {
+        Some(mint) => require!(mint == collateral_mint, ErrorCode::CollateralKindMismatch),
+        None => require!(
+            ctx.accounts.funding_round.collateral_amount == 0,
+            ErrorCode::CollateralKindMismatch
+        ),
+    }
+
+    let transfer_accounts = token::Transfer {
+        from: ctx.accounts.owner_token_account.to_account_info(),
+        to: ctx.accounts.collateral_vault.to_account_info(),
+        authority: ctx.accounts.owner.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_accounts);
+    token::transfer(cpi_ctx, amount)?;
+
+    ctx.accounts.funding_round.collateral_mint = Some(collateral_mint);
+    ctx.accounts.funding_round.collateral_amount = ctx.accounts.funding_round.collateral_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositTokenCollateral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    pub collateral_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    // the funding round PDA owns the vault, so only the program can move the collateral
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = funding_round,
+    )]
+    pub collateral_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
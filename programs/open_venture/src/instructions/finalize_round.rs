@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, FundingRoundStatus},
+};
+
+pub(crate) fn handler(ctx: Context<FinalizeRound>) -> Result<()> {
+    require!(
+        ctx.accounts.funding_round.status == FundingRoundStatus::Open,
+        ErrorCode::FundingRoundNotActive
+    );
+    // anyone can finalize the round, but only once its funding window has closed
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(
+        now >= ctx.accounts.funding_round.funding_end,
+        ErrorCode::FundingWindowStillOpen
+    );
+
+    let funding_round = &mut ctx.accounts.funding_round;
+    if funding_round.total_raised >= funding_round.target_amount {
+        funding_round.status = FundingRoundStatus::Funded;
+    } else {
+        // an underfunded round ends here and frees the company to raise again
+        funding_round.status = FundingRoundStatus::Failed;
+        funding_round.is_active = false;
+        ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeRound<'info> {
+    #[account(
+        mut,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+}
@@ -1,30 +1,59 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
 use anchor_lang::system_program;
 
 use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, FUNDING_ROUND_VAULT_SEED, FUNDING_ROUND_VERSION,
+        INVESTOR_POSITION_SEED, INVESTOR_PORTFOLIO_SEED, PORTFOLIO_ENTRY_SEED,
+    },
     error::ErrorCode,
-    state::{CompanyProfile, FundingRound},
+    state::{
+        CompanyProfile, FundingRound, FundingRoundStatus, InvestorAttestation, InvestorPortfolio,
+        InvestorPosition, PortfolioEntry, RoundVault,
+    },
+    utils::verify_allowlist_proof,
 };
 
-pub fn handler(ctx: Context<FundCompany>, amount: u64) -> Result<()> {
+pub(crate) fn handler(ctx: Context<FundCompany>, amount: u64, allowlist_proof: Vec<[u8; 32]>) -> Result<()> {
     require!(amount > 0, ErrorCode::DepositAmountMustBeGreaterThanZero);
-    require!(ctx.accounts.funding_round.is_active, ErrorCode::FundingRoundNotActive);
-
-    // Validate vault PDA
-    let company_profile_key = ctx.accounts.company_profile.key();
-    let round_id_hash = hash(ctx.accounts.funding_round.id.as_bytes());
-    let round_id_seed = round_id_hash.to_bytes();
-    let seeds = &[
-        b"funding_round_vault".as_ref(),
-        company_profile_key.as_ref(),
-        round_id_seed.as_ref(),
-    ];
-    let (expected_vault, _) = Pubkey::find_program_address(seeds, ctx.program_id);
-    // prevent contribution to the wrong vault
     require!(
-        ctx.accounts.vault.key() == expected_vault,
-        ErrorCode::FundingRoundCompanyMismatch
+        ctx.accounts.funding_round.is_active
+            && ctx.accounts.funding_round.status == FundingRoundStatus::Open,
+        ErrorCode::FundingRoundNotActive
+    );
+    // deposits are only accepted inside the round's funding window
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(
+        now >= ctx.accounts.funding_round.funding_start,
+        ErrorCode::FundingWindowNotOpen
+    );
+    require!(
+        now < ctx.accounts.funding_round.funding_end,
+        ErrorCode::FundingWindowClosed
+    );
+
+    validate_investor_eligibility(&ctx, &allowlist_proof)?;
+
+    // enforce the round's ticket size and the investor's cumulative cap
+    let funding_round = &ctx.accounts.funding_round;
+    require!(
+        amount >= funding_round.min_contribution,
+        ErrorCode::ContributionBelowMinimum
+    );
+    let investor_total = ctx.accounts.investor_position.amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        funding_round.max_contribution == 0 || investor_total <= funding_round.max_contribution,
+        ErrorCode::ContributionExceedsMaximum
+    );
+    // the amount owed back is computed from the target, so the round never raises more than it
+    let total_raised = funding_round.total_raised
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        total_raised <= funding_round.target_amount,
+        ErrorCode::ContributionExceedsTarget
     );
 
     let transfer_accounts = system_program::Transfer {
@@ -34,10 +63,64 @@ pub fn handler(ctx: Context<FundCompany>, amount: u64) -> Result<()> {
 
     let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_accounts);
     system_program::transfer(cpi_ctx, amount)?;
+    ctx.accounts.vault.credit(amount)?;
+
+    ctx.accounts.funding_round.total_raised = total_raised;
+
+    let is_new_position = !ctx.accounts.investor_position.is_opened();
+    let investor_position_key = ctx.accounts.investor_position.key();
+    let investor_position = &mut ctx.accounts.investor_position;
+    investor_position.investor = ctx.accounts.investor.key();
+    investor_position.funding_round = ctx.accounts.funding_round.key();
+    investor_position.amount = investor_total;
+
+    // the first deposit into a round lists the position in the investor's portfolio
+    if is_new_position {
+        let investor_portfolio = &mut ctx.accounts.investor_portfolio;
+        investor_portfolio.investor = ctx.accounts.investor.key();
+        investor_portfolio.record(
+            &mut ctx.accounts.portfolio_entry,
+            investor_position,
+            investor_position_key,
+        )?;
+    }
 
     Ok(())
 }
 
+/// Checks the investor against the round's allowlist and KYC gates.
+///
+/// A round with neither gate configured accepts any investor. When one or both
+/// are configured, the investor must satisfy at least one of them: a merkle proof
+/// against `allowlist_root`, or an unexpired attestation issued by `kyc_verifier`.
+fn validate_investor_eligibility(ctx: &Context<FundCompany>, allowlist_proof: &[[u8; 32]]) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    if funding_round.allowlist_root.is_none() && funding_round.kyc_verifier.is_none() {
+        return Ok(());
+    }
+
+    let investor_key = ctx.accounts.investor.key();
+    if let Some(root) = funding_round.allowlist_root {
+        if verify_allowlist_proof(allowlist_proof, &root, &investor_key) {
+            return Ok(());
+        }
+    }
+
+    if let (Some(kyc_verifier), Some(attestation)) =
+        (funding_round.kyc_verifier, ctx.accounts.attestation.as_ref())
+    {
+        let now = Clock::get()?.unix_timestamp;
+        if attestation.verifier == kyc_verifier
+            && attestation.investor == investor_key
+            && (attestation.expires_at == 0 || attestation.expires_at > now)
+        {
+            return Ok(());
+        }
+    }
+
+    err!(ErrorCode::InvestorNotEligible)
+}
+
 #[derive(Accounts)]
 pub struct FundCompany<'info> {
     #[account(mut)]
@@ -46,11 +129,47 @@ pub struct FundCompany<'info> {
     pub company_profile: Account<'info, CompanyProfile>,
     #[account(
         mut,
-        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
     )]
     pub funding_round: Account<'info, FundingRound>,
-    /// CHECK: Vault PDA is validated in handler
-    #[account(mut)]
-    pub vault: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = investor,
+        space = ANCHOR_DISCRIMINATOR + InvestorPosition::INIT_SPACE,
+        seeds = [INVESTOR_POSITION_SEED, funding_round.key().as_ref(), investor.key().as_ref()],
+        bump,
+    )]
+    pub investor_position: Account<'info, InvestorPosition>,
+    #[account(
+        init_if_needed,
+        payer = investor,
+        space = ANCHOR_DISCRIMINATOR + InvestorPortfolio::INIT_SPACE,
+        seeds = [INVESTOR_PORTFOLIO_SEED, investor.key().as_ref()],
+        bump,
+    )]
+    pub investor_portfolio: Account<'info, InvestorPortfolio>,
+    /// Portfolio slot of the position, created on the investor's first deposit into the round
+    #[account(
+        init_if_needed,
+        payer = investor,
+        space = ANCHOR_DISCRIMINATOR + PortfolioEntry::INIT_SPACE,
+        seeds = [
+            PORTFOLIO_ENTRY_SEED,
+            investor.key().as_ref(),
+            investor_position.portfolio_slot(&investor_portfolio).to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub portfolio_entry: Account<'info, PortfolioEntry>,
+    /// Attestation issued to the investor by the round's KYC verifier, only required for gated rounds
+    pub attestation: Option<Account<'info, InvestorAttestation>>,
+    /// Round vault receiving the deposit
+    #[account(
+        mut,
+        seeds = [FUNDING_ROUND_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
     pub system_program: Program<'info, System>,
 }
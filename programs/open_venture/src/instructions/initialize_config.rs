@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, BASIS_POINTS, PROGRAM_CONFIG_SEED},
+    error::ErrorCode,
+    program::OpenVenture,
+    state::ProgramConfig,
+};
+
+pub(crate) fn handler(
+    ctx: Context<InitializeConfig>,
+    max_auto_extension: u64,
+    max_auto_extensions: u8,
+    liquidation_grace_period: u64,
+    keeper_reward_bps: u16,
+    unproven_round_cap: u64,
+    max_active_rounds: u8,
+) -> Result<()> {
+    require!(
+        keeper_reward_bps as u64 <= BASIS_POINTS,
+        ErrorCode::InvalidKeeperReward
+    );
+    require!(max_active_rounds > 0, ErrorCode::InvalidActiveRoundLimit);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.max_auto_extension = max_auto_extension;
+    config.max_auto_extensions = max_auto_extensions;
+    config.liquidation_grace_period = liquidation_grace_period;
+    config.keeper_reward_bps = keeper_reward_bps;
+    config.unproven_round_cap = unproven_round_cap;
+    config.max_active_rounds = max_active_rounds;
+    config.bump = ctx.bumps.config;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR + ProgramConfig::INIT_SPACE,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    // only the upgrade authority of the deployed program can become its admin
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, OpenVenture>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::UnauthorizedConfigInitialization
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, INVESTOR_ATTESTATION_SEED},
+    error::ErrorCode,
+    state::InvestorAttestation,
+};
+
+pub(crate) fn handler(ctx: Context<IssueAttestation>, expires_at: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    // an expiry of 0 issues an attestation that is valid until revoked
+    require!(
+        expires_at == 0 || expires_at > now,
+        ErrorCode::AttestationExpiryInThePast
+    );
+
+    let attestation = &mut ctx.accounts.attestation;
+    attestation.verifier = ctx.accounts.verifier.key();
+    attestation.investor = ctx.accounts.investor.key();
+    attestation.issued_at = now;
+    attestation.expires_at = expires_at;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct IssueAttestation<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+    /// CHECK: The investor being attested only contributes its key to the attestation seeds
+    pub investor: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = verifier,
+        space = ANCHOR_DISCRIMINATOR + InvestorAttestation::INIT_SPACE,
+        seeds = [INVESTOR_ATTESTATION_SEED, verifier.key().as_ref(), investor.key().as_ref()],
+        bump,
+    )]
+    pub attestation: Account<'info, InvestorAttestation>,
+    pub system_program: Program<'info, System>,
+}
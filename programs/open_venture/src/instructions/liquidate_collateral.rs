@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::{
+        BASIS_POINTS, COMPANY_PROFILE_VERSION, REPAYMENT_VAULT_SEED, COLLATERAL_VAULT_SEED, COMPANY_REPUTATION_SEED,
+        PROGRAM_CONFIG_SEED, FUNDING_ROUND_VAULT_SEED, FUNDING_ROUND_VERSION, TREASURY_LEDGER_SEED,
+    },
+    error::ErrorCode,
+    state::{
+        CompanyProfile, CompanyReputation, FundingRound, FundingRoundStatus, ProgramConfig, RoundVault,
+        TreasuryLedger,
+    },
+    utils::{release_unwithdrawn_capital, transfer_collateral_tokens},
+};
+
+pub(crate) fn handler(ctx: Context<LiquidateCollateral>) -> Result<()> {
+    let funding_round = &ctx.accounts.funding_round;
+    require!(funding_round.collateral_amount > 0, ErrorCode::NoCollateral);
+    require!(
+        !funding_round.collateral_liquidated,
+        ErrorCode::CollateralAlreadyLiquidated
+    );
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(
+        funding_round.is_in_default(now),
+        ErrorCode::FundingRoundNotInDefault
+    );
+    // the company keeps a grace period after the deadline to repay before anyone can liquidate
+    require!(
+        funding_round.grace_period_elapsed(now, ctx.accounts.config.liquidation_grace_period)?,
+        ErrorCode::LiquidationGracePeriodActive
+    );
+
+    let keeper_reward = (funding_round.collateral_amount as u128)
+        .checked_mul(ctx.accounts.config.keeper_reward_bps as u128)
+        .and_then(|v| v.checked_div(BASIS_POINTS as u128))
+        .and_then(|v| u64::try_from(v).ok())
+        .ok_or(ErrorCode::MathOverflow)?;
+    let proceeds = funding_round.collateral_amount
+        .checked_sub(keeper_reward)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    match funding_round.collateral_mint {
+        Some(collateral_mint) => {
+            // token collateral stays in its vault, which investors then claim from pro-rata
+            let (Some(keeper_token_account), Some(token_program)) = (
+                ctx.accounts.keeper_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingTokenAccounts);
+            };
+            require!(
+                keeper_token_account.mint == collateral_mint,
+                ErrorCode::CollateralMintMismatch
+            );
+            transfer_collateral_tokens(
+                funding_round,
+                &ctx.accounts.collateral_vault.to_account_info(),
+                &keeper_token_account.to_account_info(),
+                &token_program.to_account_info(),
+                keeper_reward,
+            )?;
+            ctx.accounts.funding_round.collateral_amount = proceeds;
+        }
+        None => {
+            // SOL collateral is moved into the repayment vault and paid out through repayment claims
+            **ctx.accounts
+                .collateral_vault
+                .try_borrow_mut_lamports()
+                .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= funding_round.collateral_amount;
+            **ctx.accounts
+                .keeper
+                .try_borrow_mut_lamports()
+                .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += keeper_reward;
+            **ctx.accounts
+                .repayment_vault
+                .to_account_info()
+                .try_borrow_mut_lamports()
+                .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += proceeds;
+            ctx.accounts.repayment_vault.credit(proceeds)?;
+            ctx.accounts.funding_round.liquidation_proceeds = proceeds;
+            ctx.accounts.funding_round.collateral_amount = 0;
+        }
+    }
+
+    release_unwithdrawn_capital(
+        &mut ctx.accounts.funding_round,
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.repayment_vault,
+    )?;
+
+    let funding_round = &mut ctx.accounts.funding_round;
+    funding_round.collateral_liquidated = true;
+    funding_round.status = FundingRoundStatus::Defaulted;
+    funding_round.is_active = false;
+    ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.company_reputation.record_default(funding_round.total_raised)?;
+    ctx.accounts.treasury_ledger.record_default(&mut ctx.accounts.company_profile)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LiquidateCollateral<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    #[account(
+        mut,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// CHECK: Collateral vault PDA, either a lamport vault or a token account depending on the collateral
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
+        bump = funding_round.collateral_vault_bump,
+    )]
+    pub collateral_vault: AccountInfo<'info>,
+    /// Funding round vault, whose unwithdrawn capital is released to investors
+    #[account(
+        mut,
+        seeds = [FUNDING_ROUND_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
+    /// Repayment vault, credited with the proceeds when collateral is SOL and with the released capital
+    #[account(
+        mut,
+        seeds = [REPAYMENT_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.repayment_vault_bump,
+    )]
+    pub repayment_vault: Account<'info, RoundVault>,
+    /// The treasury's record of what moves between it and this round
+    #[account(
+        mut,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump = funding_round.treasury_ledger_bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+    #[account(
+        mut,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    /// Destination for the keeper reward when collateral is an SPL token
+    #[account(mut)]
+    pub keeper_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        COMPANY_PROFILE_VERSION, COMPANY_REPUTATION_SEED, FUNDING_ROUND_VAULT_SEED, FUNDING_ROUND_VERSION,
+        PROGRAM_CONFIG_SEED, REPAYMENT_VAULT_SEED, TREASURY_LEDGER_SEED,
+    },
+    error::ErrorCode,
+    state::{
+        CompanyProfile, CompanyReputation, FundingRound, FundingRoundStatus, ProgramConfig, RoundVault,
+        TreasuryLedger,
+    },
+    utils::release_unwithdrawn_capital,
+};
+
+pub(crate) fn handler(ctx: Context<MarkRoundDefaulted>) -> Result<()> {
+    // collateralized rounds are defaulted by liquidating their collateral instead
+    require!(
+        ctx.accounts.funding_round.collateral_amount == 0,
+        ErrorCode::CollateralNotLiquidated
+    );
+    let now = Clock::get()?.unix_timestamp as u64;
+    require!(
+        ctx.accounts.funding_round.is_in_default(now),
+        ErrorCode::FundingRoundNotInDefault
+    );
+    require!(
+        ctx.accounts.funding_round
+            .grace_period_elapsed(now, ctx.accounts.config.liquidation_grace_period)?,
+        ErrorCode::LiquidationGracePeriodActive
+    );
+
+    release_unwithdrawn_capital(
+        &mut ctx.accounts.funding_round,
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.repayment_vault,
+    )?;
+
+    let funding_round = &mut ctx.accounts.funding_round;
+    funding_round.status = FundingRoundStatus::Defaulted;
+    funding_round.is_active = false;
+    ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.company_reputation.record_default(funding_round.total_raised)?;
+    ctx.accounts.treasury_ledger.record_default(&mut ctx.accounts.company_profile)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MarkRoundDefaulted<'info> {
+    #[account(
+        mut,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// Funding round vault, whose unwithdrawn capital is released to investors
+    #[account(
+        mut,
+        seeds = [FUNDING_ROUND_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
+    /// Repayment vault investors claim the released capital from
+    #[account(
+        mut,
+        seeds = [REPAYMENT_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.repayment_vault_bump,
+    )]
+    pub repayment_vault: Account<'info, RoundVault>,
+    /// The treasury's record of what moves between it and this round
+    #[account(
+        mut,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump = funding_round.treasury_ledger_bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+    #[account(
+        mut,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+}
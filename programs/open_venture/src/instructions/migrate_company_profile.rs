@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_SEED, COMPANY_PROFILE_VERSION, COMPANY_REPUTATION_SEED,
+        COMPANY_TREASURY_SEED, PROGRAM_CONFIG_SEED,
+    },
+    error::ErrorCode,
+    state::{CompanyProfile, CompanyReputation, ProgramConfig},
+    utils::grow_account,
+};
+
+pub(crate) fn handler(ctx: Context<MigrateCompanyProfile>) -> Result<()> {
+    let company_profile_info = ctx.accounts.company_profile.to_account_info();
+    // grow first: fields appended since the profile was created decode from the zeroed tail
+    grow_account(
+        &company_profile_info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ANCHOR_DISCRIMINATOR + CompanyProfile::INIT_SPACE,
+    )?;
+
+    let mut company_profile =
+        CompanyProfile::try_deserialize(&mut &company_profile_info.try_borrow_data()?[..])?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == company_profile.owner || authority == ctx.accounts.config.admin,
+        ErrorCode::UnauthorizedMigration
+    );
+    require!(
+        company_profile.version < COMPANY_PROFILE_VERSION,
+        ErrorCode::AccountAlreadyMigrated
+    );
+
+    // profiles created before bumps were stored find them once here
+    let owner = company_profile.owner;
+    let company_profile_key = ctx.accounts.company_profile.key();
+    company_profile.bump = Pubkey::find_program_address(
+        &[COMPANY_PROFILE_SEED, owner.as_ref(), hash(company_profile.name.as_bytes()).as_ref()],
+        ctx.program_id,
+    ).1;
+    company_profile.treasury_bump = Pubkey::find_program_address(
+        &[COMPANY_TREASURY_SEED, owner.as_ref(), company_profile_key.as_ref()],
+        ctx.program_id,
+    ).1;
+    // the single active round becomes a count, and the company keeps its limit of one
+    company_profile.active_round_count = company_profile.active_funding_round.is_some() as u8;
+    company_profile.active_funding_round = None;
+    company_profile.max_active_rounds = 1;
+    company_profile.version = COMPANY_PROFILE_VERSION;
+    company_profile.try_serialize(&mut &mut company_profile_info.try_borrow_mut_data()?[..])?;
+
+    // companies from before reputations were tracked start with an empty track record
+    let company_reputation = &mut ctx.accounts.company_reputation;
+    company_reputation.company = company_profile_key;
+    company_reputation.bump = ctx.bumps.company_reputation;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateCompanyProfile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    /// CHECK: Loaded by hand because profiles on an older layout may not deserialize until they
+    /// have been grown; the discriminator is checked when the profile is deserialized.
+    #[account(mut, owner = crate::ID)]
+    pub company_profile: UncheckedAccount<'info>,
+    /// Created for companies from before reputations were tracked, kept if it exists
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + CompanyReputation::INIT_SPACE,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
+    pub system_program: Program<'info, System>,
+}
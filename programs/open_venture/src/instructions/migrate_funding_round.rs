@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, COLLATERAL_VAULT_SEED, FUNDING_ROUND_SEED, FUNDING_ROUND_VAULT_SEED,
+        FUNDING_ROUND_VERSION, LEGACY_FUNDING_ROUND_SPACE, PROGRAM_CONFIG_SEED, REPAYMENT_VAULT_SEED,
+        TREASURY_LEDGER_SEED,
+    },
+    error::ErrorCode,
+    state::{
+        CompanyProfile, FundingRound, FundingRoundStatus, ProgramConfig, RoundVault, TreasuryLedger,
+        VaultKind,
+    },
+    utils::grow_account,
+};
+
+pub(crate) fn handler(ctx: Context<MigrateFundingRound>) -> Result<()> {
+    let funding_round_info = ctx.accounts.funding_round.to_account_info();
+    let legacy_layout = funding_round_info.data_len() <= ANCHOR_DISCRIMINATOR + LEGACY_FUNDING_ROUND_SPACE;
+    // grow first: fields appended since the round was created decode from the zeroed tail
+    grow_account(
+        &funding_round_info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ANCHOR_DISCRIMINATOR + FundingRound::INIT_SPACE,
+    )?;
+
+    let mut funding_round =
+        FundingRound::try_deserialize(&mut &funding_round_info.try_borrow_data()?[..])?;
+    require!(
+        funding_round.company == ctx.accounts.company_profile.key(),
+        ErrorCode::FundingRoundCompanyMismatch
+    );
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.company_profile.owner || authority == ctx.accounts.config.admin,
+        ErrorCode::UnauthorizedMigration
+    );
+    require!(
+        funding_round.version < FUNDING_ROUND_VERSION,
+        ErrorCode::AccountAlreadyMigrated
+    );
+
+    // rounds created before bumps were stored find them once here
+    let company_profile_key = ctx.accounts.company_profile.key();
+    let funding_round_key = ctx.accounts.funding_round.key();
+    let round_id_seed = funding_round.round_seed();
+    let find_round_pda = |seed_prefix: &[u8]| {
+        Pubkey::find_program_address(
+            &[seed_prefix, company_profile_key.as_ref(), round_id_seed.as_ref()],
+            ctx.program_id,
+        )
+    };
+    let (expected_round, bump) = find_round_pda(FUNDING_ROUND_SEED);
+    require!(
+        funding_round_key == expected_round,
+        ErrorCode::FundingRoundCompanyMismatch
+    );
+    let (expected_vault, vault_bump) = find_round_pda(FUNDING_ROUND_VAULT_SEED);
+    let (expected_repayment_vault, repayment_vault_bump) = find_round_pda(REPAYMENT_VAULT_SEED);
+    require!(
+        ctx.accounts.vault.key() == expected_vault
+            && ctx.accounts.repayment_vault.key() == expected_repayment_vault,
+        ErrorCode::FundingRoundCompanyMismatch
+    );
+    if legacy_layout {
+        fill_legacy_round(&mut funding_round, ctx.accounts)?;
+    }
+    funding_round.bump = bump;
+    funding_round.vault_bump = vault_bump;
+    funding_round.repayment_vault_bump = repayment_vault_bump;
+    funding_round.collateral_vault_bump = Pubkey::find_program_address(
+        &[COLLATERAL_VAULT_SEED, funding_round_key.as_ref()],
+        ctx.program_id,
+    ).1;
+    funding_round.treasury_ledger_bump = ctx.bumps.treasury_ledger;
+    funding_round.version = FUNDING_ROUND_VERSION;
+    funding_round.try_serialize(&mut &mut funding_round_info.try_borrow_mut_data()?[..])?;
+
+    // the round's totals say what moved before it had a ledger; stray transfers swept back
+    // then were not recorded and stay out of it
+    let treasury_ledger = &mut ctx.accounts.treasury_ledger;
+    if !treasury_ledger.is_opened() {
+        treasury_ledger.company = company_profile_key;
+        treasury_ledger.funding_round = funding_round_key;
+        treasury_ledger.record_withdrawal(&mut ctx.accounts.company_profile, funding_round.total_withdrawn)?;
+        treasury_ledger.record_repayment(&mut ctx.accounts.company_profile, funding_round.total_repaid)?;
+        if funding_round.status == FundingRoundStatus::Defaulted {
+            treasury_ledger.record_default(&mut ctx.accounts.company_profile)?;
+        }
+    }
+
+    add_vault_header(ctx.accounts, &ctx.accounts.vault, VaultKind::Funding)?;
+    add_vault_header(ctx.accounts, &ctx.accounts.repayment_vault, VaultKind::Repayment)?;
+
+    Ok(())
+}
+
+/// Fills in what a round written by the first program version did not record.
+///
+/// Those rounds took deposits for as long as they were active and kept no totals, so what they
+/// raised and repaid is what their vaults hold above rent. Their investors have no positions to
+/// be refunded from, so the round's funding closes as `Funded`, or `Repaid` once the repayment
+/// is in, and it can't be finalized as failed.
+fn fill_legacy_round(funding_round: &mut FundingRound, accounts: &mut MigrateFundingRound) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let now = Clock::get()?.unix_timestamp as u64;
+    funding_round.total_raised = accounts.vault.lamports().saturating_sub(rent_exempt_minimum);
+    funding_round.funding_start = 0;
+    funding_round.funding_end = now;
+
+    let repaid = accounts.repayment_vault.lamports().saturating_sub(rent_exempt_minimum);
+    if repaid == 0 {
+        funding_round.status = FundingRoundStatus::Funded;
+        return Ok(());
+    }
+    funding_round.status = FundingRoundStatus::Repaid;
+    funding_round.total_repaid = repaid;
+    funding_round.is_active = false;
+    accounts.company_profile.active_round_count = accounts.company_profile.active_round_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Gives a vault created before vaults carried a header its `RoundVault` header.
+///
+/// Deposits and stray transfers into such a vault can't be told apart, so everything above
+/// its rent-exempt minimum becomes the tracked balance. The authority pays the rent of the
+/// header so the vault still holds all of it afterwards.
+fn add_vault_header<'info>(
+    accounts: &MigrateFundingRound<'info>,
+    vault: &UncheckedAccount<'info>,
+    kind: VaultKind,
+) -> Result<()> {
+    // a vault emptied by the round, like the repayment vault of a cancelled round, has been
+    // reclaimed by the runtime and has nothing left to track
+    if vault.owner != &crate::ID || !vault.data_is_empty() {
+        return Ok(());
+    }
+
+    let vault_info = vault.to_account_info();
+    let rent = Rent::get()?;
+    let header_len = ANCHOR_DISCRIMINATOR + RoundVault::INIT_SPACE;
+    let tracked_balance = vault_info.lamports().saturating_sub(rent.minimum_balance(0));
+    let rent_due = (rent.minimum_balance(header_len) + tracked_balance)
+        .saturating_sub(vault_info.lamports());
+    if rent_due > 0 {
+        let transfer_accounts = system_program::Transfer {
+            from: accounts.authority.to_account_info(),
+            to: vault_info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(accounts.system_program.to_account_info(), transfer_accounts);
+        system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    vault_info.resize(header_len)?;
+
+    let header = RoundVault {
+        funding_round: accounts.funding_round.key(),
+        kind,
+        tracked_balance,
+    };
+    header.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateFundingRound<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    /// The company profile must be on a readable layout, so migrate it first
+    #[account(mut)]
+    pub company_profile: Account<'info, CompanyProfile>,
+    /// CHECK: Loaded by hand because rounds on an older layout may not deserialize until they
+    /// have been grown; the discriminator is checked when the round is deserialized.
+    #[account(mut, owner = crate::ID)]
+    pub funding_round: UncheckedAccount<'info>,
+    /// CHECK: Checked against the round's vault PDA in the handler, whose seeds come from the
+    /// round; given a header if it was created without one
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    /// CHECK: Checked against the round's repayment vault PDA in the handler, like `vault`
+    #[account(mut)]
+    pub repayment_vault: UncheckedAccount<'info>,
+    /// Created for rounds from before the treasury kept a ledger per round, kept if it exists
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + TreasuryLedger::INIT_SPACE,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+    pub system_program: Program<'info, System>,
+}
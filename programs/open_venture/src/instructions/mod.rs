@@ -13,3 +13,93 @@ pub use withdraw_funds::*;
 
 pub mod repay_funding_round;
 pub use repay_funding_round::*;
+
+pub mod set_contribution_limits;
+pub use set_contribution_limits::*;
+
+pub mod set_investor_gate;
+pub use set_investor_gate::*;
+
+pub mod issue_attestation;
+pub use issue_attestation::*;
+
+pub mod revoke_attestation;
+pub use revoke_attestation::*;
+
+pub mod finalize_round;
+pub use finalize_round::*;
+
+pub mod claim_refund;
+pub use claim_refund::*;
+
+pub mod cancel_funding_round;
+pub use cancel_funding_round::*;
+
+pub mod amend_funding_round;
+pub use amend_funding_round::*;
+
+pub mod approve_amendment;
+pub use approve_amendment::*;
+
+pub mod initialize_config;
+pub use initialize_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod request_extension;
+pub use request_extension::*;
+
+pub mod deposit_collateral;
+pub use deposit_collateral::*;
+
+pub mod deposit_token_collateral;
+pub use deposit_token_collateral::*;
+
+pub mod release_collateral;
+pub use release_collateral::*;
+
+pub mod claim_collateral;
+pub use claim_collateral::*;
+
+pub mod liquidate_collateral;
+pub use liquidate_collateral::*;
+
+pub mod claim_repayment;
+pub use claim_repayment::*;
+
+pub mod mark_round_defaulted;
+pub use mark_round_defaulted::*;
+
+pub mod add_company_verifier;
+pub use add_company_verifier::*;
+
+pub mod remove_company_verifier;
+pub use remove_company_verifier::*;
+
+pub mod verify_company;
+pub use verify_company::*;
+
+pub mod revoke_company_verification;
+pub use revoke_company_verification::*;
+
+pub mod update_company_metadata;
+pub use update_company_metadata::*;
+
+pub mod migrate_company_profile;
+pub use migrate_company_profile::*;
+
+pub mod migrate_funding_round;
+pub use migrate_funding_round::*;
+
+pub mod register_company_profile;
+pub use register_company_profile::*;
+
+pub mod close_company_profile;
+pub use close_company_profile::*;
+
+pub mod sweep_vault;
+pub use sweep_vault::*;
+
+pub mod set_active_round_limit;
+pub use set_active_round_limit::*;
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, OWNER_REGISTRY_SEED, COMPANY_INDEX_SEED},
+    error::ErrorCode,
+    state::{CompanyIndex, CompanyProfile, OwnerRegistry},
+};
+
+/// Lists a profile created before the owner registry existed under its owner.
+pub(crate) fn handler(ctx: Context<RegisterCompanyProfile>) -> Result<()> {
+    require!(
+        !ctx.accounts.company_profile.registered,
+        ErrorCode::CompanyAlreadyRegistered
+    );
+
+    let owner_registry = &mut ctx.accounts.owner_registry;
+    owner_registry.owner = ctx.accounts.owner.key();
+    owner_registry.bump = ctx.bumps.owner_registry;
+    owner_registry.register(&mut ctx.accounts.company_index, ctx.accounts.company_profile.key())?;
+    ctx.accounts.company_index.bump = ctx.bumps.company_index;
+    ctx.accounts.company_profile.registered = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterCompanyProfile<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner)]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + OwnerRegistry::INIT_SPACE,
+        seeds = [OWNER_REGISTRY_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub owner_registry: Account<'info, OwnerRegistry>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + CompanyIndex::INIT_SPACE,
+        seeds = [COMPANY_INDEX_SEED, owner.key().as_ref(), owner_registry.company_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub company_index: Account<'info, CompanyIndex>,
+    pub system_program: Program<'info, System>,
+}
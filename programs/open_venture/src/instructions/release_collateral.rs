@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::{
+    constants::{COLLATERAL_VAULT_SEED, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, FundingRoundStatus},
+    utils::transfer_collateral_tokens,
+};
+
+pub(crate) fn handler(ctx: Context<ReleaseCollateral>) -> Result<()> {
+    require!(
+        ctx.accounts.funding_round.collateral_amount > 0,
+        ErrorCode::NoCollateral
+    );
+    // collateral only goes back to the owner once investors no longer depend on it: the round was
+    // repaid, or it ended without capital leaving the vault so refunds make investors whole
+    let funding_round = &ctx.accounts.funding_round;
+    let releasable = match funding_round.status {
+        FundingRoundStatus::Repaid => true,
+        FundingRoundStatus::Failed | FundingRoundStatus::Cancelled => funding_round.total_withdrawn == 0,
+        _ => false,
+    };
+    require!(releasable, ErrorCode::CollateralNotReleasable);
+
+    match ctx.accounts.funding_round.collateral_mint {
+        Some(collateral_mint) => {
+            let (Some(owner_token_account), Some(token_program)) = (
+                ctx.accounts.owner_token_account.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingTokenAccounts);
+            };
+            require!(
+                owner_token_account.mint == collateral_mint,
+                ErrorCode::CollateralMintMismatch
+            );
+            let vault_balance = TokenAccount::try_deserialize(
+                &mut &ctx.accounts.collateral_vault.try_borrow_data()?[..],
+            )?
+            .amount;
+            transfer_collateral_tokens(
+                &ctx.accounts.funding_round,
+                &ctx.accounts.collateral_vault.to_account_info(),
+                &owner_token_account.to_account_info(),
+                &token_program.to_account_info(),
+                vault_balance,
+            )?;
+        }
+        None => {
+            // drain the vault entirely, which also returns its rent to the owner
+            let vault_lamports = ctx.accounts.collateral_vault.lamports();
+            **ctx.accounts
+                .collateral_vault
+                .try_borrow_mut_lamports()
+                .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= vault_lamports;
+            **ctx.accounts
+                .owner
+                .try_borrow_mut_lamports()
+                .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += vault_lamports;
+        }
+    }
+
+    ctx.accounts.funding_round.collateral_amount = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReleaseCollateral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner)]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// CHECK: Collateral vault PDA, either a lamport vault or a token account depending on the collateral
+    #[account(
+        mut,
+        seeds = [COLLATERAL_VAULT_SEED, funding_round.key().as_ref()],
+        bump = funding_round.collateral_vault_bump,
+    )]
+    pub collateral_vault: AccountInfo<'info>,
+    /// Destination for SPL token collateral, not needed for SOL collateral
+    #[account(mut)]
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PROGRAM_CONFIG_SEED, COMPANY_VERIFIER_SEED},
+    error::ErrorCode,
+    state::{CompanyVerifier, ProgramConfig},
+};
+
+pub(crate) fn handler(_ctx: Context<RemoveCompanyVerifier>) -> Result<()> {
+    // the verifier account is closed by the `close` constraint; verifications it already issued stay
+    // in place until they are revoked individually
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveCompanyVerifier<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [COMPANY_VERIFIER_SEED, company_verifier.authority.as_ref()],
+        bump = company_verifier.bump,
+    )]
+    pub company_verifier: Account<'info, CompanyVerifier>,
+}
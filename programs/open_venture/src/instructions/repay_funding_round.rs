@@ -1,36 +1,28 @@
 use anchor_lang::prelude::*;
 use crate::{
+    constants::{
+        COMPANY_PROFILE_VERSION, COMPANY_REPUTATION_SEED, COMPANY_TREASURY_SEED,
+        FUNDING_ROUND_VERSION, REPAYMENT_VAULT_SEED, TREASURY_LEDGER_SEED,
+    },
     error::ErrorCode,
-    state::{CompanyProfile, FundingRound},
-    utils::validate_repayment_vault_access,
+    state::{
+        CompanyProfile, CompanyReputation, FundingRound, FundingRoundStatus, RoundVault,
+        TreasuryLedger,
+    },
 };
 
-pub fn handler(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
-    // the expression below evaluates to: target_amount * (1 + interest_rate / 100)
-    let total_with_interest = ctx.accounts.funding_round.target_amount
-        .checked_mul(100u64 + ctx.accounts.funding_round.interest_rate)
-        .and_then(|v| v.checked_div(100))
-        .ok_or(ErrorCode::MathOverflow)?;
-
+pub(crate) fn handler(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
     require!(
-        amount == total_with_interest,
-        ErrorCode::RepaymentAmountExceedsTargetAmount
+        ctx.accounts.funding_round.status == FundingRoundStatus::Funded,
+        ErrorCode::FundingRoundNotFunded
     );
 
-    // ensure it's the company owner who is repaying the funding round
-    // ensure details match the repayment vault PDA
-    validate_repayment_vault_access(
-        &ctx.accounts.owner,
-        &ctx.accounts.company_profile,
-        &ctx.accounts.funding_round.id,
-        &ctx.accounts.repayment_vault,
-        ctx.program_id,
-    )?;
+    // target_amount * (1 + interest_rate / 100) plus any extension fees
+    let total_with_interest = ctx.accounts.funding_round.total_repayment_due()?;
 
-    // ensure funding round belongs to company profile
     require!(
-        ctx.accounts.funding_round.company == ctx.accounts.company_profile.key(),
-        ErrorCode::FundingRoundCompanyMismatch
+        amount == total_with_interest,
+        ErrorCode::RepaymentAmountExceedsTargetAmount
     );
 
     // ensure treasury has enough lamports
@@ -39,6 +31,14 @@ pub fn handler(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
         treasury_lamports >= total_with_interest,
         ErrorCode::InsufficientVaultFunds
     );
+    // capital withdrawn from the company's other rounds stays in the treasury for them
+    let committed_to_other_rounds = ctx.accounts.company_profile.treasury_outstanding
+        .checked_sub(ctx.accounts.treasury_ledger.outstanding())
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        treasury_lamports.saturating_sub(committed_to_other_rounds) >= total_with_interest,
+        ErrorCode::TreasuryFundsCommittedToOtherRounds
+    );
 
     // move lamports directly between PDAs owned by this program
     **ctx.accounts
@@ -47,8 +47,26 @@ pub fn handler(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
         .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= amount;
     **ctx.accounts
         .repayment_vault
+        .to_account_info()
         .try_borrow_mut_lamports()
         .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += amount;
+    ctx.accounts.repayment_vault.credit(amount)?;
+    ctx.accounts.treasury_ledger.record_repayment(&mut ctx.accounts.company_profile, amount)?;
+
+    ctx.accounts.company_reputation.record_repayment(
+        ctx.accounts.funding_round.total_raised,
+        amount,
+        ctx.accounts.funding_round.repayment_deadline,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+
+    // a repaid round is finished, which frees the company to raise again
+    ctx.accounts.funding_round.total_repaid = amount;
+    ctx.accounts.funding_round.status = FundingRoundStatus::Repaid;
+    ctx.accounts.funding_round.is_active = false;
+    ctx.accounts.company_profile.active_round_count = ctx.accounts.company_profile.active_round_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::MathOverflow)?;
 
     Ok(())
 }
@@ -57,18 +75,44 @@ pub fn handler(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
 pub struct RepayFundingRound<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
-    #[account(mut, has_one = owner)]
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
     pub company_profile: Account<'info, CompanyProfile>,
     #[account(
         mut,
-        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
     )]
     pub funding_round: Account<'info, FundingRound>,
-    /// CHECK: Company treasury PDA validated in the handler
-    #[account(mut)]
+    /// CHECK: Company treasury PDA the repayment is paid from
+    #[account(
+        mut,
+        seeds = [COMPANY_TREASURY_SEED, owner.key().as_ref(), company_profile.key().as_ref()],
+        bump = company_profile.treasury_bump,
+    )]
     pub company_treasury: AccountInfo<'info>,
-    /// CHECK: Repayment vault PDA validated in the handler
-    #[account(mut)]
-    pub repayment_vault: AccountInfo<'info>,
+    /// Repayment vault investors claim from
+    #[account(
+        mut,
+        seeds = [REPAYMENT_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.repayment_vault_bump,
+    )]
+    pub repayment_vault: Account<'info, RoundVault>,
+    /// The treasury's record of what moves between it and this round
+    #[account(
+        mut,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump = funding_round.treasury_ledger_bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+    #[account(
+        mut,
+        seeds = [COMPANY_REPUTATION_SEED, company_profile.key().as_ref()],
+        bump = company_reputation.bump,
+    )]
+    pub company_reputation: Account<'info, CompanyReputation>,
 }
 
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        AMENDMENT_SEED, ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION,
+        ROUND_AMENDMENTS_SEED, PROGRAM_CONFIG_SEED,
+    },
+    error::ErrorCode,
+    state::{
+        Amendment, AmendmentKind, AmendmentStatus, CompanyProfile, FundingRound,
+        FundingRoundStatus, ProgramConfig, RoundAmendments,
+    },
+};
+
+pub(crate) fn handler(
+    ctx: Context<RequestExtension>,
+    new_repayment_deadline: u64,
+    extension_fee: u64,
+    rate_bump: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let funding_round = &ctx.accounts.funding_round;
+    require!(
+        funding_round.is_active
+            && matches!(
+                funding_round.status,
+                FundingRoundStatus::Open | FundingRoundStatus::Funded
+            ),
+        ErrorCode::FundingRoundNotActive
+    );
+    // a round past its deadline is in default and is left to `mark_round_defaulted`
+    require!(
+        now as u64 <= funding_round.repayment_deadline,
+        ErrorCode::RepaymentDeadlinePassed
+    );
+    require!(
+        new_repayment_deadline > funding_round.repayment_deadline,
+        ErrorCode::ExtensionMustExtendDeadline
+    );
+
+    let amendment = &mut ctx.accounts.amendment;
+    amendment.kind = AmendmentKind::Extension;
+    amendment.target_amount = funding_round.target_amount;
+    amendment.interest_rate = funding_round.interest_rate
+        .checked_add(rate_bump)
+        .ok_or(ErrorCode::MathOverflow)?;
+    amendment.repayment_deadline = new_repayment_deadline;
+    amendment.extension_fee = extension_fee;
+    amendment.proposed_at = now;
+    amendment.voting_base = funding_round.total_raised;
+    amendment.approved_amount = 0;
+    amendment.status = AmendmentStatus::Pending;
+
+    // short extensions are granted without a vote, up to the admin-configured number per round
+    let config = &ctx.accounts.config;
+    let extension = new_repayment_deadline - funding_round.repayment_deadline;
+    let funding_round = &mut ctx.accounts.funding_round;
+    if extension <= config.max_auto_extension && funding_round.auto_extensions < config.max_auto_extensions {
+        funding_round.apply_amendment(amendment)?;
+        funding_round.auto_extensions += 1;
+        amendment.status = AmendmentStatus::Applied;
+    }
+
+    let round_amendments = &mut ctx.accounts.round_amendments;
+    round_amendments.funding_round = funding_round.key();
+    round_amendments.propose(amendment, ctx.accounts.latest_amendment.as_deref_mut())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestExtension<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + RoundAmendments::INIT_SPACE,
+        seeds = [ROUND_AMENDMENTS_SEED, funding_round.key().as_ref()],
+        bump,
+    )]
+    pub round_amendments: Account<'info, RoundAmendments>,
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + Amendment::INIT_SPACE,
+        seeds = [
+            AMENDMENT_SEED,
+            funding_round.key().as_ref(),
+            round_amendments.amendment_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub amendment: Account<'info, Amendment>,
+    /// The round's latest amendment, superseded by this one if investors have not approved it yet
+    #[account(mut)]
+    pub latest_amendment: Option<Account<'info, Amendment>>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::state::InvestorAttestation;
+
+pub(crate) fn handler(_ctx: Context<RevokeAttestation>) -> Result<()> {
+    // the attestation account is closed by the `close` constraint, returning rent to the verifier
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeAttestation<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+    #[account(
+        mut,
+        close = verifier,
+        has_one = verifier,
+    )]
+    pub attestation: Account<'info, InvestorAttestation>,
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PROGRAM_CONFIG_SEED, COMPANY_VERIFIER_SEED, COMPANY_VERIFICATION_SEED},
+    state::{CompanyVerification, CompanyVerifier, ProgramConfig},
+    utils::validate_company_verifier,
+};
+
+pub(crate) fn handler(ctx: Context<RevokeCompanyVerification>) -> Result<()> {
+    validate_company_verifier(
+        &ctx.accounts.verifier.key(),
+        &ctx.accounts.config,
+        &ctx.accounts.company_verifier,
+    )?;
+    // the verification account is closed by the `close` constraint, returning rent to the revoker
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeCompanyVerification<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    /// Required unless the verifier is the program admin
+    #[account(
+        seeds = [COMPANY_VERIFIER_SEED, verifier.key().as_ref()],
+        bump = company_verifier.bump,
+    )]
+    pub company_verifier: Option<Account<'info, CompanyVerifier>>,
+    #[account(
+        mut,
+        close = verifier,
+        seeds = [COMPANY_VERIFICATION_SEED, company_verification.company.as_ref()],
+        bump = company_verification.bump,
+    )]
+    pub company_verification: Account<'info, CompanyVerification>,
+}
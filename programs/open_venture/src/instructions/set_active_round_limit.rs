@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{COMPANY_PROFILE_VERSION, PROGRAM_CONFIG_SEED},
+    error::ErrorCode,
+    state::{CompanyProfile, ProgramConfig},
+};
+
+pub(crate) fn handler(ctx: Context<SetActiveRoundLimit>, max_active_rounds: u8) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.company_profile.owner || authority == ctx.accounts.config.admin,
+        ErrorCode::UnauthorizedRoundLimit
+    );
+    // a limit below the rounds already active only stops new rounds until enough of them end
+    require!(
+        max_active_rounds > 0 && max_active_rounds <= ctx.accounts.config.max_active_rounds,
+        ErrorCode::InvalidActiveRoundLimit
+    );
+
+    ctx.accounts.company_profile.max_active_rounds = max_active_rounds;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetActiveRoundLimit<'info> {
+    pub authority: Signer<'info>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound},
+};
+
+pub(crate) fn handler(
+    ctx: Context<SetContributionLimits>,
+    min_contribution: u64,
+    max_contribution: u64,
+) -> Result<()> {
+    require!(ctx.accounts.funding_round.is_active, ErrorCode::FundingRoundNotActive);
+    // limits are part of the round's terms, so they cannot change once investors have deposited
+    require!(
+        ctx.accounts.funding_round.total_raised == 0,
+        ErrorCode::FundingRoundHasDeposits
+    );
+    // a maximum of 0 means investors are not capped
+    require!(
+        max_contribution == 0 || max_contribution >= min_contribution,
+        ErrorCode::InvalidContributionLimits
+    );
+
+    ctx.accounts.funding_round.min_contribution = min_contribution;
+    ctx.accounts.funding_round.max_contribution = max_contribution;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetContributionLimits<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{COMPANY_PROFILE_VERSION, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound},
+};
+
+pub(crate) fn handler(
+    ctx: Context<SetInvestorGate>,
+    allowlist_root: Option<[u8; 32]>,
+    kyc_verifier: Option<Pubkey>,
+) -> Result<()> {
+    require!(ctx.accounts.funding_round.is_active, ErrorCode::FundingRoundNotActive);
+
+    // with neither gate set the round is open to any investor
+    ctx.accounts.funding_round.allowlist_root = allowlist_root;
+    ctx.accounts.funding_round.kyc_verifier = kyc_verifier;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetInvestorGate<'info> {
+    pub owner: Signer<'info>,
+    #[account(
+        has_one = owner @ ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        COMPANY_PROFILE_VERSION, COMPANY_TREASURY_SEED, FUNDING_ROUND_VERSION, TREASURY_LEDGER_SEED,
+    },
+    error::ErrorCode,
+    state::{CompanyProfile, FundingRound, RoundVault, TreasuryLedger},
+};
+
+pub(crate) fn handler(ctx: Context<SweepVault>) -> Result<()> {
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(vault_info.data_len());
+    let surplus = ctx.accounts.vault.surplus(vault_info.lamports(), rent_exempt_minimum)?;
+    require!(surplus > 0, ErrorCode::NothingToSweep);
+
+    // lamports nobody paid in through the program belong to the company, not to investors
+    **vault_info
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= surplus;
+    **ctx.accounts
+        .company_treasury
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += surplus;
+    ctx.accounts.treasury_ledger.record_sweep(surplus)?;
+
+    Ok(())
+}
+
+/// Permissionless: the surplus can only go to the company treasury.
+#[derive(Accounts)]
+pub struct SweepVault<'info> {
+    #[account(
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
+    )]
+    pub funding_round: Account<'info, FundingRound>,
+    /// Either of the round's vaults; only the program writes vault headers, so the header
+    /// naming the round is enough to tie the vault to it
+    #[account(
+        mut,
+        has_one = funding_round @ ErrorCode::FundingRoundCompanyMismatch,
+    )]
+    pub vault: Account<'info, RoundVault>,
+    /// CHECK: Company treasury PDA receiving the surplus
+    #[account(
+        mut,
+        seeds = [COMPANY_TREASURY_SEED, company_profile.owner.as_ref(), company_profile.key().as_ref()],
+        bump = company_profile.treasury_bump,
+    )]
+    pub company_treasury: AccountInfo<'info>,
+    /// The treasury's record of what moves between it and the round
+    #[account(
+        mut,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump = funding_round.treasury_ledger_bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
+}
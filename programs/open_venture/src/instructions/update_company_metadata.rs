@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::ANCHOR_DISCRIMINATOR,
+    error::ErrorCode,
+    state::{CompanyCategory, CompanyProfile},
+    utils::grow_account,
+};
+
+pub(crate) fn handler(
+    ctx: Context<UpdateCompanyMetadata>,
+    metadata_uri: String,
+    category: Option<CompanyCategory>,
+    country_code: Option<[u8; 2]>,
+    document_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    require!(metadata_uri.len() <= 200, ErrorCode::MetadataUriTooLong);
+    if let Some(country_code) = country_code {
+        require!(
+            country_code.iter().all(u8::is_ascii_uppercase),
+            ErrorCode::InvalidCountryCode
+        );
+    }
+
+    let company_profile_info = ctx.accounts.company_profile.to_account_info();
+    // grow first: a profile from before metadata existed whose name and bio fill its old layout
+    // only decodes once the metadata fields can be read from the zeroed tail
+    grow_account(
+        &company_profile_info,
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ANCHOR_DISCRIMINATOR + CompanyProfile::INIT_SPACE,
+    )?;
+
+    let mut company_profile =
+        CompanyProfile::try_deserialize(&mut &company_profile_info.try_borrow_data()?[..])?;
+    require!(
+        company_profile.owner == ctx.accounts.owner.key(),
+        ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner
+    );
+    company_profile.metadata_uri = metadata_uri;
+    company_profile.category = category;
+    company_profile.country_code = country_code;
+    company_profile.document_hash = document_hash;
+    company_profile.try_serialize(&mut &mut company_profile_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateCompanyMetadata<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: Loaded by hand because profiles created before metadata existed may not deserialize
+    /// until they have been grown to the current layout, with the owner paying for the extra rent;
+    /// the discriminator is checked when the profile is deserialized.
+    #[account(mut, owner = crate::ID)]
+    pub company_profile: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
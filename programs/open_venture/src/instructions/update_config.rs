@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::{BASIS_POINTS, PROGRAM_CONFIG_SEED}, error::ErrorCode, state::ProgramConfig};
+
+pub(crate) fn handler(
+    ctx: Context<UpdateConfig>,
+    max_auto_extension: u64,
+    max_auto_extensions: u8,
+    liquidation_grace_period: u64,
+    keeper_reward_bps: u16,
+    unproven_round_cap: u64,
+    max_active_rounds: u8,
+) -> Result<()> {
+    require!(
+        keeper_reward_bps as u64 <= BASIS_POINTS,
+        ErrorCode::InvalidKeeperReward
+    );
+    require!(max_active_rounds > 0, ErrorCode::InvalidActiveRoundLimit);
+
+    let config = &mut ctx.accounts.config;
+    config.max_auto_extension = max_auto_extension;
+    config.max_auto_extensions = max_auto_extensions;
+    config.liquidation_grace_period = liquidation_grace_period;
+    config.keeper_reward_bps = keeper_reward_bps;
+    config.unproven_round_cap = unproven_round_cap;
+    config.max_active_rounds = max_active_rounds;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PROGRAM_CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+}
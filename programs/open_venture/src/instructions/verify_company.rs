@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, PROGRAM_CONFIG_SEED, COMPANY_VERIFIER_SEED,
+        COMPANY_VERIFICATION_SEED,
+    },
+    error::ErrorCode,
+    state::{CompanyProfile, CompanyVerification, CompanyVerifier, ProgramConfig},
+    utils::validate_company_verifier,
+};
+
+pub(crate) fn handler(
+    ctx: Context<VerifyCompany>,
+    legal_name: String,
+    jurisdiction: String,
+    registration_hash: [u8; 32],
+) -> Result<()> {
+    validate_company_verifier(
+        &ctx.accounts.verifier.key(),
+        &ctx.accounts.config,
+        &ctx.accounts.company_verifier,
+    )?;
+    require!(!legal_name.is_empty(), ErrorCode::LegalNameRequired);
+    require!(legal_name.len() <= 64, ErrorCode::LegalNameTooLong);
+    require!(!jurisdiction.is_empty(), ErrorCode::JurisdictionRequired);
+    require!(jurisdiction.len() <= 32, ErrorCode::JurisdictionTooLong);
+
+    let verification = &mut ctx.accounts.company_verification;
+    verification.company = ctx.accounts.company_profile.key();
+    verification.verifier = ctx.accounts.verifier.key();
+    verification.legal_name = legal_name;
+    verification.jurisdiction = jurisdiction;
+    verification.registration_hash = registration_hash;
+    verification.verified_at = Clock::get()?.unix_timestamp;
+    verification.bump = ctx.bumps.company_verification;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VerifyCompany<'info> {
+    #[account(mut)]
+    pub verifier: Signer<'info>,
+    #[account(seeds = [PROGRAM_CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    /// Required unless the verifier is the program admin
+    #[account(
+        seeds = [COMPANY_VERIFIER_SEED, verifier.key().as_ref()],
+        bump = company_verifier.bump,
+    )]
+    pub company_verifier: Option<Account<'info, CompanyVerifier>>,
+    pub company_profile: Account<'info, CompanyProfile>,
+    #[account(
+        init,
+        payer = verifier,
+        space = ANCHOR_DISCRIMINATOR + CompanyVerification::INIT_SPACE,
+        seeds = [COMPANY_VERIFICATION_SEED, company_profile.key().as_ref()],
+        bump,
+    )]
+    pub company_verification: Account<'info, CompanyVerification>,
+    pub system_program: Program<'info, System>,
+}
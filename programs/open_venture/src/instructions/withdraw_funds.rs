@@ -1,45 +1,31 @@
 use anchor_lang::prelude::*;
 use crate::{
+    constants::{
+        COMPANY_PROFILE_VERSION, COMPANY_TREASURY_SEED, FUNDING_ROUND_VAULT_SEED,
+        FUNDING_ROUND_VERSION, TREASURY_LEDGER_SEED,
+    },
     error::ErrorCode,
-    state::{CompanyProfile, FundingRound},
-    utils::{validate_vault_access, validate_company_treasury_access},
+    state::{CompanyProfile, FundingRound, FundingRoundStatus, RoundVault, TreasuryLedger},
 };
 
-pub fn handler(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
-    // Validate that only the company owner can access the vault
-    validate_vault_access(
-        &ctx.accounts.owner,
-        &ctx.accounts.company_profile,
-        &ctx.accounts.vault,
-        &ctx.accounts.funding_round.id,
-        ctx.program_id,
-    )?;
-
-    // Check that the funding round belongs to the company
+pub(crate) fn handler(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
+    // capital stays in the vault until the round closes funded: an open round may still fail and
+    // owe refunds, and ended rounds have nothing left to withdraw
     require!(
-        ctx.accounts.funding_round.company == ctx.accounts.company_profile.key(),
-        ErrorCode::FundingRoundCompanyMismatch
+        ctx.accounts.funding_round.status == FundingRoundStatus::Funded,
+        ErrorCode::FundingRoundNotFunded
     );
 
-    // Check vault balance
-    let vault_lamports = ctx.accounts.vault.lamports();
-    // check that the vault has enough funds to withdraw
-    require!(vault_lamports >= amount, ErrorCode::InsufficientVaultFunds);
+    // only tracked deposits can be withdrawn, never the vault's rent or stray transfers
+    ctx.accounts.vault.debit(amount)?;
 
     // check that the withdrawal amount is greater than zero
     require!(amount > 0, ErrorCode::WithdrawalAmountMustBeGreaterThanZero);
 
-    // Validate that only the company owner can access the company treasury vault
-    validate_company_treasury_access(
-        &ctx.accounts.owner,
-        &ctx.accounts.company_profile,
-        &ctx.accounts.company_treasury,
-        ctx.program_id,
-    )?;
-
     // Transfer funds from funding round vault to company treasury vault by directly mutating lamports
     **ctx.accounts
         .vault
+        .to_account_info()
         .try_borrow_mut_lamports()
         .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= amount;
     **ctx.accounts
@@ -47,6 +33,11 @@ pub fn handler(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
         .try_borrow_mut_lamports()
         .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += amount;
 
+    ctx.accounts.funding_round.total_withdrawn = ctx.accounts.funding_round.total_withdrawn
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    ctx.accounts.treasury_ledger.record_withdrawal(&mut ctx.accounts.company_profile, amount)?;
+
     Ok(())
 }
 
@@ -55,19 +46,36 @@ pub struct WithdrawFunds<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
     #[account(
-        has_one = owner @ ErrorCode::UnauthorizedVaultAccess
+        mut,
+        has_one = owner @ ErrorCode::UnauthorizedVaultAccess,
+        constraint = company_profile.version >= COMPANY_PROFILE_VERSION @ ErrorCode::AccountNotMigrated,
     )]
     pub company_profile: Account<'info, CompanyProfile>,
     #[account(
-        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch
+        mut,
+        constraint = funding_round.company == company_profile.key() @ ErrorCode::FundingRoundCompanyMismatch,
+        constraint = funding_round.version >= FUNDING_ROUND_VERSION @ ErrorCode::AccountNotMigrated,
     )]
     pub funding_round: Account<'info, FundingRound>,
-    /// CHECK: Vault PDA is validated in handler via validate_vault_access
-    #[account(mut)]
-    pub vault: AccountInfo<'info>,
-    /// CHECK: Company treasury vault PDA is validated in handler via validate_company_treasury_access
-    /// Only the company owner can access this vault
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [FUNDING_ROUND_VAULT_SEED, company_profile.key().as_ref(), funding_round.round_seed().as_ref()],
+        bump = funding_round.vault_bump,
+    )]
+    pub vault: Account<'info, RoundVault>,
+    /// CHECK: Company treasury PDA, which only the owner's signature can move funds into or out of
+    #[account(
+        mut,
+        seeds = [COMPANY_TREASURY_SEED, owner.key().as_ref(), company_profile.key().as_ref()],
+        bump = company_profile.treasury_bump,
+    )]
     pub company_treasury: AccountInfo<'info>,
+    /// The treasury's record of what moves between it and this round
+    #[account(
+        mut,
+        seeds = [TREASURY_LEDGER_SEED, funding_round.key().as_ref()],
+        bump = funding_round.treasury_ledger_bump,
+    )]
+    pub treasury_ledger: Account<'info, TreasuryLedger>,
     pub system_program: Program<'info, System>,
 }
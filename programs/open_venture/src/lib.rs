@@ -13,43 +13,260 @@ pub use utils::*;
 
 declare_id!("C6SFwFPjwGPdKHF8yKH9BypyYi5eysVPK7S8WUuvbAiE");
 
-#[program]
-pub mod open_venture {
+pub use program_interface::*;
+
+// `#[program]` generates the IDL instructions next to the program module, and they resize the
+// IDL account with the deprecated `AccountInfo::realloc`
+#[allow(deprecated)]
+mod program_interface {
     use super::*;
 
-    pub fn create_company_profile(
-        ctx: Context<CreateCompanyProfile>,
-        name: String,
-        bio: String,
-    ) -> Result<()> {
-        instructions::create_company_profile::handler(ctx, name, bio)
-    }
+    #[program]
+    pub mod open_venture {
+        use super::*;
 
-    pub fn create_funding_round(
-        ctx: Context<CreateFundingRound>,
-        round_id: String,
-        target_amount: u64,
-        interest_rate: u64,
-        repayment_deadline: u64,
-    ) -> Result<()> {
-        instructions::create_funding_round::handler(
-            ctx,
-            round_id,
-            target_amount,
-            interest_rate,
-            repayment_deadline,
-        )
-    }
+        pub fn initialize_config(
+            ctx: Context<InitializeConfig>,
+            max_auto_extension: u64,
+            max_auto_extensions: u8,
+            liquidation_grace_period: u64,
+            keeper_reward_bps: u16,
+            unproven_round_cap: u64,
+            max_active_rounds: u8,
+        ) -> Result<()> {
+            instructions::initialize_config::handler(
+                ctx,
+                max_auto_extension,
+                max_auto_extensions,
+                liquidation_grace_period,
+                keeper_reward_bps,
+                unproven_round_cap,
+                max_active_rounds,
+            )
+        }
 
-    pub fn fund_company(ctx: Context<FundCompany>, amount: u64) -> Result<()> {
-        instructions::fund_company::handler(ctx, amount)
-    }
+        pub fn update_config(
+            ctx: Context<UpdateConfig>,
+            max_auto_extension: u64,
+            max_auto_extensions: u8,
+            liquidation_grace_period: u64,
+            keeper_reward_bps: u16,
+            unproven_round_cap: u64,
+            max_active_rounds: u8,
+        ) -> Result<()> {
+            instructions::update_config::handler(
+                ctx,
+                max_auto_extension,
+                max_auto_extensions,
+                liquidation_grace_period,
+                keeper_reward_bps,
+                unproven_round_cap,
+                max_active_rounds,
+            )
+        }
 
-    pub fn withdraw_funds(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
-        instructions::withdraw_funds::handler(ctx, amount)
-    }
+        pub fn create_company_profile(
+            ctx: Context<CreateCompanyProfile>,
+            name: String,
+            bio: String,
+        ) -> Result<()> {
+            instructions::create_company_profile::handler(ctx, name, bio)
+        }
+
+        pub fn register_company_profile(ctx: Context<RegisterCompanyProfile>) -> Result<()> {
+            instructions::register_company_profile::handler(ctx)
+        }
+
+        pub fn close_company_profile(ctx: Context<CloseCompanyProfile>) -> Result<()> {
+            instructions::close_company_profile::handler(ctx)
+        }
+
+        pub fn update_company_metadata(
+            ctx: Context<UpdateCompanyMetadata>,
+            metadata_uri: String,
+            category: Option<CompanyCategory>,
+            country_code: Option<[u8; 2]>,
+            document_hash: Option<[u8; 32]>,
+        ) -> Result<()> {
+            instructions::update_company_metadata::handler(
+                ctx,
+                metadata_uri,
+                category,
+                country_code,
+                document_hash,
+            )
+        }
+
+        pub fn migrate_company_profile(ctx: Context<MigrateCompanyProfile>) -> Result<()> {
+            instructions::migrate_company_profile::handler(ctx)
+        }
+
+        pub fn migrate_funding_round(ctx: Context<MigrateFundingRound>) -> Result<()> {
+            instructions::migrate_funding_round::handler(ctx)
+        }
+
+        pub fn set_active_round_limit(
+            ctx: Context<SetActiveRoundLimit>,
+            max_active_rounds: u8,
+        ) -> Result<()> {
+            instructions::set_active_round_limit::handler(ctx, max_active_rounds)
+        }
+
+        pub fn create_funding_round(
+            ctx: Context<CreateFundingRound>,
+            label: String,
+            target_amount: u64,
+            interest_rate: u64,
+            repayment_deadline: u64,
+            funding_start: u64,
+            funding_end: u64,
+        ) -> Result<()> {
+            instructions::create_funding_round::handler(
+                ctx,
+                label,
+                target_amount,
+                interest_rate,
+                repayment_deadline,
+                funding_start,
+                funding_end,
+            )
+        }
+
+        pub fn set_contribution_limits(
+            ctx: Context<SetContributionLimits>,
+            min_contribution: u64,
+            max_contribution: u64,
+        ) -> Result<()> {
+            instructions::set_contribution_limits::handler(ctx, min_contribution, max_contribution)
+        }
+
+        pub fn set_investor_gate(
+            ctx: Context<SetInvestorGate>,
+            allowlist_root: Option<[u8; 32]>,
+            kyc_verifier: Option<Pubkey>,
+        ) -> Result<()> {
+            instructions::set_investor_gate::handler(ctx, allowlist_root, kyc_verifier)
+        }
+
+        pub fn issue_attestation(ctx: Context<IssueAttestation>, expires_at: i64) -> Result<()> {
+            instructions::issue_attestation::handler(ctx, expires_at)
+        }
+
+        pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+            instructions::revoke_attestation::handler(ctx)
+        }
+
+        pub fn add_company_verifier(ctx: Context<AddCompanyVerifier>) -> Result<()> {
+            instructions::add_company_verifier::handler(ctx)
+        }
+
+        pub fn remove_company_verifier(ctx: Context<RemoveCompanyVerifier>) -> Result<()> {
+            instructions::remove_company_verifier::handler(ctx)
+        }
+
+        pub fn verify_company(
+            ctx: Context<VerifyCompany>,
+            legal_name: String,
+            jurisdiction: String,
+            registration_hash: [u8; 32],
+        ) -> Result<()> {
+            instructions::verify_company::handler(ctx, legal_name, jurisdiction, registration_hash)
+        }
+
+        pub fn revoke_company_verification(ctx: Context<RevokeCompanyVerification>) -> Result<()> {
+            instructions::revoke_company_verification::handler(ctx)
+        }
+
+        pub fn fund_company(
+            ctx: Context<FundCompany>,
+            amount: u64,
+            allowlist_proof: Vec<[u8; 32]>,
+        ) -> Result<()> {
+            instructions::fund_company::handler(ctx, amount, allowlist_proof)
+        }
+
+        pub fn finalize_round(ctx: Context<FinalizeRound>) -> Result<()> {
+            instructions::finalize_round::handler(ctx)
+        }
+
+        pub fn amend_funding_round(
+            ctx: Context<AmendFundingRound>,
+            target_amount: u64,
+            interest_rate: u64,
+            repayment_deadline: u64,
+        ) -> Result<()> {
+            instructions::amend_funding_round::handler(
+                ctx,
+                target_amount,
+                interest_rate,
+                repayment_deadline,
+            )
+        }
+
+        pub fn approve_amendment(ctx: Context<ApproveAmendment>, amendment_index: u32) -> Result<()> {
+            instructions::approve_amendment::handler(ctx, amendment_index)
+        }
+
+        pub fn request_extension(
+            ctx: Context<RequestExtension>,
+            new_repayment_deadline: u64,
+            extension_fee: u64,
+            rate_bump: u64,
+        ) -> Result<()> {
+            instructions::request_extension::handler(
+                ctx,
+                new_repayment_deadline,
+                extension_fee,
+                rate_bump,
+            )
+        }
+
+        pub fn cancel_funding_round(ctx: Context<CancelFundingRound>) -> Result<()> {
+            instructions::cancel_funding_round::handler(ctx)
+        }
+
+        pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+            instructions::claim_refund::handler(ctx)
+        }
+
+        pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+            instructions::deposit_collateral::handler(ctx, amount)
+        }
+
+        pub fn deposit_token_collateral(ctx: Context<DepositTokenCollateral>, amount: u64) -> Result<()> {
+            instructions::deposit_token_collateral::handler(ctx, amount)
+        }
+
+        pub fn release_collateral(ctx: Context<ReleaseCollateral>) -> Result<()> {
+            instructions::release_collateral::handler(ctx)
+        }
+
+        pub fn claim_collateral(ctx: Context<ClaimCollateral>) -> Result<()> {
+            instructions::claim_collateral::handler(ctx)
+        }
+
+        pub fn liquidate_collateral(ctx: Context<LiquidateCollateral>) -> Result<()> {
+            instructions::liquidate_collateral::handler(ctx)
+        }
+
+        pub fn mark_round_defaulted(ctx: Context<MarkRoundDefaulted>) -> Result<()> {
+            instructions::mark_round_defaulted::handler(ctx)
+        }
+
+        pub fn claim_repayment(ctx: Context<ClaimRepayment>) -> Result<()> {
+            instructions::claim_repayment::handler(ctx)
+        }
+
+        pub fn withdraw_funds(ctx: Context<WithdrawFunds>, amount: u64) -> Result<()> {
+            instructions::withdraw_funds::handler(ctx, amount)
+        }
+
+        pub fn repay_funding_round(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
+            instructions::repay_funding_round::handler(ctx, amount)
+        }
 
-    pub fn repay_funding_round(ctx: Context<RepayFundingRound>, amount: u64) -> Result<()> {
-        instructions::repay_funding_round::handler(ctx, amount)
+        pub fn sweep_vault(ctx: Context<SweepVault>) -> Result<()> {
+            instructions::sweep_vault::handler(ctx)
+        }
     }
 }
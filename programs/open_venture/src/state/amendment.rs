@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Counts the amendments proposed for a funding round; they are kept by the `Amendment`
+/// accounts at indices `0..amendment_count`.
+#[account]
+#[derive(InitSpace)]
+pub struct RoundAmendments {
+    pub funding_round: Pubkey,
+    pub amendment_count: u32,
+}
+
+impl RoundAmendments {
+    /// Records a new proposal at the next free index. Only the latest amendment can still be
+    /// pending, so the proposal supersedes it unless investors approved it already.
+    pub fn propose(&mut self, amendment: &mut Amendment, latest: Option<&mut Amendment>) -> Result<()> {
+        if let Some(latest_index) = self.amendment_count.checked_sub(1) {
+            let latest = latest.ok_or(ErrorCode::LatestAmendmentMismatch)?;
+            require!(
+                latest.funding_round == self.funding_round && latest.index == latest_index,
+                ErrorCode::LatestAmendmentMismatch
+            );
+            if latest.status == AmendmentStatus::Pending {
+                latest.status = AmendmentStatus::Superseded;
+            }
+        }
+        amendment.funding_round = self.funding_round;
+        amendment.index = self.amendment_count;
+        self.amendment_count = self.amendment_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Amendment `index` of a funding round.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Amendment {
+    pub funding_round: Pubkey,
+    pub index: u32,
+    pub kind: AmendmentKind,
+    pub target_amount: u64,
+    pub interest_rate: u64,
+    pub repayment_deadline: u64,
+    pub extension_fee: u64, // added to the amount owed to investors when the amendment is applied
+    pub proposed_at: i64,
+    pub voting_base: u64, // capital raised when the amendment was proposed, which approvals are counted against
+    pub approved_amount: u64, // investor capital that has approved the amendment
+    pub status: AmendmentStatus,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum AmendmentKind {
+    Terms,     // owner-proposed change to the round's terms
+    Extension, // repayment deadline extension requested by the company
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum AmendmentStatus {
+    Pending,    // waiting for investors holding a majority of the voting base to approve
+    Applied,    // terms were written to the funding round
+    Superseded, // replaced by a newer proposal before it was approved
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AmendmentApproval {
+    pub investor: Pubkey,
+    pub funding_round: Pubkey,
+    pub amendment_index: u32,
+}
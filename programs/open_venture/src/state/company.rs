@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::{constants::{COMPANY_PROFILE_RESERVED_SPACE, FUNDING_ROUND_RESERVED_SPACE}, error::ErrorCode, state::Amendment};
 
 #[account]
 #[derive(InitSpace)]
@@ -8,17 +11,148 @@ pub struct CompanyProfile {
     pub name: String,
     #[max_len(280)]
     pub bio: String,
-    pub active_funding_round: Option<Pubkey>,
+    pub active_funding_round: Option<Pubkey>, // single active round of profiles before version 3, superseded by `active_round_count`
+    #[max_len(200)]
+    pub metadata_uri: String, // off-chain JSON with the logo and links, empty when not set
+    pub category: Option<CompanyCategory>,
+    pub country_code: Option<[u8; 2]>, // ISO 3166-1 alpha-2, e.g. b"KE"
+    pub document_hash: Option<[u8; 32]>, // hash of the pitch deck or term sheet shown to investors
+    pub version: u8, // layout version, 0 for profiles created before versioning
+    pub round_count: u64, // number of rounds created, the next round's index
+    pub registered: bool, // listed in the owner's registry, false for profiles created before the registry
+    pub bump: u8,
+    pub treasury_bump: u8,
+    pub active_round_count: u8, // rounds that are open or funded and not yet repaid, cancelled or defaulted
+    pub max_active_rounds: u8, // rounds the company may have active at once, bounded by the program config
+    pub treasury_outstanding: u64, // sum of its rounds' `TreasuryLedger::outstanding`, rounds from before ledgers join when migrated
+    pub reserved: [u8; COMPANY_PROFILE_RESERVED_SPACE],
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct FundingRound {
     #[max_len(36)]
-    pub id: String,
+    pub id: String, // optional label, only part of the seeds of rounds that predate `index`
     pub company: Pubkey,
     pub target_amount: u64,
     pub interest_rate: u64, // interest rate on amount raised to be paid back to investors
     pub repayment_deadline: u64,
     pub is_active: bool,
+    pub min_contribution: u64, // minimum ticket size per deposit, 0 means no minimum
+    pub max_contribution: u64, // maximum cumulative contribution per investor, 0 means no maximum
+    pub total_raised: u64,
+    pub allowlist_root: Option<[u8; 32]>, // merkle root of investors allowed to deposit
+    pub kyc_verifier: Option<Pubkey>, // authority whose attestations allow investors to deposit
+    pub funding_start: u64,
+    pub funding_end: u64,
+    pub status: FundingRoundStatus,
+    pub total_withdrawn: u64, // capital moved from the round vault to the company treasury
+    pub extension_fees: u64, // fees owed to investors on top of interest for granted extensions
+    pub auto_extensions: u8, // extensions granted without investor approval
+    pub collateral_mint: Option<Pubkey>, // mint of SPL token collateral, None when collateral is SOL
+    pub collateral_amount: u64, // collateral locked by the owner when the round was opened
+    pub total_repaid: u64,
+    pub collateral_liquidated: bool,
+    pub liquidation_proceeds: u64, // SOL collateral moved into the repayment vault by liquidation
+    pub version: u8, // layout version, 0 for rounds created before versioning
+    pub index: u64, // position among the company's rounds
+    pub indexed: bool, // false for rounds created before rounds were numbered, which are seeded by hash(id)
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub repayment_vault_bump: u8,
+    pub collateral_vault_bump: u8, // stored when the round is created, before the vault exists
+    pub treasury_ledger_bump: u8,
+    pub released_capital: u64, // capital never withdrawn, moved into the repayment vault on default
+    pub reserved: [u8; FUNDING_ROUND_RESERVED_SPACE],
+}
+
+impl FundingRound {
+    /// Seed that tells the round's PDAs apart from the company's other rounds: the
+    /// little-endian round index, or `hash(id)` for rounds created before rounds were numbered.
+    pub fn round_seed(&self) -> Vec<u8> {
+        if self.indexed {
+            self.index.to_le_bytes().to_vec()
+        } else {
+            hash(self.id.as_bytes()).to_bytes().to_vec()
+        }
+    }
+
+    /// Replaces the round's repayment terms with the ones from an approved amendment.
+    pub fn apply_amendment(&mut self, amendment: &Amendment) -> Result<()> {
+        // investors are repaid on the target, so it cannot drop below what they put in
+        require!(
+            amendment.target_amount >= self.total_raised,
+            ErrorCode::TargetBelowAmountRaised
+        );
+        self.target_amount = amendment.target_amount;
+        self.interest_rate = amendment.interest_rate;
+        self.repayment_deadline = amendment.repayment_deadline;
+        self.extension_fees = self.extension_fees
+            .checked_add(amendment.extension_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// A round is in default once its repayment deadline has passed without it being repaid.
+    ///
+    /// Only funded rounds can default: an open round has lent nothing yet and is finalized
+    /// instead, refunding its investors if it fell short.
+    pub fn is_in_default(&self, now: u64) -> bool {
+        self.status == FundingRoundStatus::Funded && now > self.repayment_deadline
+    }
+
+    /// Everything paid into the repayment vault for investors to claim pro-rata: the company's
+    /// repayment, liquidated SOL collateral and the capital released when the round defaulted.
+    pub fn distributable(&self) -> Result<u64> {
+        let distributable = self.total_repaid
+            .checked_add(self.liquidation_proceeds)
+            .and_then(|v| v.checked_add(self.released_capital))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(distributable)
+    }
+
+    /// Whether the grace period following the repayment deadline has fully elapsed.
+    pub fn grace_period_elapsed(&self, now: u64, grace_period: u64) -> Result<bool> {
+        let grace_period_end = self.repayment_deadline
+            .checked_add(grace_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(now > grace_period_end)
+    }
+
+    /// Total the company owes investors: target_amount * (1 + interest_rate / 100) plus extension fees.
+    pub fn total_repayment_due(&self) -> Result<u64> {
+        let total_with_interest = 100u64
+            .checked_add(self.interest_rate)
+            .and_then(|v| v.checked_mul(self.target_amount))
+            .and_then(|v| v.checked_div(100))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let total_due = total_with_interest
+            .checked_add(self.extension_fees)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(total_due)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum FundingRoundStatus {
+    Open,   // accepting deposits until the funding window closes
+    Funded, // funding window closed with the target amount raised
+    Failed, // funding window closed short of the target amount, investors can claim refunds
+    Cancelled, // aborted by the owner before any capital was withdrawn, investors can claim refunds
+    Repaid,    // the company repaid the round in full, collateral can be released to the owner
+    Defaulted, // the round was not repaid in time and its collateral was liquidated for investors
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CompanyCategory {
+    Agriculture,
+    Education,
+    Energy,
+    Fintech,
+    Healthcare,
+    Manufacturing,
+    RealEstate,
+    Retail,
+    Technology,
+    Other,
 }
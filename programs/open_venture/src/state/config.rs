@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub max_auto_extension: u64, // longest repayment deadline extension, in seconds, granted without investor approval
+    pub max_auto_extensions: u8, // number of extensions per round that can be granted without investor approval
+    pub liquidation_grace_period: u64, // seconds after the repayment deadline before collateral can be liquidated
+    pub keeper_reward_bps: u16, // share of liquidated collateral paid to whoever runs the liquidation
+    pub unproven_round_cap: u64, // largest target a company can raise on top of what it has repaid, 0 means uncapped
+    pub max_active_rounds: u8, // most rounds a company may have active at once
+    pub bump: u8,
+}
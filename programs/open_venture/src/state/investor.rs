@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::state::InvestorPortfolio;
+
+#[account]
+#[derive(InitSpace)]
+pub struct InvestorPosition {
+    pub investor: Pubkey,
+    pub funding_round: Pubkey,
+    pub amount: u64, // running total contributed by the investor to the funding round
+    pub refunded: bool,
+    pub collateral_claimed: bool,
+    pub repayment_claimed: u64,
+    pub portfolio_index: u64, // slot of the position in the investor's portfolio
+}
+
+impl InvestorPosition {
+    /// Portfolio slot the position is listed at, or will be listed at once a position that
+    /// has not been opened yet receives its first deposit.
+    pub fn portfolio_slot(&self, portfolio: &InvestorPortfolio) -> u64 {
+        if self.is_opened() {
+            self.portfolio_index
+        } else {
+            portfolio.position_count
+        }
+    }
+
+    pub fn is_opened(&self) -> bool {
+        self.investor != Pubkey::default()
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct InvestorAttestation {
+    pub verifier: Pubkey,
+    pub investor: Pubkey,
+    pub issued_at: i64,
+    pub expires_at: i64, // 0 means the attestation does not expire
+}
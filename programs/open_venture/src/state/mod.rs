@@ -1,2 +1,29 @@
 pub mod company;
 pub use company::*;
+
+pub mod investor;
+pub use investor::*;
+
+pub mod amendment;
+pub use amendment::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod reputation;
+pub use reputation::*;
+
+pub mod verification;
+pub use verification::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod portfolio;
+pub use portfolio::*;
+
+pub mod vault;
+pub use vault::*;
+
+pub mod treasury;
+pub use treasury::*;
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, state::InvestorPosition};
+
+/// Counts the positions an investor has opened; they are listed by the `PortfolioEntry`
+/// accounts at indices `0..position_count`.
+#[account]
+#[derive(InitSpace)]
+pub struct InvestorPortfolio {
+    pub investor: Pubkey,
+    pub position_count: u64,
+}
+
+/// Entry `index` of an investor's portfolio.
+#[account]
+#[derive(InitSpace)]
+pub struct PortfolioEntry {
+    pub investor: Pubkey,
+    pub index: u64,
+    pub funding_round: Pubkey,
+    pub investor_position: Pubkey,
+}
+
+impl InvestorPortfolio {
+    /// Appends a newly opened position to the portfolio at the next free `entry` slot.
+    pub fn record(
+        &mut self,
+        entry: &mut PortfolioEntry,
+        investor_position: &mut InvestorPosition,
+        investor_position_key: Pubkey,
+    ) -> Result<()> {
+        entry.investor = self.investor;
+        entry.index = self.position_count;
+        entry.funding_round = investor_position.funding_round;
+        entry.investor_position = investor_position_key;
+        investor_position.portfolio_index = self.position_count;
+        self.position_count = self.position_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
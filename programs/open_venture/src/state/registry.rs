@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+/// Counts the companies owned by a wallet; its companies are listed by the `CompanyIndex`
+/// accounts at indices `0..company_count`.
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerRegistry {
+    pub owner: Pubkey,
+    pub company_count: u64,
+    pub bump: u8,
+}
+
+/// Entry `index` of an owner's company list.
+#[account]
+#[derive(InitSpace)]
+pub struct CompanyIndex {
+    pub owner: Pubkey,
+    pub index: u64,
+    pub company: Pubkey,
+    pub bump: u8,
+}
+
+impl OwnerRegistry {
+    /// Appends `company` to the owner's list at the next free `company_index` slot.
+    pub fn register(&mut self, company_index: &mut CompanyIndex, company: Pubkey) -> Result<()> {
+        company_index.owner = self.owner;
+        company_index.index = self.company_count;
+        company_index.company = company;
+        self.company_count = self.company_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Index of the last entry of the owner's list.
+    pub fn last_index(&self) -> Result<u64> {
+        let last_index = self.company_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::CompanyNotRegistered)?;
+        Ok(last_index)
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::SECONDS_PER_DAY, error::ErrorCode};
+
+#[account]
+#[derive(InitSpace)]
+pub struct CompanyReputation {
+    pub company: Pubkey,
+    pub rounds_completed: u32, // rounds repaid in full
+    pub defaults: u32,         // rounds that passed their repayment deadline and grace period unpaid
+    pub total_raised: u64,     // capital raised across completed and defaulted rounds
+    pub total_repaid: u64,
+    pub days_late: u64, // cumulative whole days past the deadline across repaid rounds
+    pub bump: u8,
+}
+
+impl CompanyReputation {
+    /// Records a round the company repaid in full.
+    pub fn record_repayment(
+        &mut self,
+        raised: u64,
+        repaid: u64,
+        repayment_deadline: u64,
+        now: u64,
+    ) -> Result<()> {
+        self.rounds_completed = self.rounds_completed
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_raised = self.total_raised
+            .checked_add(raised)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_repaid = self.total_repaid
+            .checked_add(repaid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.days_late = self.days_late
+            .checked_add(now.saturating_sub(repayment_deadline) / SECONDS_PER_DAY)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Records a round the company failed to repay.
+    pub fn record_default(&mut self, raised: u64) -> Result<()> {
+        self.defaults = self.defaults
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_raised = self.total_raised
+            .checked_add(raised)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, state::CompanyProfile};
+
+/// The company treasury's ledger for one funding round.
+///
+/// Every lamport the program moves between the round and the treasury is recorded here, so the
+/// round's cash flows can be reconstructed from chain state even though the treasury pools the
+/// company's rounds in one account.
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryLedger {
+    pub company: Pubkey,
+    pub funding_round: Pubkey,
+    pub withdrawn: u64, // capital moved from the round vault into the treasury
+    pub repaid: u64, // moved from the treasury into the round's repayment vault
+    pub swept: u64, // stray transfers swept from the round's vaults into the treasury
+    pub written_off: u64, // capital still outstanding when the round defaulted
+}
+
+impl TreasuryLedger {
+    /// Capital withdrawn from the round that has not been repaid or written off yet. Interest
+    /// makes a full repayment larger than what was withdrawn, so this floors at zero.
+    pub fn outstanding(&self) -> u64 {
+        self.withdrawn
+            .saturating_sub(self.repaid)
+            .saturating_sub(self.written_off)
+    }
+
+    pub fn is_opened(&self) -> bool {
+        self.funding_round != Pubkey::default()
+    }
+
+    pub fn record_withdrawal(&mut self, company_profile: &mut CompanyProfile, amount: u64) -> Result<()> {
+        let outstanding = self.outstanding();
+        self.withdrawn = self.withdrawn
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.update_company_outstanding(company_profile, outstanding)
+    }
+
+    pub fn record_repayment(&mut self, company_profile: &mut CompanyProfile, amount: u64) -> Result<()> {
+        let outstanding = self.outstanding();
+        self.repaid = self.repaid
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.update_company_outstanding(company_profile, outstanding)
+    }
+
+    /// Writes off what the round still owes once it has defaulted. A defaulted round is never
+    /// repaid, so its capital no longer holds back the company's other rounds.
+    pub fn record_default(&mut self, company_profile: &mut CompanyProfile) -> Result<()> {
+        let outstanding = self.outstanding();
+        self.written_off = self.written_off
+            .checked_add(outstanding)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.update_company_outstanding(company_profile, outstanding)
+    }
+
+    pub fn record_sweep(&mut self, amount: u64) -> Result<()> {
+        self.swept = self.swept
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Replaces the round's previous `outstanding` in the company's total with the current one.
+    fn update_company_outstanding(&self, company_profile: &mut CompanyProfile, previous: u64) -> Result<()> {
+        company_profile.treasury_outstanding = company_profile.treasury_outstanding
+            .checked_sub(previous)
+            .and_then(|v| v.checked_add(self.outstanding()))
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
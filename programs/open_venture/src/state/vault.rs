@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum VaultKind {
+    Funding,   // holds investor deposits until the company withdraws them or investors are refunded
+    Repayment, // holds repayments and liquidation proceeds until investors claim them
+}
+
+/// Header of a funding round's lamport vault.
+///
+/// `tracked_balance` is what the program has paid in and not yet paid out. Anything the vault
+/// holds above it and its rent-exempt minimum arrived outside the program, e.g. a direct
+/// transfer, and can be swept to the company treasury.
+#[account]
+#[derive(InitSpace)]
+pub struct RoundVault {
+    pub funding_round: Pubkey,
+    pub kind: VaultKind,
+    pub tracked_balance: u64,
+}
+
+impl RoundVault {
+    pub fn credit(&mut self, amount: u64) -> Result<()> {
+        self.tracked_balance = self.tracked_balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn debit(&mut self, amount: u64) -> Result<()> {
+        self.tracked_balance = self.tracked_balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientVaultFunds)?;
+        Ok(())
+    }
+
+    /// Lamports held on top of the tracked balance and the rent-exempt minimum.
+    ///
+    /// Lamports only leave a vault through the program, so holding less than that means the
+    /// bookkeeping is wrong and is reported rather than treated as an empty surplus.
+    pub fn surplus(&self, lamports: u64, rent_exempt_minimum: u64) -> Result<u64> {
+        lamports
+            .checked_sub(rent_exempt_minimum)
+            .and_then(|v| v.checked_sub(self.tracked_balance))
+            .ok_or_else(|| error!(ErrorCode::VaultBalanceDeficit))
+    }
+}
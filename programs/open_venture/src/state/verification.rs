@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Grants `authority` the right to verify companies on behalf of the program admin.
+#[account]
+#[derive(InitSpace)]
+pub struct CompanyVerifier {
+    pub authority: Pubkey,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+/// Verified identity of a company. Its existence is the verified badge, so clients can list
+/// verified companies by fetching every `CompanyVerification` account.
+#[account]
+#[derive(InitSpace)]
+pub struct CompanyVerification {
+    pub company: Pubkey,
+    pub verifier: Pubkey, // admin or delegated verifier that vouched for the company
+    #[max_len(64)]
+    pub legal_name: String,
+    #[max_len(32)]
+    pub jurisdiction: String,
+    pub registration_hash: [u8; 32], // hash of the company's registration number, kept off-chain
+    pub verified_at: i64,
+    pub bump: u8,
+}
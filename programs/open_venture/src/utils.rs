@@ -1,129 +1,162 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash::hash;
-
-use crate::{error::ErrorCode, state::CompanyProfile};
-
-/// Validates that only the company owner can access the vault funds.
-/// 
-/// This function ensures:
-/// 1. The signer is the company owner
-/// 2. The vault PDA is correctly derived from the company profile and funding round
-/// 
-/// # Security
-/// The vault is a Program Derived Address (PDA), which means:
-/// - Only the program can sign for vault transfers
-/// - The vault seeds include the company profile key, ensuring vaults are unique per company
-/// - This function enforces that only the company owner can authorize vault access
-/// 
-/// # Usage
-/// Call this function in any instruction that needs to access vault funds to ensure
-/// only the company owner has permission.
-pub fn validate_vault_access(
-    owner: &Signer,
-    company_profile: &Account<CompanyProfile>,
-    vault: &AccountInfo,
-    funding_round_id: &str,
-    program_id: &Pubkey,
+use anchor_lang::solana_program::hash::{hash, hashv};
+
+use anchor_lang::system_program;
+use anchor_spl::token;
+
+use crate::{
+    constants::{FUNDING_ROUND_SEED, FUNDING_ROUND_VERSION},
+    error::ErrorCode,
+    state::{CompanyReputation, CompanyVerifier, FundingRound, ProgramConfig, RoundVault},
+};
+
+/// Verifies that `investor` is a leaf of the merkle tree with the given `root`.
+///
+/// Leaves are `sha256(investor)` and each level hashes the pair in sorted order,
+/// so proofs don't need to carry left/right position flags.
+pub fn verify_allowlist_proof(proof: &[[u8; 32]], root: &[u8; 32], investor: &Pubkey) -> bool {
+    let mut computed = hash(investor.as_ref()).to_bytes();
+    for node in proof {
+        computed = if computed <= *node {
+            hashv(&[&computed, node]).to_bytes()
+        } else {
+            hashv(&[node, &computed]).to_bytes()
+        };
+    }
+    computed == *root
+}
+
+/// Transfers SPL token collateral out of a funding round's collateral vault.
+///
+/// The collateral vault is a token account whose authority is the funding round PDA,
+/// so the transfer is signed with the funding round seeds and its stored bump.
+pub fn transfer_collateral_tokens<'info>(
+    funding_round: &Account<'info, FundingRound>,
+    collateral_vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
 ) -> Result<()> {
-    // Ensure the signer is the company owner
     require!(
-        owner.key() == company_profile.owner,
-        ErrorCode::UnauthorizedVaultAccess
+        funding_round.version >= FUNDING_ROUND_VERSION,
+        ErrorCode::AccountNotMigrated
     );
-
-    // Validate that the vault PDA is correctly derived
-    let company_profile_key = company_profile.key();
-    let round_id_hash = hash(funding_round_id.as_bytes());
-    let round_id_seed = round_id_hash.to_bytes();
-    let seeds = &[
-        b"funding_round_vault".as_ref(),
-        company_profile_key.as_ref(),
+    let round_id_seed = funding_round.round_seed();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        FUNDING_ROUND_SEED,
+        funding_round.company.as_ref(),
         round_id_seed.as_ref(),
-    ];
-    let (expected_vault, _) = Pubkey::find_program_address(seeds, program_id);
-    
-    require!(
-        vault.key() == expected_vault,
-        ErrorCode::UnauthorizedVaultAccess
-    );
+        &[funding_round.bump],
+    ]];
+
+    let transfer_accounts = token::Transfer {
+        from: collateral_vault.clone(),
+        to: destination.clone(),
+        authority: funding_round.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), transfer_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)
+}
+
+/// Moves the capital a defaulted round never withdrew from its funding vault into its
+/// repayment vault, where investors claim it pro-rata alongside any repayment.
+///
+/// Without this, deposits the company left in the vault would stay locked once the round
+/// can no longer be withdrawn from or refunded.
+pub fn release_unwithdrawn_capital<'info>(
+    funding_round: &mut Account<'info, FundingRound>,
+    vault: &mut Account<'info, RoundVault>,
+    repayment_vault: &mut Account<'info, RoundVault>,
+) -> Result<()> {
+    let unwithdrawn = vault.tracked_balance;
+    if unwithdrawn == 0 {
+        return Ok(());
+    }
+
+    vault.debit(unwithdrawn)?;
+    **vault
+        .to_account_info()
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? -= unwithdrawn;
+    **repayment_vault
+        .to_account_info()
+        .try_borrow_mut_lamports()
+        .map_err(|_| ErrorCode::UnauthorizedVaultAccess)? += unwithdrawn;
+    repayment_vault.credit(unwithdrawn)?;
+    funding_round.released_capital = unwithdrawn;
 
     Ok(())
 }
 
-/// Validates that only the company owner can access the company treasury vault.
-/// 
-/// This function ensures:
-/// 1. The signer is the company owner
-/// 2. The company treasury vault PDA is correctly derived from the company profile
-/// 
-/// # Security
-/// The company treasury vault is a Program Derived Address (PDA), which means:
-/// - Only the program can sign for vault transfers
-/// - The vault seeds include the company profile key, ensuring vaults are unique per company
-/// - This function enforces that only the company owner can authorize treasury vault access
-/// 
-/// # Usage
-/// Call this function in any instruction that needs to access the company treasury vault to ensure
-/// only the company owner has permission.
-pub fn validate_company_treasury_access(
-    owner: &Signer,
-    company_profile: &Account<CompanyProfile>,
-    company_treasury: &AccountInfo,
-    program_id: &Pubkey,
+/// Validates a funding round target against the company's repayment track record.
+///
+/// When the admin configures an `unproven_round_cap`, a company can raise at most
+/// that cap plus everything it has repaid across earlier rounds.
+pub fn validate_round_size(
+    config: &ProgramConfig,
+    company_reputation: &CompanyReputation,
+    target_amount: u64,
 ) -> Result<()> {
-    // Ensure the signer is the company owner
-    require!(
-        owner.key() == company_profile.owner,
-        ErrorCode::UnauthorizedVaultAccess
-    );
+    if config.unproven_round_cap == 0 {
+        return Ok(());
+    }
 
-    // Validate that the company treasury vault PDA is correctly derived
-    let owner_key = owner.key();
-    let company_profile_key = company_profile.key();
-    let treasury_seeds = &[
-        b"company_treasury".as_ref(),
-        owner_key.as_ref(),
-        company_profile_key.as_ref(),
-    ];
-    let (expected_treasury, _) = Pubkey::find_program_address(treasury_seeds, program_id);
-    
+    let max_target_amount = config.unproven_round_cap
+        .saturating_add(company_reputation.total_repaid);
     require!(
-        company_treasury.key() == expected_treasury,
-        ErrorCode::UnauthorizedVaultAccess
+        target_amount <= max_target_amount,
+        ErrorCode::TargetAmountExceedsReputationCap
     );
 
     Ok(())
 }
 
-/// Validates that only the company owner can access the funding round repayment vault.
-pub fn validate_repayment_vault_access(
-    owner: &Signer,
-    company_profile: &Account<CompanyProfile>,
-    funding_round_id: &str,
-    repayment_vault: &AccountInfo,
-    program_id: &Pubkey,
+/// Validates that a signer may verify companies or revoke their verification.
+///
+/// The program admin can always do so; anyone else must hold a `CompanyVerifier` account
+/// delegated to them by the admin.
+pub fn validate_company_verifier(
+    verifier: &Pubkey,
+    config: &ProgramConfig,
+    company_verifier: &Option<Account<CompanyVerifier>>,
 ) -> Result<()> {
+    let is_delegated = company_verifier
+        .as_ref()
+        .is_some_and(|company_verifier| company_verifier.authority == *verifier);
     require!(
-        owner.key() == company_profile.owner,
-        ErrorCode::UnauthorizedVaultAccess
+        *verifier == config.admin || is_delegated,
+        ErrorCode::UnauthorizedVerifier
     );
 
-    // derive repayment vault PDA
-    let company_profile_key = company_profile.key();
-    let round_id_hash = hash(funding_round_id.as_bytes());
-    let round_id_seed = round_id_hash.to_bytes();
-    // repayment vault seeds
-    let seeds = &[
-        b"funding_round_repayment".as_ref(),
-        company_profile_key.as_ref(),
-        round_id_seed.as_ref(),
-    ];
-    let (expected_vault, _) = Pubkey::find_program_address(seeds, program_id);
+    Ok(())
+}
 
-    require!(
-        repayment_vault.key() == expected_vault,
-        ErrorCode::UnauthorizedVaultAccess
-    );
+/// Grows a program-owned account to `new_len` bytes, zero-filling the new space.
+///
+/// The payer tops up the account's lamports so it stays rent exempt at its new size.
+/// Accounts that are already large enough are left untouched.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        let transfer_accounts = system_program::Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), transfer_accounts);
+        system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    account.resize(new_len)?;
 
     Ok(())
 }
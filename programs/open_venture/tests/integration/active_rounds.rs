@@ -0,0 +1,126 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use open_venture::{error::ErrorCode, CompanyProfile, FundingRound, FundingRoundStatus};
+
+use crate::context::*;
+
+pub fn set_limit(
+    ctx: &mut TestContext,
+    authority: Pubkey,
+    company: &Company,
+    max_active_rounds: u8,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::SetActiveRoundLimit {
+            authority,
+            config: config_address(),
+            company_profile: company.profile,
+        },
+        open_venture::instruction::SetActiveRoundLimit { max_active_rounds },
+    );
+    ctx.process(ix, &[authority])
+}
+
+fn set_program_limit(ctx: &mut TestContext, max_active_rounds: u8) -> ProgramResult {
+    let admin = ctx.admin;
+    let ix = instruction(
+        open_venture::accounts::UpdateConfig {
+            admin,
+            config: config_address(),
+        },
+        open_venture::instruction::UpdateConfig {
+            max_auto_extension: MAX_AUTO_EXTENSION,
+            max_auto_extensions: MAX_AUTO_EXTENSIONS,
+            liquidation_grace_period: LIQUIDATION_GRACE_PERIOD,
+            keeper_reward_bps: KEEPER_REWARD_BPS,
+            unproven_round_cap: 0,
+            max_active_rounds,
+        },
+    );
+    ctx.process(ix, &[admin])
+}
+
+fn try_create_round(ctx: &mut TestContext, company: &Company) -> ProgramResult {
+    let index = ctx.account::<CompanyProfile>(&company.profile).round_count;
+    let ix = ctx.create_funding_round_ix(company, index, SOL, 10, Window::default());
+    ctx.process(ix, &[company.owner])
+}
+
+#[test]
+fn companies_run_as_many_rounds_as_their_limit_allows() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    set_limit(&mut ctx, owner, &company, 2).unwrap();
+
+    let first = ctx.create_round(&company, Window::default());
+    let second = ctx.create_round(&company, Window::default());
+    let result = try_create_round(&mut ctx, &company);
+    assert_error(result, ErrorCode::ActiveRoundLimitReached);
+    assert_eq!(ctx.account::<CompanyProfile>(&company.profile).active_round_count, 2);
+
+    // the rounds run independently of each other
+    let investor = ctx.user();
+    ctx.fund(investor, &second, SOL).unwrap();
+    ctx.cancel(&first).unwrap();
+    let second_round: FundingRound = ctx.account(&second.address);
+    assert_eq!(second_round.status, FundingRoundStatus::Open);
+    assert_eq!(second_round.total_raised, SOL);
+
+    // an ended round frees its slot
+    assert_eq!(ctx.account::<CompanyProfile>(&company.profile).active_round_count, 1);
+    let third = ctx.create_round(&company, Window::default());
+    assert_eq!(third.index, 2);
+}
+
+#[test]
+fn set_active_round_limit_by_owner_or_admin() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let stranger = ctx.user();
+    let admin = ctx.admin;
+
+    let result = set_limit(&mut ctx, stranger, &company, 2);
+    assert_error(result, ErrorCode::UnauthorizedRoundLimit);
+
+    set_limit(&mut ctx, owner, &company, 2).unwrap();
+    assert_eq!(ctx.account::<CompanyProfile>(&company.profile).max_active_rounds, 2);
+    set_limit(&mut ctx, admin, &company, MAX_ACTIVE_ROUNDS).unwrap();
+    assert_eq!(
+        ctx.account::<CompanyProfile>(&company.profile).max_active_rounds,
+        MAX_ACTIVE_ROUNDS
+    );
+}
+
+#[test]
+fn set_active_round_limit_stays_within_the_program_limit() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    let result = set_limit(&mut ctx, owner, &company, 0);
+    assert_error(result, ErrorCode::InvalidActiveRoundLimit);
+
+    let result = set_limit(&mut ctx, owner, &company, MAX_ACTIVE_ROUNDS + 1);
+    assert_error(result, ErrorCode::InvalidActiveRoundLimit);
+
+    let result = set_program_limit(&mut ctx, 0);
+    assert_error(result, ErrorCode::InvalidActiveRoundLimit);
+}
+
+#[test]
+fn lowering_the_program_limit_stops_new_rounds() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    set_limit(&mut ctx, owner, &company, MAX_ACTIVE_ROUNDS).unwrap();
+    let round = ctx.create_round(&company, Window::default());
+
+    set_program_limit(&mut ctx, 1).unwrap();
+
+    let result = try_create_round(&mut ctx, &company);
+    assert_error(result, ErrorCode::ActiveRoundLimitReached);
+    ctx.cancel(&round).unwrap();
+    try_create_round(&mut ctx, &company).unwrap();
+}
@@ -0,0 +1,495 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{entrypoint::ProgramResult};
+use anchor_lang::system_program;
+use open_venture::{
+    error::ErrorCode, Amendment, AmendmentKind, AmendmentStatus, FundingRound, RoundAmendments,
+};
+
+use crate::context::*;
+
+pub fn amend(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    round: &Round,
+    target_amount: u64,
+    interest_rate: u64,
+    repayment_deadline: u64,
+) -> ProgramResult {
+    let index = amendment_count(ctx, round);
+    let ix = instruction(
+        open_venture::accounts::AmendFundingRound {
+            owner: signer,
+            company_profile: round.company.profile,
+            funding_round: round.address,
+            round_amendments: round_amendments_address(&round.address),
+            amendment: amendment_address(&round.address, index),
+            latest_amendment: index
+                .checked_sub(1)
+                .map(|latest| amendment_address(&round.address, latest)),
+            company_reputation: round.company.reputation,
+            config: config_address(),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::AmendFundingRound {
+            target_amount,
+            interest_rate,
+            repayment_deadline,
+        },
+    );
+    ctx.process(ix, &[signer])
+}
+
+pub fn approve(
+    ctx: &mut TestContext,
+    investor: Pubkey,
+    round: &Round,
+    amendment_index: u32,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::ApproveAmendment {
+            investor,
+            funding_round: round.address,
+            amendment: amendment_address(&round.address, amendment_index),
+            investor_position: investor_position_address(&round.address, &investor),
+            amendment_approval: amendment_approval_address(
+                &round.address,
+                amendment_index,
+                &investor,
+            ),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::ApproveAmendment { amendment_index },
+    );
+    ctx.process(ix, &[investor])
+}
+
+pub fn request_extension(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    round: &Round,
+    new_repayment_deadline: u64,
+    extension_fee: u64,
+    rate_bump: u64,
+) -> ProgramResult {
+    let index = amendment_count(ctx, round);
+    let ix = instruction(
+        open_venture::accounts::RequestExtension {
+            owner: signer,
+            company_profile: round.company.profile,
+            funding_round: round.address,
+            round_amendments: round_amendments_address(&round.address),
+            amendment: amendment_address(&round.address, index),
+            latest_amendment: index
+                .checked_sub(1)
+                .map(|latest| amendment_address(&round.address, latest)),
+            config: config_address(),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::RequestExtension {
+            new_repayment_deadline,
+            extension_fee,
+            rate_bump,
+        },
+    );
+    ctx.process(ix, &[signer])
+}
+
+/// Number of amendments proposed for the round, the index the next proposal is stored at.
+pub fn amendment_count(ctx: &TestContext, round: &Round) -> u32 {
+    let address = round_amendments_address(&round.address);
+    match ctx.svm.account(&address) {
+        Some(_) => ctx.account::<RoundAmendments>(&address).amendment_count,
+        None => 0,
+    }
+}
+
+fn history(ctx: &TestContext, round: &Round) -> Vec<Amendment> {
+    (0..amendment_count(ctx, round))
+        .map(|index| ctx.account(&amendment_address(&round.address, index)))
+        .collect()
+}
+
+#[test]
+fn amendments_apply_immediately_before_any_deposit() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let deadline = ctx.now() + 2_000_000;
+
+    amend(&mut ctx, round.company.owner, &round, 2 * SOL, 12, deadline).unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.target_amount, 2 * SOL);
+    assert_eq!(funding_round.interest_rate, 12);
+    assert_eq!(funding_round.repayment_deadline, deadline);
+    let history = history(&ctx, &round);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].kind, AmendmentKind::Terms);
+    assert_eq!(history[0].status, AmendmentStatus::Applied);
+}
+
+#[test]
+fn amendments_need_a_capital_majority_once_investors_deposited() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let large = ctx.user();
+    let small = ctx.user();
+    ctx.fund(large, &round, SOL * 6 / 10).unwrap();
+    ctx.fund(small, &round, SOL * 4 / 10).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+    assert_eq!(history(&ctx, &round)[0].status, AmendmentStatus::Pending);
+
+    approve(&mut ctx, small, &round, 0).unwrap();
+    assert_eq!(history(&ctx, &round)[0].approved_amount, SOL * 4 / 10);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).interest_rate,
+        10
+    );
+
+    approve(&mut ctx, large, &round, 0).unwrap();
+    assert_eq!(history(&ctx, &round)[0].status, AmendmentStatus::Applied);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).interest_rate,
+        15
+    );
+
+    // the approval account from the first vote already exists
+    let result = approve(&mut ctx, small, &round, 0);
+    assert_eq!(result, Err(account_already_in_use()));
+}
+
+#[test]
+fn amendments_cannot_lower_the_target_below_the_capital_raised() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let first = ctx.user();
+    let second = ctx.user();
+    ctx.fund(first, &round, SOL / 2).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+
+    let result = amend(&mut ctx, round.company.owner, &round, SOL / 4, 10, deadline);
+    assert_error(result, ErrorCode::TargetBelowAmountRaised);
+
+    // deposits that arrive while a lower target awaits approval keep it from being applied
+    amend(&mut ctx, round.company.owner, &round, SOL / 2, 10, deadline).unwrap();
+    ctx.fund(second, &round, SOL / 4).unwrap();
+    let result = approve(&mut ctx, first, &round, 0);
+    assert_error(result, ErrorCode::AmendmentVotingBaseChanged);
+    assert_eq!(ctx.account::<FundingRound>(&round.address).target_amount, SOL);
+}
+
+#[test]
+fn approvals_are_counted_against_the_capital_raised_at_the_proposal() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let early = ctx.user();
+    let late = ctx.user();
+    ctx.fund(early, &round, SOL * 3 / 10).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+    assert_eq!(history(&ctx, &round)[0].voting_base, SOL * 3 / 10);
+
+    // capital deposited after the proposal would otherwise shift the majority under the vote
+    ctx.fund(late, &round, SOL * 7 / 10).unwrap();
+    let result = approve(&mut ctx, early, &round, 0);
+    assert_error(result, ErrorCode::AmendmentVotingBaseChanged);
+    let result = approve(&mut ctx, late, &round, 0);
+    assert_error(result, ErrorCode::AmendmentVotingBaseChanged);
+
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+    let history = history(&ctx, &round);
+    assert_eq!(history[0].status, AmendmentStatus::Superseded);
+    assert_eq!(history[1].voting_base, SOL);
+
+    approve(&mut ctx, early, &round, 1).unwrap();
+    assert_eq!(ctx.account::<FundingRound>(&round.address).interest_rate, 10);
+    approve(&mut ctx, late, &round, 1).unwrap();
+    assert_eq!(ctx.account::<FundingRound>(&round.address).interest_rate, 15);
+}
+
+#[test]
+fn new_proposals_supersede_pending_ones() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+    amend(&mut ctx, round.company.owner, &round, SOL, 20, deadline).unwrap();
+
+    assert_eq!(history(&ctx, &round)[0].status, AmendmentStatus::Superseded);
+    let result = approve(&mut ctx, investor, &round, 0);
+    assert_error(result, ErrorCode::AmendmentNotPending);
+    let result = approve(&mut ctx, investor, &round, 2);
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::AccountNotInitialized);
+}
+
+#[test]
+fn proposals_must_pass_the_latest_amendment() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    let owner = round.company.owner;
+    let deadline = ctx.now() + 2_000_000;
+    amend(&mut ctx, owner, &round, SOL, 15, deadline).unwrap();
+    amend(&mut ctx, owner, &round, SOL, 20, deadline).unwrap();
+
+    // leaving out the pending proposal, or passing an older one, would keep it approvable
+    for latest in [None, Some(amendment_address(&round.address, 0))] {
+        let ix = instruction(
+            open_venture::accounts::AmendFundingRound {
+                owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                round_amendments: round_amendments_address(&round.address),
+                amendment: amendment_address(&round.address, 2),
+                latest_amendment: latest,
+                company_reputation: round.company.reputation,
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::AmendFundingRound {
+                target_amount: SOL,
+                interest_rate: 25,
+                repayment_deadline: deadline,
+            },
+        );
+        let result = ctx.process(ix, &[owner]);
+        assert_error(result, ErrorCode::LatestAmendmentMismatch);
+    }
+    assert_eq!(history(&ctx, &round)[1].status, AmendmentStatus::Pending);
+}
+
+#[test]
+fn approvals_require_a_position_in_an_active_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+
+    ctx.cancel(&round).unwrap();
+    let result = approve(&mut ctx, investor, &round, 0);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn refunded_investors_cannot_approve() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let deadline = ctx.now() + 2_000_000;
+    amend(&mut ctx, round.company.owner, &round, SOL, 15, deadline).unwrap();
+    // a refunded position in a round that is still open can only be forged, but it must not vote
+    let position = investor_position_address(&round.address, &investor);
+    ctx.update_account::<open_venture::InvestorPosition>(&position, |position| {
+        position.refunded = true
+    });
+
+    let result = approve(&mut ctx, investor, &round, 0);
+
+    assert_error(result, ErrorCode::NotAnInvestor);
+}
+
+#[test]
+fn amendments_validate_terms() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let owner = round.company.owner;
+    let now = ctx.now();
+
+    let result = amend(&mut ctx, owner, &round, 0, 10, now + 2_000_000);
+    assert_error(result, ErrorCode::TargetAmountMustBeGreaterThanZero);
+
+    let result = amend(&mut ctx, owner, &round, SOL, 0, now + 2_000_000);
+    assert_error(result, ErrorCode::InterestRateMustBeGreaterThanZero);
+
+    let result = amend(&mut ctx, owner, &round, SOL, 10, now);
+    assert_error(result, ErrorCode::RepaymentDeadlineInThePast);
+
+    let result = amend(&mut ctx, owner, &round, SOL, 10, now + 100);
+    assert_error(result, ErrorCode::RepaymentDeadlineBeforeFundingEnd);
+
+    let admin = ctx.admin;
+    ctx.update_config(admin, KEEPER_REWARD_BPS, SOL).unwrap();
+    let result = amend(&mut ctx, owner, &round, 2 * SOL, 10, now + 2_000_000);
+    assert_error(result, ErrorCode::TargetAmountExceedsReputationCap);
+
+    let stranger = ctx.user();
+    let result = amend(&mut ctx, stranger, &round, SOL, 10, now + 2_000_000);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let other = ctx.create_company_with_round(Window::default());
+    let result = amend(
+        &mut ctx,
+        owner,
+        &Round {
+            address: other.address,
+            ..round
+        },
+        SOL,
+        10,
+        now + 2_000_000,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    ctx.cancel(&round).unwrap();
+    let result = amend(&mut ctx, owner, &round, SOL, 10, now + 2_000_000);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn amendment_history_keeps_every_amendment() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    let deadline = ctx.now() + 2_000_000;
+    for rate in 0..40 {
+        amend(&mut ctx, round.company.owner, &round, SOL, 11 + rate, deadline).unwrap();
+    }
+
+    let history = history(&ctx, &round);
+    assert_eq!(history.len(), 40);
+    for (index, amendment) in history.iter().enumerate() {
+        assert_eq!(amendment.funding_round, round.address);
+        assert_eq!(amendment.index, index as u32);
+        assert_eq!(amendment.interest_rate, 11 + index as u64);
+    }
+    assert!(history[..39]
+        .iter()
+        .all(|amendment| amendment.status == AmendmentStatus::Superseded));
+
+    let result = approve(&mut ctx, investor, &round, 0);
+    assert_error(result, ErrorCode::AmendmentNotPending);
+    approve(&mut ctx, investor, &round, 39).unwrap();
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.interest_rate, 50);
+}
+
+#[test]
+fn short_extensions_are_granted_automatically() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let owner = round.company.owner;
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+
+    request_extension(
+        &mut ctx,
+        owner,
+        &round,
+        deadline + MAX_AUTO_EXTENSION,
+        SOL / 100,
+        2,
+    )
+    .unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(
+        funding_round.repayment_deadline,
+        deadline + MAX_AUTO_EXTENSION
+    );
+    assert_eq!(funding_round.interest_rate, 12);
+    assert_eq!(funding_round.extension_fees, SOL / 100);
+    assert_eq!(funding_round.auto_extensions, 1);
+    assert_eq!(history(&ctx, &round)[0].kind, AmendmentKind::Extension);
+
+    // the configured number of automatic extensions has been used up
+    let deadline = funding_round.repayment_deadline;
+    request_extension(&mut ctx, owner, &round, deadline + 60, 0, 0).unwrap();
+    assert_eq!(history(&ctx, &round)[1].status, AmendmentStatus::Pending);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .repayment_deadline,
+        deadline
+    );
+}
+
+#[test]
+fn long_extensions_need_investor_approval() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    let new_deadline = deadline + MAX_AUTO_EXTENSION + 1;
+
+    request_extension(&mut ctx, round.company.owner, &round, new_deadline, 0, 0).unwrap();
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .repayment_deadline,
+        deadline
+    );
+
+    approve(&mut ctx, investor, &round, 0).unwrap();
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .repayment_deadline,
+        new_deadline
+    );
+}
+
+#[test]
+fn extensions_are_validated() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let owner = round.company.owner;
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+
+    let result = request_extension(&mut ctx, owner, &round, deadline, 0, 0);
+    assert_error(result, ErrorCode::ExtensionMustExtendDeadline);
+
+    let result = request_extension(&mut ctx, owner, &round, deadline + 60, 0, u64::MAX);
+    assert_error(result, ErrorCode::MathOverflow);
+
+    let stranger = ctx.user();
+    let result = request_extension(&mut ctx, stranger, &round, deadline + 60, 0, 0);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let other = ctx.create_company_with_round(Window::default());
+    let result = request_extension(
+        &mut ctx,
+        owner,
+        &Round {
+            address: other.address,
+            ..round
+        },
+        deadline + 60,
+        0,
+        0,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    ctx.cancel(&round).unwrap();
+    let result = request_extension(&mut ctx, owner, &round, deadline + 60, 0, 0);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn extensions_cannot_be_requested_once_the_round_is_in_default() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let owner = round.company.owner;
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    ctx.svm.warp(deadline as i64 - ctx.svm.now() + 1);
+
+    // even an extension short enough to be granted automatically
+    let result = request_extension(&mut ctx, owner, &round, deadline + 60, 0, 0);
+    assert_error(result, ErrorCode::RepaymentDeadlinePassed);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).repayment_deadline,
+        deadline
+    );
+    ctx.svm.warp(LIQUIDATION_GRACE_PERIOD as i64);
+    ctx.mark_defaulted(&round).unwrap();
+}
@@ -0,0 +1,578 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_spl::token::spl_token;
+use open_venture::{
+    error::ErrorCode, CompanyReputation, FundingRound, FundingRoundStatus, InvestorPosition, RoundVault,
+};
+
+use crate::context::*;
+
+pub const TOKENS: u64 = 1_000_000;
+
+pub fn release(
+    ctx: &mut TestContext,
+    round: &Round,
+    owner_token_account: Option<Pubkey>,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::ReleaseCollateral {
+            owner: round.company.owner,
+            company_profile: round.company.profile,
+            funding_round: round.address,
+            collateral_vault: collateral_vault_address(&round.address),
+            owner_token_account,
+            token_program: owner_token_account.map(|_| spl_token::ID),
+        },
+        open_venture::instruction::ReleaseCollateral {},
+    );
+    ctx.process(ix, &[round.company.owner])
+}
+
+pub fn claim_collateral(
+    ctx: &mut TestContext,
+    investor: Pubkey,
+    round: &Round,
+    investor_token_account: Option<Pubkey>,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::ClaimCollateral {
+            investor,
+            funding_round: round.address,
+            investor_position: investor_position_address(&round.address, &investor),
+            collateral_vault: collateral_vault_address(&round.address),
+            investor_token_account,
+            token_program: investor_token_account.map(|_| spl_token::ID),
+        },
+        open_venture::instruction::ClaimCollateral {},
+    );
+    ctx.process(ix, &[investor])
+}
+
+/// Creates a round backed by `collateral` lamports and raises its whole target.
+pub fn sol_collateralized_round(ctx: &mut TestContext, collateral: u64) -> (Round, Pubkey) {
+    let round = ctx.create_company_with_round(Window::default());
+    ctx.deposit_collateral(&round, collateral).unwrap();
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    (round, investor)
+}
+
+/// Creates a round backed by [`TOKENS`] of a new mint and raises its whole target.
+pub fn token_collateralized_round(ctx: &mut TestContext) -> (Round, Pubkey, Pubkey) {
+    let round = ctx.create_company_with_round(Window::default());
+    let mint = ctx.svm.create_mint();
+    let owner_tokens = ctx
+        .svm
+        .create_token_account(mint, round.company.owner, TOKENS);
+    ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS)
+        .unwrap();
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    (round, investor, mint)
+}
+
+/// Moves the clock past the round's repayment deadline and liquidation grace period.
+pub fn warp_past_grace_period(ctx: &mut TestContext, round: &Round) {
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    ctx.svm
+        .warp(deadline as i64 - ctx.svm.now() + LIQUIDATION_GRACE_PERIOD as i64 + 1);
+}
+
+#[test]
+fn deposit_collateral_locks_sol_in_the_vault() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+
+    ctx.deposit_collateral(&round, SOL / 2).unwrap();
+    let vault_after_first = ctx.svm.lamports(&collateral_vault_address(&round.address));
+    ctx.deposit_collateral(&round, SOL / 2).unwrap();
+
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .collateral_amount,
+        SOL
+    );
+    assert_eq!(
+        ctx.svm.lamports(&collateral_vault_address(&round.address)),
+        vault_after_first + SOL / 2
+    );
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).collateral_mint,
+        None
+    );
+}
+
+#[test]
+fn deposit_token_collateral_locks_tokens_in_the_vault() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let mint = ctx.svm.create_mint();
+    let owner_tokens = ctx
+        .svm
+        .create_token_account(mint, round.company.owner, TOKENS);
+
+    ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS / 2)
+        .unwrap();
+    ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS / 2)
+        .unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.collateral_mint, Some(mint));
+    assert_eq!(funding_round.collateral_amount, TOKENS);
+    assert_eq!(
+        ctx.svm
+            .token_balance(&collateral_vault_address(&round.address)),
+        TOKENS
+    );
+    assert_eq!(ctx.svm.token_balance(&owner_tokens), 0);
+}
+
+#[test]
+fn collateral_is_locked_before_the_first_deposit() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let mint = ctx.svm.create_mint();
+    let owner_tokens = ctx
+        .svm
+        .create_token_account(mint, round.company.owner, TOKENS);
+
+    let result = ctx.deposit_collateral(&round, 0);
+    assert_error(result, ErrorCode::CollateralAmountMustBeGreaterThanZero);
+    let result = ctx.deposit_token_collateral(&round, mint, owner_tokens, 0);
+    assert_error(result, ErrorCode::CollateralAmountMustBeGreaterThanZero);
+
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let result = ctx.deposit_collateral(&round, SOL);
+    assert_error(result, ErrorCode::FundingRoundHasDeposits);
+    let result = ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS);
+    assert_error(result, ErrorCode::FundingRoundHasDeposits);
+
+    ctx.cancel(&round).unwrap();
+    let result = ctx.deposit_collateral(&round, SOL);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+    let result = ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn a_round_holds_a_single_collateral_asset() {
+    let mut ctx = TestContext::new();
+    let sol_round = ctx.create_company_with_round(Window::default());
+    let token_round = ctx.create_company_with_round(Window::default());
+    let mint = ctx.svm.create_mint();
+    let other_mint = ctx.svm.create_mint();
+    let owner_tokens = ctx
+        .svm
+        .create_token_account(mint, sol_round.company.owner, TOKENS);
+    let token_owner_tokens = ctx
+        .svm
+        .create_token_account(mint, token_round.company.owner, TOKENS);
+    let token_owner_other_tokens =
+        ctx.svm
+            .create_token_account(other_mint, token_round.company.owner, TOKENS);
+    ctx.deposit_collateral(&sol_round, SOL).unwrap();
+    ctx.deposit_token_collateral(&token_round, mint, token_owner_tokens, TOKENS / 2)
+        .unwrap();
+
+    // the collateral vault's layout already tells the assets apart, before the round's terms are checked
+    let result = ctx.deposit_token_collateral(&sol_round, mint, owner_tokens, TOKENS);
+    assert_anchor_error(
+        result,
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram,
+    );
+
+    let result = ctx.deposit_collateral(&token_round, SOL);
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSpace);
+
+    let result =
+        ctx.deposit_token_collateral(&token_round, other_mint, token_owner_other_tokens, TOKENS);
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintTokenMint);
+}
+
+#[test]
+fn a_round_records_a_single_collateral_asset() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let mint = ctx.svm.create_mint();
+    let other_mint = ctx.svm.create_mint();
+    let owner_tokens = ctx
+        .svm
+        .create_token_account(mint, round.company.owner, TOKENS);
+    // the vault's layout turns the other asset away first, so the round's own record only
+    // decides for a round whose vault is missing, forged here
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.collateral_mint = Some(other_mint);
+    });
+
+    let result = ctx.deposit_collateral(&round, SOL);
+    assert_error(result, ErrorCode::CollateralKindMismatch);
+    let result = ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS);
+    assert_error(result, ErrorCode::CollateralKindMismatch);
+
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.collateral_mint = None;
+        funding_round.collateral_amount = SOL;
+    });
+    let result = ctx.deposit_token_collateral(&round, mint, owner_tokens, TOKENS);
+    assert_error(result, ErrorCode::CollateralKindMismatch);
+    assert!(ctx.svm.account(&collateral_vault_address(&round.address)).is_none());
+}
+
+#[test]
+fn collateral_is_deposited_by_the_owner() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+
+    let stranger = ctx.user();
+    let result = ctx.deposit_collateral(
+        &Round {
+            company: Company {
+                owner: stranger,
+                ..round.company
+            },
+            ..round
+        },
+        SOL,
+    );
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = ctx.deposit_collateral(
+        &Round {
+            address: other.address,
+            ..round
+        },
+        SOL,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+}
+
+#[test]
+fn release_collateral_once_the_round_is_repaid() {
+    let mut ctx = TestContext::new();
+    let (round, _) = sol_collateralized_round(&mut ctx, SOL / 2);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+
+    let result = release(&mut ctx, &round, None);
+    assert_error(result, ErrorCode::CollateralNotReleasable);
+
+    ctx.withdraw(&round, SOL).unwrap();
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+    ctx.repay(&round, SOL + SOL / 10).unwrap();
+    let owner_before = ctx.svm.lamports(&round.company.owner);
+    let vault_lamports = ctx.svm.lamports(&collateral_vault_address(&round.address));
+
+    release(&mut ctx, &round, None).unwrap();
+
+    assert_eq!(
+        ctx.svm.lamports(&round.company.owner),
+        owner_before + vault_lamports
+    );
+    assert!(ctx
+        .svm
+        .account(&collateral_vault_address(&round.address))
+        .is_none());
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .collateral_amount,
+        0
+    );
+
+    let result = release(&mut ctx, &round, None);
+    assert_error(result, ErrorCode::NoCollateral);
+}
+
+#[test]
+fn release_token_collateral_of_a_cancelled_round() {
+    let mut ctx = TestContext::new();
+    let (round, _, mint) = token_collateralized_round(&mut ctx);
+    ctx.cancel(&round).unwrap();
+    let owner_tokens = ctx.svm.create_token_account(mint, round.company.owner, 0);
+    let other_mint = ctx.svm.create_mint();
+    let other_tokens = ctx
+        .svm
+        .create_token_account(other_mint, round.company.owner, 0);
+
+    let result = release(&mut ctx, &round, None);
+    assert_error(result, ErrorCode::MissingTokenAccounts);
+
+    let result = release(&mut ctx, &round, Some(other_tokens));
+    assert_error(result, ErrorCode::CollateralMintMismatch);
+
+    release(&mut ctx, &round, Some(owner_tokens)).unwrap();
+    assert_eq!(ctx.svm.token_balance(&owner_tokens), TOKENS);
+    assert_eq!(
+        ctx.svm
+            .token_balance(&collateral_vault_address(&round.address)),
+        0
+    );
+}
+
+#[test]
+fn release_collateral_stays_locked_when_a_failed_round_is_short() {
+    let mut ctx = TestContext::new();
+    let (round, _) = sol_collateralized_round(&mut ctx, SOL / 2);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    ctx.withdraw(&round, SOL / 4).unwrap();
+    // a round that failed after capital left its vault, as rounds could before withdrawals
+    // waited for the round to be funded
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.status = FundingRoundStatus::Failed;
+        funding_round.is_active = false;
+    });
+
+    let result = release(&mut ctx, &round, None);
+    assert_error(result, ErrorCode::CollateralNotReleasable);
+}
+
+#[test]
+fn release_collateral_is_for_the_owner() {
+    let mut ctx = TestContext::new();
+    let (round, _) = sol_collateralized_round(&mut ctx, SOL / 2);
+    let other = ctx.create_company_with_round(Window::default());
+    ctx.cancel(&round).unwrap();
+
+    let stranger = ctx.user();
+    let result = release(
+        &mut ctx,
+        &Round {
+            company: Company {
+                owner: stranger,
+                ..round.company
+            },
+            ..round
+        },
+        None,
+    );
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = release(
+        &mut ctx,
+        &Round {
+            company: other.company,
+            ..round
+        },
+        None,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+}
+
+#[test]
+fn liquidate_sol_collateral_into_the_repayment_vault() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = sol_collateralized_round(&mut ctx, SOL / 2);
+    let keeper = ctx.user();
+
+    let result = ctx.liquidate(keeper, &round, None);
+    assert_error(result, ErrorCode::FundingRoundNotInDefault);
+
+    // a round still open past its deadline has lent nothing, so it is finalized rather than defaulted
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    ctx.svm.warp(deadline as i64 - ctx.svm.now() + 1);
+    let result = ctx.liquidate(keeper, &round, None);
+    assert_error(result, ErrorCode::FundingRoundNotInDefault);
+    ctx.finalize(&round).unwrap();
+    ctx.withdraw(&round, SOL / 4).unwrap();
+
+    let result = ctx.liquidate(keeper, &round, None);
+    assert_error(result, ErrorCode::LiquidationGracePeriodActive);
+    let result = ctx.mark_defaulted(&round);
+    assert_error(result, ErrorCode::CollateralNotLiquidated);
+
+    ctx.svm.warp(LIQUIDATION_GRACE_PERIOD as i64);
+    let keeper_before = ctx.svm.lamports(&keeper);
+    let vault_before = ctx.svm.lamports(&round.vault);
+    let repayment_vault_before = ctx.svm.lamports(&round.repayment_vault);
+    ctx.liquidate(keeper, &round, None).unwrap();
+
+    let reward = SOL / 2 * KEEPER_REWARD_BPS as u64 / 10_000;
+    let proceeds = SOL / 2 - reward;
+    // the capital the company never withdrew goes back to investors with the collateral
+    let unwithdrawn = SOL - SOL / 4;
+    assert_eq!(ctx.svm.lamports(&keeper), keeper_before + reward);
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_before - unwithdrawn);
+    assert_eq!(ctx.account::<RoundVault>(&round.vault).tracked_balance, 0);
+    assert_eq!(
+        ctx.svm.lamports(&round.repayment_vault),
+        repayment_vault_before + proceeds + unwithdrawn
+    );
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Defaulted);
+    assert!(funding_round.collateral_liquidated);
+    assert_eq!(funding_round.liquidation_proceeds, proceeds);
+    assert_eq!(funding_round.released_capital, unwithdrawn);
+    assert_eq!(
+        ctx.account::<CompanyReputation>(&round.company.reputation)
+            .defaults,
+        1
+    );
+
+    // SOL collateral reaches investors through their repayment claims
+    let result = claim_collateral(&mut ctx, investor, &round, None);
+    assert_error(result, ErrorCode::NoCollateral);
+    let result = ctx.claim_refund(investor, &round);
+    assert_error(result, ErrorCode::FundingRoundNotRefundable);
+    let investor_before = ctx.svm.lamports(&investor);
+    ctx.claim_repayment(investor, &round).unwrap();
+    assert_eq!(
+        ctx.svm.lamports(&investor),
+        investor_before + proceeds + unwithdrawn
+    );
+
+    let result = ctx.liquidate(keeper, &round, None);
+    assert_error(result, ErrorCode::NoCollateral);
+}
+
+#[test]
+fn liquidate_sol_collateral_checks_the_repayment_vault() {
+    let mut ctx = TestContext::new();
+    let (round, _) = sol_collateralized_round(&mut ctx, SOL / 2);
+    let other = ctx.create_company_with_round(Window::default());
+    let keeper = ctx.user();
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    warp_past_grace_period(&mut ctx, &round);
+
+    let result = ctx.liquidate(
+        keeper,
+        &Round {
+            repayment_vault: other.repayment_vault,
+            ..round
+        },
+        None,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.liquidate(
+        keeper,
+        &Round {
+            vault: other.vault,
+            ..round
+        },
+        None,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.liquidate(
+        keeper,
+        &Round {
+            company: other.company,
+            ..round
+        },
+        None,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+}
+
+#[test]
+fn liquidate_requires_collateral() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let keeper = ctx.user();
+    warp_past_grace_period(&mut ctx, &round);
+
+    let result = ctx.liquidate(keeper, &round, None);
+
+    assert_error(result, ErrorCode::NoCollateral);
+}
+
+#[test]
+fn liquidate_token_collateral_for_investors_to_claim() {
+    let mut ctx = TestContext::new();
+    let (round, investor, mint) = token_collateralized_round(&mut ctx);
+    let keeper = ctx.user();
+    let keeper_tokens = ctx.svm.create_token_account(mint, keeper, 0);
+    let investor_tokens = ctx.svm.create_token_account(mint, investor, 0);
+    let other_mint = ctx.svm.create_mint();
+    let other_tokens = ctx.svm.create_token_account(other_mint, keeper, 0);
+
+    let result = claim_collateral(&mut ctx, investor, &round, Some(investor_tokens));
+    assert_error(result, ErrorCode::CollateralNotLiquidated);
+
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    warp_past_grace_period(&mut ctx, &round);
+    let result = ctx.liquidate(keeper, &round, None);
+    assert_error(result, ErrorCode::MissingTokenAccounts);
+    let result = ctx.liquidate(keeper, &round, Some(other_tokens));
+    assert_error(result, ErrorCode::CollateralMintMismatch);
+
+    ctx.liquidate(keeper, &round, Some(keeper_tokens)).unwrap();
+
+    let reward = TOKENS * KEEPER_REWARD_BPS as u64 / 10_000;
+    assert_eq!(ctx.svm.token_balance(&keeper_tokens), reward);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address)
+            .collateral_amount,
+        TOKENS - reward
+    );
+    let result = ctx.liquidate(keeper, &round, Some(keeper_tokens));
+    assert_error(result, ErrorCode::CollateralAlreadyLiquidated);
+
+    let result = claim_collateral(&mut ctx, investor, &round, None);
+    assert_error(result, ErrorCode::MissingTokenAccounts);
+    let investor_other_tokens = ctx.svm.create_token_account(other_mint, investor, 0);
+    let result = claim_collateral(&mut ctx, investor, &round, Some(investor_other_tokens));
+    assert_error(result, ErrorCode::CollateralMintMismatch);
+
+    claim_collateral(&mut ctx, investor, &round, Some(investor_tokens)).unwrap();
+    assert_eq!(ctx.svm.token_balance(&investor_tokens), TOKENS - reward);
+    let position: InvestorPosition =
+        ctx.account(&investor_position_address(&round.address, &investor));
+    assert!(position.collateral_claimed);
+
+    let result = claim_collateral(&mut ctx, investor, &round, Some(investor_tokens));
+    assert_error(result, ErrorCode::CollateralAlreadyClaimed);
+
+    // token collateral stays in its vault, but the unwithdrawn capital is repaid in SOL
+    let investor_before = ctx.svm.lamports(&investor);
+    ctx.claim_repayment(investor, &round).unwrap();
+    assert_eq!(ctx.svm.lamports(&investor), investor_before + SOL);
+}
+
+#[test]
+fn claim_collateral_requires_an_investor() {
+    let mut ctx = TestContext::new();
+    let (round, investor, mint) = token_collateralized_round(&mut ctx);
+    let keeper = ctx.user();
+    let keeper_tokens = ctx.svm.create_token_account(mint, keeper, 0);
+    let investor_tokens = ctx.svm.create_token_account(mint, investor, 0);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    warp_past_grace_period(&mut ctx, &round);
+    ctx.liquidate(keeper, &round, Some(keeper_tokens)).unwrap();
+    // refunds and liquidations exclude each other, so a refunded position here can only be forged
+    let position = investor_position_address(&round.address, &investor);
+    ctx.update_account::<InvestorPosition>(&position, |position| position.refunded = true);
+
+    let result = claim_collateral(&mut ctx, investor, &round, Some(investor_tokens));
+
+    assert_error(result, ErrorCode::NotAnInvestor);
+}
+
+#[test]
+fn claim_collateral_leaves_sol_collateral_to_repayment_claims() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = sol_collateralized_round(&mut ctx, SOL / 2);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    warp_past_grace_period(&mut ctx, &round);
+    let keeper = ctx.user();
+    ctx.liquidate(keeper, &round, None).unwrap();
+    // liquidation clears SOL collateral from the round as it moves it into the repayment vault,
+    // so only a forged round still records it
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.collateral_amount = SOL / 2;
+    });
+
+    let result = claim_collateral(&mut ctx, investor, &round, None);
+
+    assert_error(result, ErrorCode::CollateralClaimedThroughRepayment);
+}
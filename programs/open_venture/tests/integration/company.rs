@@ -0,0 +1,376 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{entrypoint::ProgramResult};
+use anchor_lang::system_program;
+use open_venture::{
+    error::ErrorCode, CompanyCategory, CompanyIndex, CompanyProfile, CompanyReputation,
+    CompanyVerification, OwnerRegistry, COMPANY_PROFILE_VERSION,
+};
+
+use crate::context::*;
+use crate::verification;
+
+pub fn update_metadata(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    company: &Company,
+    metadata_uri: &str,
+    country_code: Option<[u8; 2]>,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::UpdateCompanyMetadata {
+            owner: signer,
+            company_profile: company.profile,
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::UpdateCompanyMetadata {
+            metadata_uri: metadata_uri.to_string(),
+            category: Some(CompanyCategory::Fintech),
+            country_code,
+            document_hash: Some([7; 32]),
+        },
+    );
+    ctx.process(ix, &[signer])
+}
+
+pub fn register(ctx: &mut TestContext, company: &Company) -> ProgramResult {
+    let registry = owner_registry_address(&company.owner);
+    let company_count = match ctx.svm.account(&registry) {
+        Some(_) => ctx.account::<OwnerRegistry>(&registry).company_count,
+        None => 0,
+    };
+    let ix = instruction(
+        open_venture::accounts::RegisterCompanyProfile {
+            owner: company.owner,
+            company_profile: company.profile,
+            owner_registry: registry,
+            company_index: company_index_address(&company.owner, company_count),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::RegisterCompanyProfile {},
+    );
+    ctx.process(ix, &[company.owner])
+}
+
+/// Closes `company`, refunding its verification to the verifier that paid for it, if any.
+pub fn close(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    company: &Company,
+    index: u64,
+    last_index: Option<u64>,
+) -> ProgramResult {
+    let verification = company_verification_address(&company.profile);
+    let verifier = ctx
+        .svm
+        .account(&verification)
+        .map(|_| ctx.account::<CompanyVerification>(&verification).verifier);
+    close_refunding(ctx, signer, company, index, last_index, verifier)
+}
+
+pub fn close_refunding(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    company: &Company,
+    index: u64,
+    last_index: Option<u64>,
+    verifier: Option<Pubkey>,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::CloseCompanyProfile {
+            owner: signer,
+            company_profile: company.profile,
+            company_treasury: company.treasury,
+            company_reputation: company.reputation,
+            owner_registry: owner_registry_address(&company.owner),
+            company_index: company_index_address(&company.owner, index),
+            last_company_index: last_index
+                .map(|index| company_index_address(&company.owner, index)),
+            company_verification: company_verification_address(&company.profile),
+            verifier,
+        },
+        open_venture::instruction::CloseCompanyProfile {},
+    );
+    ctx.process(ix, &[signer])
+}
+
+/// Turns a company into one created before the owner registry existed.
+pub fn make_unregistered(ctx: &mut TestContext, company: &Company) {
+    ctx.update_account::<CompanyProfile>(&company.profile, |profile| profile.registered = false);
+    ctx.svm
+        .remove_account(&owner_registry_address(&company.owner));
+    ctx.svm
+        .remove_account(&company_index_address(&company.owner, 0));
+}
+
+#[test]
+fn create_company_profile() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+
+    ctx.create_company_profile(owner, "Acme", "Makes everything")
+        .unwrap();
+
+    let profile_address = company_profile_address(&owner, "Acme");
+    let profile: CompanyProfile = ctx.account(&profile_address);
+    assert_eq!(profile.owner, owner);
+    assert_eq!(profile.name, "Acme");
+    assert_eq!(profile.bio, "Makes everything");
+    assert_eq!(profile.active_round_count, 0);
+    assert_eq!(profile.max_active_rounds, 1);
+    assert_eq!(profile.version, COMPANY_PROFILE_VERSION);
+    assert!(profile.registered);
+
+    let reputation: CompanyReputation = ctx.account(&company_reputation_address(&profile_address));
+    assert_eq!(reputation.company, profile_address);
+    let registry: OwnerRegistry = ctx.account(&owner_registry_address(&owner));
+    assert_eq!(registry.company_count, 1);
+    assert_eq!(
+        registry.bump,
+        bump(&owner_registry_address(&owner), &[b"owner_registry", owner.as_ref()])
+    );
+    let index: CompanyIndex = ctx.account(&company_index_address(&owner, 0));
+    assert_eq!(index.company, profile_address);
+    assert_eq!(
+        index.bump,
+        bump(&company_index_address(&owner, 0), &[b"company_index", owner.as_ref(), &0u64.to_le_bytes()])
+    );
+    assert!(ctx
+        .svm
+        .account(&company_treasury_address(&owner, &profile_address))
+        .is_some());
+}
+
+#[test]
+fn create_company_profile_validates_name_and_bio() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+
+    let result = ctx.create_company_profile(owner, "", "bio");
+    assert_error(result, ErrorCode::CompanyNameRequired);
+
+    let result = ctx.create_company_profile(owner, &"a".repeat(33), "bio");
+    assert_error(result, ErrorCode::CompanyNameTooLong);
+
+    let result = ctx.create_company_profile(owner, "Acme", &"b".repeat(281));
+    assert_error(result, ErrorCode::CompanyBioTooLong);
+}
+
+#[test]
+fn update_company_metadata() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    update_metadata(
+        &mut ctx,
+        owner,
+        &company,
+        "https://acme.example/meta.json",
+        Some(*b"KE"),
+    )
+    .unwrap();
+
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    assert_eq!(profile.metadata_uri, "https://acme.example/meta.json");
+    assert_eq!(profile.category, Some(CompanyCategory::Fintech));
+    assert_eq!(profile.country_code, Some(*b"KE"));
+    assert_eq!(profile.document_hash, Some([7; 32]));
+}
+
+#[test]
+fn update_company_metadata_validates_input() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    let result = update_metadata(&mut ctx, owner, &company, &"u".repeat(201), None);
+    assert_error(result, ErrorCode::MetadataUriTooLong);
+
+    let result = update_metadata(&mut ctx, owner, &company, "", Some(*b"ke"));
+    assert_error(result, ErrorCode::InvalidCountryCode);
+
+    let stranger = ctx.user();
+    let result = update_metadata(&mut ctx, stranger, &company, "", None);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+}
+
+#[test]
+fn register_company_profile_lists_legacy_profiles() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    make_unregistered(&mut ctx, &company);
+
+    register(&mut ctx, &company).unwrap();
+
+    assert!(ctx.account::<CompanyProfile>(&company.profile).registered);
+    let registry: OwnerRegistry = ctx.account(&owner_registry_address(&owner));
+    assert_eq!(registry.company_count, 1);
+    let index: CompanyIndex = ctx.account(&company_index_address(&owner, 0));
+    assert_eq!(index.company, company.profile);
+}
+
+#[test]
+fn register_company_profile_rejects_registered_profiles() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    let result = register(&mut ctx, &company);
+
+    assert_error(result, ErrorCode::CompanyAlreadyRegistered);
+}
+
+#[test]
+fn close_company_profile_returns_rent_to_the_owner() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let balance_before = ctx.svm.lamports(&owner);
+    let rent = ctx.svm.lamports(&company.profile)
+        + ctx.svm.lamports(&company.treasury)
+        + ctx.svm.lamports(&company.reputation)
+        + ctx.svm.lamports(&company_index_address(&owner, 0));
+
+    close(&mut ctx, owner, &company, 0, None).unwrap();
+
+    assert_eq!(ctx.svm.lamports(&owner), balance_before + rent);
+    assert!(ctx.svm.account(&company.profile).is_none());
+    assert!(ctx.svm.account(&company.treasury).is_none());
+    assert!(ctx.svm.account(&company.reputation).is_none());
+    assert!(ctx.svm.account(&company_index_address(&owner, 0)).is_none());
+    assert_eq!(
+        ctx.account::<OwnerRegistry>(&owner_registry_address(&owner))
+            .company_count,
+        0
+    );
+}
+
+#[test]
+fn close_company_profile_moves_the_last_entry_into_the_freed_slot() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let first = ctx.create_company(owner);
+    let second = ctx.create_company(owner);
+
+    let result = close(&mut ctx, owner, &first, 0, None);
+    assert_error(result, ErrorCode::CompanyIndexMismatch);
+    let result = close(&mut ctx, owner, &first, 1, None);
+    assert_error(result, ErrorCode::CompanyIndexMismatch);
+
+    close(&mut ctx, owner, &first, 0, Some(1)).unwrap();
+
+    let index: CompanyIndex = ctx.account(&company_index_address(&owner, 0));
+    assert_eq!(index.company, second.profile);
+    assert!(ctx.svm.account(&company_index_address(&owner, 1)).is_none());
+    assert_eq!(
+        ctx.account::<OwnerRegistry>(&owner_registry_address(&owner))
+            .company_count,
+        1
+    );
+}
+
+#[test]
+fn close_company_profile_keeps_companies_that_raised() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let round = ctx.create_round(&company, Window::default());
+    ctx.cancel(&round).unwrap();
+
+    let result = close(&mut ctx, owner, &company, 0, None);
+
+    assert_error(result, ErrorCode::CompanyHasFundingRounds);
+}
+
+#[test]
+fn close_company_profile_rejects_unregistered_profiles() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let legacy = ctx.create_company(owner);
+    make_unregistered(&mut ctx, &legacy);
+    let listed = ctx.create_company(owner);
+    assert_eq!(
+        ctx.account::<CompanyIndex>(&company_index_address(&owner, 0))
+            .company,
+        listed.profile
+    );
+
+    let result = close(&mut ctx, owner, &legacy, 0, None);
+
+    assert_error(result, ErrorCode::CompanyNotRegistered);
+}
+
+#[test]
+fn close_company_profile_closes_the_verification() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    ctx.create_company_profile(owner, "Acme", "").unwrap();
+    let profile = company_profile_address(&owner, "Acme");
+    let company = Company {
+        owner,
+        profile,
+        treasury: company_treasury_address(&owner, &profile),
+        reputation: company_reputation_address(&profile),
+    };
+    let verifier = ctx.user();
+    let admin = ctx.admin;
+    verification::add_verifier(&mut ctx, admin, verifier).unwrap();
+    verification::verify(&mut ctx, verifier, &company, "Acme Holdings Ltd", "KE").unwrap();
+    let verification = company_verification_address(&company.profile);
+
+    let result = close_refunding(&mut ctx, owner, &company, 0, None, None);
+    assert_error(result, ErrorCode::VerifierRefundMismatch);
+    let result = close_refunding(&mut ctx, owner, &company, 0, None, Some(owner));
+    assert_error(result, ErrorCode::VerifierRefundMismatch);
+
+    let verifier_before = ctx.svm.lamports(&verifier);
+    let rent = ctx.svm.lamports(&verification);
+    close(&mut ctx, owner, &company, 0, None).unwrap();
+    assert_eq!(ctx.svm.lamports(&verifier), verifier_before + rent);
+    assert!(ctx.svm.account(&verification).is_none());
+
+    // a company recreated under the same name starts unverified
+    ctx.create_company_profile(owner, "Acme", "").unwrap();
+    assert!(ctx.svm.account(&verification).is_none());
+}
+
+#[test]
+fn close_company_profile_needs_a_listed_last_entry() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    // a registry that lists no companies has no last entry to move
+    ctx.update_account::<OwnerRegistry>(&owner_registry_address(&owner), |registry| {
+        registry.company_count = 0;
+    });
+
+    let result = close(&mut ctx, owner, &company, 0, Some(0));
+    assert_error(result, ErrorCode::CompanyNotRegistered);
+    let result = close(&mut ctx, owner, &company, 0, None);
+    assert_error(result, ErrorCode::CompanyNotRegistered);
+}
+
+#[test]
+fn close_company_profile_checks_owner_and_treasury() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let other = ctx.create_company(owner);
+
+    let stranger = ctx.user();
+    let result = close(&mut ctx, stranger, &company, 0, None);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = close(
+        &mut ctx,
+        owner,
+        &Company {
+            treasury: other.treasury,
+            ..company
+        },
+        0,
+        Some(1),
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
@@ -0,0 +1,303 @@
+//! Compute unit benchmark: every instruction runs through the flows integrators drive in the
+//! SBF build of the program, and the most compute units it consumed is compared with the
+//! baseline checked in at `tests/compute_units.baseline`. The benchmark fails when an
+//! instruction uses more than [`TOLERANCE_PERCENT`] over its baseline.
+//!
+//! Natively the runtime does not meter the program's own code, so the benchmark only runs
+//! against the `.so` in `SBF_OUT_DIR` and `cargo test` skips it. `make bench` builds the program
+//! and runs it. After an intended change, refresh the baseline with
+//! `UPDATE_BASELINE=1 make bench`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anchor_lang::solana_program::hash::hash;
+use open_venture::{CompanyProfile, FundingRound};
+
+use crate::context::*;
+use crate::svm::{Usage, SBF_OUT_DIR};
+use crate::{
+    active_rounds, amendments, collateral, company, funding_round, investor_gate, migration, vault,
+    verification,
+};
+
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compute_units.baseline");
+/// How far above its baseline an instruction may go before the benchmark fails.
+const TOLERANCE_PERCENT: u64 = 5;
+
+const INSTRUCTIONS: [&str; 35] = [
+    "initialize_config",
+    "update_config",
+    "create_company_profile",
+    "register_company_profile",
+    "close_company_profile",
+    "update_company_metadata",
+    "migrate_company_profile",
+    "migrate_funding_round",
+    "set_active_round_limit",
+    "create_funding_round",
+    "set_contribution_limits",
+    "set_investor_gate",
+    "issue_attestation",
+    "revoke_attestation",
+    "add_company_verifier",
+    "remove_company_verifier",
+    "verify_company",
+    "revoke_company_verification",
+    "fund_company",
+    "finalize_round",
+    "amend_funding_round",
+    "approve_amendment",
+    "request_extension",
+    "cancel_funding_round",
+    "claim_refund",
+    "deposit_collateral",
+    "deposit_token_collateral",
+    "release_collateral",
+    "claim_collateral",
+    "liquidate_collateral",
+    "mark_round_defaulted",
+    "claim_repayment",
+    "withdraw_funds",
+    "repay_funding_round",
+    "sweep_vault",
+];
+
+fn discriminator(instruction: &str) -> [u8; 8] {
+    hash(format!("global:{instruction}").as_bytes()).to_bytes()[..8]
+        .try_into()
+        .unwrap()
+}
+
+fn companies(ctx: &mut TestContext) {
+    let admin = ctx.admin;
+    ctx.update_config(admin, KEEPER_REWARD_BPS, 0).unwrap();
+
+    let owner = ctx.user();
+    let first = ctx.create_company(owner);
+    ctx.create_company(owner);
+    active_rounds::set_limit(ctx, owner, &first, MAX_ACTIVE_ROUNDS).unwrap();
+    company::update_metadata(ctx, owner, &first, "https://acme.example/meta.json", Some(*b"KE"))
+        .unwrap();
+    let verifier = ctx.user();
+    verification::add_verifier(ctx, admin, verifier).unwrap();
+    verification::verify(ctx, verifier, &first, "Acme Holdings Ltd", "KE").unwrap();
+    verification::revoke(ctx, verifier, &first).unwrap();
+    verification::remove_verifier(ctx, admin, verifier).unwrap();
+    // the last listed company moves into the freed slot
+    company::close(ctx, owner, &first, 0, Some(1)).unwrap();
+
+    let owner = ctx.user();
+    let unregistered = ctx.create_company(owner);
+    company::make_unregistered(ctx, &unregistered);
+    company::register(ctx, &unregistered).unwrap();
+}
+
+fn funding_and_repayment(ctx: &mut TestContext) {
+    let round = ctx.create_company_with_round(Window::default());
+    let owner = round.company.owner;
+    funding_round::set_contribution_limits(ctx, owner, &round, SOL / 10, SOL).unwrap();
+    let verifier = ctx.user();
+    investor_gate::set_investor_gate(ctx, owner, &round, None, Some(verifier)).unwrap();
+    let investor = ctx.user();
+    investor_gate::issue_attestation(ctx, verifier, investor, 0).unwrap();
+    // the first deposit opens the position, the second adds to it
+    investor_gate::fund_with_attestation(ctx, investor, &round, verifier).unwrap();
+    investor_gate::fund_with_attestation(ctx, investor, &round, verifier).unwrap();
+    investor_gate::revoke_attestation(ctx, verifier, investor).unwrap();
+
+    let deadline = ctx.now() + 2_000_000;
+    amendments::amend(ctx, owner, &round, SOL, 12, deadline).unwrap();
+    amendments::approve(ctx, investor, &round, 0).unwrap();
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    amendments::request_extension(ctx, owner, &round, deadline + MAX_AUTO_EXTENSION, SOL / 100, 2)
+        .unwrap();
+
+    ctx.withdraw(&round, SOL).unwrap();
+    let due = ctx
+        .account::<FundingRound>(&round.address)
+        .total_repayment_due()
+        .unwrap();
+    ctx.svm.airdrop(round.company.treasury, due);
+    ctx.repay(&round, due).unwrap();
+    ctx.claim_repayment(investor, &round).unwrap();
+    ctx.svm.airdrop(round.repayment_vault, SOL / 10);
+    vault::sweep(ctx, &round, round.repayment_vault).unwrap();
+}
+
+fn cancellation(ctx: &mut TestContext) {
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    ctx.cancel(&round).unwrap();
+    ctx.claim_refund(investor, &round).unwrap();
+}
+
+fn default_without_collateral(ctx: &mut TestContext) {
+    let (round, _) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL).unwrap();
+    collateral::warp_past_grace_period(ctx, &round);
+    ctx.mark_defaulted(&round).unwrap();
+}
+
+fn sol_collateral(ctx: &mut TestContext) {
+    // repaid, so the collateral goes back to the company
+    let (round, _) = collateral::sol_collateralized_round(ctx, SOL / 2);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    ctx.withdraw(&round, SOL).unwrap();
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+    ctx.repay(&round, SOL + SOL / 10).unwrap();
+    collateral::release(ctx, &round, None).unwrap();
+
+    // defaulted, so a keeper liquidates it for investors to claim
+    let (round, investor) = collateral::sol_collateralized_round(ctx, SOL / 2);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    collateral::warp_past_grace_period(ctx, &round);
+    let keeper = ctx.user();
+    ctx.liquidate(keeper, &round, None).unwrap();
+    ctx.claim_repayment(investor, &round).unwrap();
+}
+
+fn token_collateral(ctx: &mut TestContext) {
+    let (round, _, mint) = collateral::token_collateralized_round(ctx);
+    ctx.cancel(&round).unwrap();
+    let owner_tokens = ctx.svm.create_token_account(mint, round.company.owner, 0);
+    collateral::release(ctx, &round, Some(owner_tokens)).unwrap();
+
+    let (round, investor, mint) = collateral::token_collateralized_round(ctx);
+    let keeper = ctx.user();
+    let keeper_tokens = ctx.svm.create_token_account(mint, keeper, 0);
+    let investor_tokens = ctx.svm.create_token_account(mint, investor, 0);
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    collateral::warp_past_grace_period(ctx, &round);
+    ctx.liquidate(keeper, &round, Some(keeper_tokens)).unwrap();
+    collateral::claim_collateral(ctx, investor, &round, Some(investor_tokens)).unwrap();
+}
+
+fn migrations(ctx: &mut TestContext) {
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    migration::make_legacy::<CompanyProfile>(
+        ctx,
+        &company.profile,
+        migration::COMPANY_PROFILE_VERSIONED_FIELDS,
+        |profile| {
+            profile.version = 0;
+            profile.registered = false;
+        },
+    );
+    migration::migrate_company_profile(ctx, owner, &company).unwrap();
+
+    // both vaults get a header
+    let round = ctx.create_company_with_round(Window::default());
+    let round = migration::move_to_legacy_seeds(ctx, round);
+    migration::make_legacy::<FundingRound>(
+        ctx,
+        &round.address,
+        migration::FUNDING_ROUND_VERSIONED_FIELDS,
+        |round| {
+            round.version = 0;
+        },
+    );
+    migration::make_legacy_vault(ctx, &round.vault, SOL / 2);
+    migration::make_legacy_vault(ctx, &round.repayment_vault, 0);
+    migration::migrate_funding_round(ctx, round.company.owner, &round).unwrap();
+}
+
+/// The most each instruction consumed across the flows.
+fn measure() -> BTreeMap<&'static str, Usage> {
+    let names: HashMap<[u8; 8], &str> = INSTRUCTIONS
+        .into_iter()
+        .map(|name| (discriminator(name), name))
+        .collect();
+    let flows: [fn(&mut TestContext); 7] = [
+        companies,
+        funding_and_repayment,
+        cancellation,
+        default_without_collateral,
+        sol_collateral,
+        token_collateral,
+        migrations,
+    ];
+    let mut measured: BTreeMap<&str, Usage> = BTreeMap::new();
+    for flow in flows {
+        let mut ctx = TestContext::new();
+        flow(&mut ctx);
+        for (discriminator, usage) in ctx.svm.usage() {
+            let name = names
+                .get(discriminator)
+                .expect("every instruction is listed in INSTRUCTIONS");
+            let most = measured.entry(name).or_default();
+            most.compute_units = most.compute_units.max(usage.compute_units);
+        }
+    }
+    measured
+}
+
+fn parse_baseline(baseline: &str) -> BTreeMap<&str, Usage> {
+    baseline
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, compute_units] = fields[..] else {
+                panic!("malformed baseline line: {line}");
+            };
+            let usage = Usage {
+                compute_units: compute_units.parse().unwrap(),
+            };
+            (name, usage)
+        })
+        .collect()
+}
+
+fn format_baseline(measured: &BTreeMap<&str, Usage>) -> String {
+    let mut baseline = String::from(
+        "# Written by tests/integration/compute_units.rs from the program's SBF build.\n",
+    );
+    baseline.push_str(&format!("# {:<30} {:>13}\n", "instruction", "compute_units"));
+    for (name, usage) in measured {
+        baseline.push_str(&format!("{name:<32} {:>13}\n", usage.compute_units));
+    }
+    baseline
+}
+
+#[test]
+#[ignore = "measures the SBF build of the program, run it with `make bench`"]
+fn compute_units_stay_within_baseline() {
+    assert!(
+        std::env::var_os(SBF_OUT_DIR).is_some(),
+        "compute units are measured on the SBF build, set {SBF_OUT_DIR} or run `make bench`"
+    );
+    let measured = measure();
+    for name in INSTRUCTIONS {
+        assert!(measured.contains_key(name), "{name} is not benchmarked");
+    }
+    if std::env::var_os("UPDATE_BASELINE").is_some() {
+        std::fs::write(BASELINE, format_baseline(&measured)).unwrap();
+        return;
+    }
+    let baseline = std::fs::read_to_string(BASELINE).unwrap();
+    let baseline = parse_baseline(&baseline);
+    let mut regressions = Vec::new();
+    for (name, usage) in &measured {
+        let Some(expected) = baseline.get(name) else {
+            regressions.push(format!("{name} has no baseline"));
+            continue;
+        };
+        if usage.compute_units * 100 > expected.compute_units * (100 + TOLERANCE_PERCENT) {
+            regressions.push(format!(
+                "{name}: compute units went from {} to {}",
+                expected.compute_units, usage.compute_units
+            ));
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "compute units regressed by more than {TOLERANCE_PERCENT}%, rerun with UPDATE_BASELINE=1 if intended:\n{}",
+        regressions.join("\n")
+    );
+}
@@ -0,0 +1,70 @@
+use open_venture::{error::ErrorCode, ProgramConfig};
+
+use crate::context::*;
+
+#[test]
+fn initialize_config_makes_the_upgrade_authority_admin() {
+    let ctx = TestContext::new();
+
+    let config: ProgramConfig = ctx.account(&config_address());
+    assert_eq!(config.admin, ctx.admin);
+    assert_eq!(config.max_auto_extension, MAX_AUTO_EXTENSION);
+    assert_eq!(config.max_auto_extensions, MAX_AUTO_EXTENSIONS);
+    assert_eq!(config.liquidation_grace_period, LIQUIDATION_GRACE_PERIOD);
+    assert_eq!(config.keeper_reward_bps, KEEPER_REWARD_BPS);
+    assert_eq!(config.unproven_round_cap, 0);
+    assert_eq!(config.max_active_rounds, MAX_ACTIVE_ROUNDS);
+}
+
+#[test]
+fn initialize_config_rejects_other_signers() {
+    let mut ctx = TestContext::uninitialized();
+    let stranger = ctx.user();
+
+    let result = ctx.initialize_config(stranger, LIQUIDATION_GRACE_PERIOD, KEEPER_REWARD_BPS);
+
+    assert_error(result, ErrorCode::UnauthorizedConfigInitialization);
+    assert!(ctx.svm.account(&config_address()).is_none());
+}
+
+#[test]
+fn initialize_config_rejects_keeper_reward_above_100_percent() {
+    let mut ctx = TestContext::uninitialized();
+    let admin = ctx.admin;
+
+    let result = ctx.initialize_config(admin, LIQUIDATION_GRACE_PERIOD, 10_001);
+
+    assert_error(result, ErrorCode::InvalidKeeperReward);
+}
+
+#[test]
+fn update_config_by_admin() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin;
+
+    ctx.update_config(admin, 250, SOL).unwrap();
+
+    let config: ProgramConfig = ctx.account(&config_address());
+    assert_eq!(config.keeper_reward_bps, 250);
+    assert_eq!(config.unproven_round_cap, SOL);
+}
+
+#[test]
+fn update_config_rejects_non_admin() {
+    let mut ctx = TestContext::new();
+    let stranger = ctx.user();
+
+    let result = ctx.update_config(stranger, 250, 0);
+
+    assert_error(result, ErrorCode::UnauthorizedAdmin);
+}
+
+#[test]
+fn update_config_rejects_keeper_reward_above_100_percent() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin;
+
+    let result = ctx.update_config(admin, 10_001, 0);
+
+    assert_error(result, ErrorCode::InvalidKeeperReward);
+}
@@ -0,0 +1,648 @@
+//! Fixtures shared by the integration tests: PDA derivations, instruction builders for the
+//! common flows and assertions on program errors.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    entrypoint::ProgramResult, hash::hash, instruction::Instruction, program_error::ProgramError,
+};
+use anchor_lang::system_program;
+use anchor_lang::{AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use open_venture::error::ErrorCode;
+
+pub use crate::svm::account_already_in_use;
+use crate::svm::{program_data_address, Svm};
+
+pub const SOL: u64 = 1_000_000_000;
+pub const MAX_AUTO_EXTENSION: u64 = 7 * 86_400;
+pub const MAX_AUTO_EXTENSIONS: u8 = 1;
+pub const LIQUIDATION_GRACE_PERIOD: u64 = 86_400;
+pub const KEEPER_REWARD_BPS: u16 = 100;
+pub const MAX_ACTIVE_ROUNDS: u8 = 3;
+
+pub fn config_address() -> Pubkey {
+    pda(&[b"program_config"])
+}
+
+pub fn company_profile_address(owner: &Pubkey, name: &str) -> Pubkey {
+    pda(&[
+        b"company_profile",
+        owner.as_ref(),
+        hash(name.as_bytes()).as_ref(),
+    ])
+}
+
+pub fn company_treasury_address(owner: &Pubkey, company_profile: &Pubkey) -> Pubkey {
+    pda(&[
+        b"company_treasury",
+        owner.as_ref(),
+        company_profile.as_ref(),
+    ])
+}
+
+pub fn company_reputation_address(company_profile: &Pubkey) -> Pubkey {
+    pda(&[b"company_reputation", company_profile.as_ref()])
+}
+
+pub fn owner_registry_address(owner: &Pubkey) -> Pubkey {
+    pda(&[b"owner_registry", owner.as_ref()])
+}
+
+pub fn company_index_address(owner: &Pubkey, index: u64) -> Pubkey {
+    pda(&[b"company_index", owner.as_ref(), &index.to_le_bytes()])
+}
+
+pub fn company_verifier_address(authority: &Pubkey) -> Pubkey {
+    pda(&[b"company_verifier", authority.as_ref()])
+}
+
+pub fn company_verification_address(company_profile: &Pubkey) -> Pubkey {
+    pda(&[b"company_verification", company_profile.as_ref()])
+}
+
+pub fn funding_round_address(company_profile: &Pubkey, index: u64) -> Pubkey {
+    pda(&[
+        b"funding_round",
+        company_profile.as_ref(),
+        &index.to_le_bytes(),
+    ])
+}
+
+pub fn funding_round_vault_address(company_profile: &Pubkey, index: u64) -> Pubkey {
+    pda(&[
+        b"funding_round_vault",
+        company_profile.as_ref(),
+        &index.to_le_bytes(),
+    ])
+}
+
+pub fn repayment_vault_address(company_profile: &Pubkey, index: u64) -> Pubkey {
+    pda(&[
+        b"funding_round_repayment",
+        company_profile.as_ref(),
+        &index.to_le_bytes(),
+    ])
+}
+
+pub fn treasury_ledger_address(funding_round: &Pubkey) -> Pubkey {
+    pda(&[b"treasury_ledger", funding_round.as_ref()])
+}
+
+pub fn collateral_vault_address(funding_round: &Pubkey) -> Pubkey {
+    pda(&[b"collateral_vault", funding_round.as_ref()])
+}
+
+pub fn round_amendments_address(funding_round: &Pubkey) -> Pubkey {
+    pda(&[b"round_amendments", funding_round.as_ref()])
+}
+
+pub fn amendment_address(funding_round: &Pubkey, index: u32) -> Pubkey {
+    pda(&[b"amendment", funding_round.as_ref(), &index.to_le_bytes()])
+}
+
+pub fn amendment_approval_address(funding_round: &Pubkey, index: u32, investor: &Pubkey) -> Pubkey {
+    pda(&[
+        b"amendment_approval",
+        funding_round.as_ref(),
+        &index.to_le_bytes(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn investor_position_address(funding_round: &Pubkey, investor: &Pubkey) -> Pubkey {
+    pda(&[
+        b"investor_position",
+        funding_round.as_ref(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn investor_attestation_address(verifier: &Pubkey, investor: &Pubkey) -> Pubkey {
+    pda(&[
+        b"investor_attestation",
+        verifier.as_ref(),
+        investor.as_ref(),
+    ])
+}
+
+pub fn investor_portfolio_address(investor: &Pubkey) -> Pubkey {
+    pda(&[b"investor_portfolio", investor.as_ref()])
+}
+
+pub fn portfolio_entry_address(investor: &Pubkey, index: u64) -> Pubkey {
+    pda(&[b"portfolio_entry", investor.as_ref(), &index.to_le_bytes()])
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &open_venture::ID).0
+}
+
+/// Finds the canonical bump of `address`, checking that `seeds` derive it.
+pub fn bump(address: &Pubkey, seeds: &[&[u8]]) -> u8 {
+    let (expected, bump) = Pubkey::find_program_address(seeds, &open_venture::ID);
+    assert_eq!(*address, expected);
+    bump
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: open_venture::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Asserts that `result` failed with the program's `error`.
+#[track_caller]
+pub fn assert_error(result: ProgramResult, error: ErrorCode) {
+    assert_eq!(
+        result,
+        Err(ProgramError::Custom(error.into())),
+        "expected {error}"
+    );
+}
+
+/// Asserts that `result` failed with one of Anchor's own errors, e.g. a violated constraint.
+#[track_caller]
+pub fn assert_anchor_error(result: ProgramResult, error: anchor_lang::error::ErrorCode) {
+    assert_eq!(
+        result,
+        Err(ProgramError::Custom(error.into())),
+        "expected {error}"
+    );
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Company {
+    pub owner: Pubkey,
+    pub profile: Pubkey,
+    pub treasury: Pubkey,
+    pub reputation: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Round {
+    pub company: Company,
+    pub index: u64,
+    pub address: Pubkey,
+    pub vault: Pubkey,
+    pub repayment_vault: Pubkey,
+}
+
+/// Funding window of a round relative to the current clock.
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+    pub start: i64,
+    pub end: i64,
+    pub deadline: i64,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            start: -60,
+            end: 500_000,
+            deadline: 1_000_000,
+        }
+    }
+}
+
+pub struct TestContext {
+    pub svm: Svm,
+    pub admin: Pubkey,
+    company_count: u64,
+}
+
+impl TestContext {
+    /// A runtime with the program config initialized by its upgrade authority.
+    pub fn new() -> Self {
+        let mut context = Self::uninitialized();
+        let admin = context.admin;
+        context
+            .initialize_config(admin, LIQUIDATION_GRACE_PERIOD, KEEPER_REWARD_BPS)
+            .unwrap();
+        context
+    }
+
+    /// A runtime where the program config has not been initialized yet.
+    pub fn uninitialized() -> Self {
+        let mut svm = Svm::new();
+        let admin = svm.new_address();
+        svm.set_upgrade_authority(admin);
+        svm.airdrop(admin, 100 * SOL);
+        Self {
+            svm,
+            admin,
+            company_count: 0,
+        }
+    }
+
+    /// A new funded wallet.
+    pub fn user(&mut self) -> Pubkey {
+        let user = self.svm.new_address();
+        self.svm.airdrop(user, 100 * SOL);
+        user
+    }
+
+    pub fn now(&self) -> u64 {
+        self.svm.now() as u64
+    }
+
+    pub fn process(&mut self, instruction: Instruction, signers: &[Pubkey]) -> ProgramResult {
+        self.svm.process(instruction, signers)
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        self.svm.anchor_account(address)
+    }
+
+    /// Rewrites an Anchor account in place, e.g. to recreate state left behind by an older
+    /// version of the program.
+    pub fn update_account<T: AccountSerialize + AccountDeserialize>(
+        &mut self,
+        address: &Pubkey,
+        update: impl FnOnce(&mut T),
+    ) {
+        let mut account = self.svm.account(address).expect("account exists");
+        let mut state = T::try_deserialize(&mut account.data.as_slice()).unwrap();
+        update(&mut state);
+        let mut data = Vec::new();
+        state.try_serialize(&mut data).unwrap();
+        account.data[..data.len()].copy_from_slice(&data);
+        self.svm.set_account(*address, account);
+    }
+
+    pub fn initialize_config(
+        &mut self,
+        admin: Pubkey,
+        liquidation_grace_period: u64,
+        keeper_reward_bps: u16,
+    ) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::InitializeConfig {
+                admin,
+                config: config_address(),
+                program: open_venture::ID,
+                program_data: program_data_address(),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::InitializeConfig {
+                max_auto_extension: MAX_AUTO_EXTENSION,
+                max_auto_extensions: MAX_AUTO_EXTENSIONS,
+                liquidation_grace_period,
+                keeper_reward_bps,
+                unproven_round_cap: 0,
+                max_active_rounds: MAX_ACTIVE_ROUNDS,
+            },
+        );
+        self.process(ix, &[admin])
+    }
+
+    pub fn update_config(
+        &mut self,
+        admin: Pubkey,
+        keeper_reward_bps: u16,
+        unproven_round_cap: u64,
+    ) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::UpdateConfig {
+                admin,
+                config: config_address(),
+            },
+            open_venture::instruction::UpdateConfig {
+                max_auto_extension: MAX_AUTO_EXTENSION,
+                max_auto_extensions: MAX_AUTO_EXTENSIONS,
+                liquidation_grace_period: LIQUIDATION_GRACE_PERIOD,
+                keeper_reward_bps,
+                unproven_round_cap,
+                max_active_rounds: MAX_ACTIVE_ROUNDS,
+            },
+        );
+        self.process(ix, &[admin])
+    }
+
+    pub fn create_company_profile(
+        &mut self,
+        owner: Pubkey,
+        name: &str,
+        bio: &str,
+    ) -> ProgramResult {
+        let company_profile = company_profile_address(&owner, name);
+        let company_count = self
+            .svm
+            .account(&owner_registry_address(&owner))
+            .map_or(0, |_| {
+                self.account::<open_venture::OwnerRegistry>(&owner_registry_address(&owner))
+                    .company_count
+            });
+        let ix = instruction(
+            open_venture::accounts::CreateCompanyProfile {
+                owner,
+                company_profile,
+                company_treasury: company_treasury_address(&owner, &company_profile),
+                company_reputation: company_reputation_address(&company_profile),
+                owner_registry: owner_registry_address(&owner),
+                company_index: company_index_address(&owner, company_count),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::CreateCompanyProfile {
+                name: name.to_string(),
+                bio: bio.to_string(),
+            },
+        );
+        self.process(ix, &[owner])
+    }
+
+    /// Creates a company with a unique name for `owner`.
+    pub fn create_company(&mut self, owner: Pubkey) -> Company {
+        self.company_count += 1;
+        let name = format!("Company {}", self.company_count);
+        self.create_company_profile(owner, &name, "Test Bio")
+            .unwrap();
+        let profile = company_profile_address(&owner, &name);
+        Company {
+            owner,
+            profile,
+            treasury: company_treasury_address(&owner, &profile),
+            reputation: company_reputation_address(&profile),
+        }
+    }
+
+    pub fn create_funding_round_ix(
+        &self,
+        company: &Company,
+        index: u64,
+        target_amount: u64,
+        interest_rate: u64,
+        window: Window,
+    ) -> Instruction {
+        let now = self.svm.now();
+        instruction(
+            open_venture::accounts::CreateFundingRound {
+                owner: company.owner,
+                company_profile: company.profile,
+                funding_round: funding_round_address(&company.profile, index),
+                vault: funding_round_vault_address(&company.profile, index),
+                repayment_vault: repayment_vault_address(&company.profile, index),
+                treasury_ledger: treasury_ledger_address(&funding_round_address(&company.profile, index)),
+                collateral_vault: collateral_vault_address(&funding_round_address(&company.profile, index)),
+                company_reputation: company.reputation,
+                config: config_address(),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::CreateFundingRound {
+                label: format!("round-{index}"),
+                target_amount,
+                interest_rate,
+                repayment_deadline: (now + window.deadline) as u64,
+                funding_start: (now + window.start) as u64,
+                funding_end: (now + window.end) as u64,
+            },
+        )
+    }
+
+    /// Opens the company's next funding round raising 1 SOL at 10% interest.
+    pub fn create_round(&mut self, company: &Company, window: Window) -> Round {
+        let index = self
+            .account::<open_venture::CompanyProfile>(&company.profile)
+            .round_count;
+        let ix = self.create_funding_round_ix(company, index, SOL, 10, window);
+        self.process(ix, &[company.owner]).unwrap();
+        Round {
+            company: *company,
+            index,
+            address: funding_round_address(&company.profile, index),
+            vault: funding_round_vault_address(&company.profile, index),
+            repayment_vault: repayment_vault_address(&company.profile, index),
+        }
+    }
+
+    /// Creates a company owned by a new wallet with a fresh funding round.
+    pub fn create_company_with_round(&mut self, window: Window) -> Round {
+        let owner = self.user();
+        let company = self.create_company(owner);
+        self.create_round(&company, window)
+    }
+
+    /// Creates a round, raises its whole target from a single investor and finalizes it once
+    /// the funding window has closed.
+    pub fn create_funded_round(&mut self) -> (Round, Pubkey) {
+        let window = Window::default();
+        let round = self.create_company_with_round(window);
+        let investor = self.user();
+        self.fund(investor, &round, SOL).unwrap();
+        self.svm.warp(window.end);
+        self.finalize(&round).unwrap();
+        (round, investor)
+    }
+
+    pub fn fund_ix(
+        &self,
+        investor: Pubkey,
+        round: &Round,
+        amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+        attestation: Option<Pubkey>,
+    ) -> Instruction {
+        let investor_position = investor_position_address(&round.address, &investor);
+        let portfolio_slot = match self.svm.account(&investor_position) {
+            Some(_) => {
+                self.account::<open_venture::InvestorPosition>(&investor_position)
+                    .portfolio_index
+            }
+            None => self
+                .svm
+                .account(&investor_portfolio_address(&investor))
+                .map_or(0, |_| {
+                    self.account::<open_venture::InvestorPortfolio>(&investor_portfolio_address(
+                        &investor,
+                    ))
+                    .position_count
+                }),
+        };
+        instruction(
+            open_venture::accounts::FundCompany {
+                investor,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                investor_position,
+                investor_portfolio: investor_portfolio_address(&investor),
+                portfolio_entry: portfolio_entry_address(&investor, portfolio_slot),
+                attestation,
+                vault: round.vault,
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::FundCompany {
+                amount,
+                allowlist_proof,
+            },
+        )
+    }
+
+    pub fn fund(&mut self, investor: Pubkey, round: &Round, amount: u64) -> ProgramResult {
+        let ix = self.fund_ix(investor, round, amount, Vec::new(), None);
+        self.process(ix, &[investor])
+    }
+
+    pub fn finalize(&mut self, round: &Round) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::FinalizeRound {
+                company_profile: round.company.profile,
+                funding_round: round.address,
+            },
+            open_venture::instruction::FinalizeRound {},
+        );
+        let payer = self.admin;
+        self.process(ix, &[payer])
+    }
+
+    pub fn withdraw(&mut self, round: &Round, amount: u64) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::WithdrawFunds {
+                owner: round.company.owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                vault: round.vault,
+                company_treasury: round.company.treasury,
+                treasury_ledger: treasury_ledger_address(&round.address),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::WithdrawFunds { amount },
+        );
+        self.process(ix, &[round.company.owner])
+    }
+
+    pub fn repay(&mut self, round: &Round, amount: u64) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::RepayFundingRound {
+                owner: round.company.owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                company_treasury: round.company.treasury,
+                repayment_vault: round.repayment_vault,
+                treasury_ledger: treasury_ledger_address(&round.address),
+                company_reputation: round.company.reputation,
+            },
+            open_venture::instruction::RepayFundingRound { amount },
+        );
+        self.process(ix, &[round.company.owner])
+    }
+
+    pub fn claim_repayment(&mut self, investor: Pubkey, round: &Round) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::ClaimRepayment {
+                investor,
+                funding_round: round.address,
+                investor_position: investor_position_address(&round.address, &investor),
+                repayment_vault: round.repayment_vault,
+            },
+            open_venture::instruction::ClaimRepayment {},
+        );
+        self.process(ix, &[investor])
+    }
+
+    pub fn claim_refund(&mut self, investor: Pubkey, round: &Round) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::ClaimRefund {
+                investor,
+                funding_round: round.address,
+                investor_position: investor_position_address(&round.address, &investor),
+                vault: round.vault,
+            },
+            open_venture::instruction::ClaimRefund {},
+        );
+        self.process(ix, &[investor])
+    }
+
+    pub fn cancel(&mut self, round: &Round) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::CancelFundingRound {
+                owner: round.company.owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                repayment_vault: round.repayment_vault,
+            },
+            open_venture::instruction::CancelFundingRound {},
+        );
+        self.process(ix, &[round.company.owner])
+    }
+
+    pub fn deposit_collateral(&mut self, round: &Round, amount: u64) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::DepositCollateral {
+                owner: round.company.owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                collateral_vault: collateral_vault_address(&round.address),
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::DepositCollateral { amount },
+        );
+        self.process(ix, &[round.company.owner])
+    }
+
+    pub fn deposit_token_collateral(
+        &mut self,
+        round: &Round,
+        mint: Pubkey,
+        owner_token_account: Pubkey,
+        amount: u64,
+    ) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::DepositTokenCollateral {
+                owner: round.company.owner,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                collateral_mint: mint,
+                owner_token_account,
+                collateral_vault: collateral_vault_address(&round.address),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            open_venture::instruction::DepositTokenCollateral { amount },
+        );
+        self.process(ix, &[round.company.owner])
+    }
+
+    pub fn liquidate(
+        &mut self,
+        keeper: Pubkey,
+        round: &Round,
+        keeper_token_account: Option<Pubkey>,
+    ) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::LiquidateCollateral {
+                keeper,
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                collateral_vault: collateral_vault_address(&round.address),
+                vault: round.vault,
+                repayment_vault: round.repayment_vault,
+                treasury_ledger: treasury_ledger_address(&round.address),
+                company_reputation: round.company.reputation,
+                config: config_address(),
+                keeper_token_account,
+                token_program: keeper_token_account.map(|_| spl_token::ID),
+            },
+            open_venture::instruction::LiquidateCollateral {},
+        );
+        self.process(ix, &[keeper])
+    }
+
+    pub fn mark_defaulted(&mut self, round: &Round) -> ProgramResult {
+        let ix = instruction(
+            open_venture::accounts::MarkRoundDefaulted {
+                company_profile: round.company.profile,
+                funding_round: round.address,
+                vault: round.vault,
+                repayment_vault: round.repayment_vault,
+                treasury_ledger: treasury_ledger_address(&round.address),
+                company_reputation: round.company.reputation,
+                config: config_address(),
+            },
+            open_venture::instruction::MarkRoundDefaulted {},
+        );
+        let payer = self.admin;
+        self.process(ix, &[payer])
+    }
+}
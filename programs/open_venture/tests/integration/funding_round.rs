@@ -0,0 +1,613 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use open_venture::{
+    error::ErrorCode, CompanyProfile, FundingRound, FundingRoundStatus, InvestorPortfolio,
+    InvestorPosition, PortfolioEntry, FUNDING_ROUND_VERSION,
+};
+
+use crate::context::*;
+
+pub fn set_contribution_limits(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    round: &Round,
+    min_contribution: u64,
+    max_contribution: u64,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::SetContributionLimits {
+            owner: signer,
+            company_profile: round.company.profile,
+            funding_round: round.address,
+        },
+        open_venture::instruction::SetContributionLimits {
+            min_contribution,
+            max_contribution,
+        },
+    );
+    ctx.process(ix, &[signer])
+}
+
+fn create_round(
+    ctx: &mut TestContext,
+    company: &Company,
+    target_amount: u64,
+    interest_rate: u64,
+    window: Window,
+) -> ProgramResult {
+    let index = ctx.account::<CompanyProfile>(&company.profile).round_count;
+    let ix = ctx.create_funding_round_ix(company, index, target_amount, interest_rate, window);
+    ctx.process(ix, &[company.owner])
+}
+
+#[test]
+fn create_funding_round() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let now = ctx.now();
+
+    let round = ctx.create_round(&company, Window::default());
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.id, "round-0");
+    assert_eq!(funding_round.company, company.profile);
+    assert_eq!(funding_round.target_amount, SOL);
+    assert_eq!(funding_round.interest_rate, 10);
+    assert_eq!(funding_round.repayment_deadline, now + 1_000_000);
+    assert_eq!(funding_round.funding_start, now - 60);
+    assert_eq!(funding_round.funding_end, now + 500_000);
+    assert_eq!(funding_round.status, FundingRoundStatus::Open);
+    assert!(funding_round.is_active);
+    assert_eq!(funding_round.version, FUNDING_ROUND_VERSION);
+    assert_eq!(funding_round.index, 0);
+    assert!(funding_round.indexed);
+
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    assert_eq!(profile.active_round_count, 1);
+    assert_eq!(profile.round_count, 1);
+    assert!(ctx.svm.account(&round.vault).is_some());
+    assert!(ctx.svm.account(&round.repayment_vault).is_some());
+}
+
+#[test]
+fn create_funding_round_allows_one_active_round() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    ctx.create_round(&company, Window::default());
+
+    let result = create_round(&mut ctx, &company, SOL, 10, Window::default());
+
+    assert_error(result, ErrorCode::ActiveRoundLimitReached);
+}
+
+#[test]
+fn create_funding_round_validates_terms() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    let result = create_round(&mut ctx, &company, 0, 10, Window::default());
+    assert_error(result, ErrorCode::TargetAmountMustBeGreaterThanZero);
+
+    let result = create_round(&mut ctx, &company, SOL, 0, Window::default());
+    assert_error(result, ErrorCode::InterestRateMustBeGreaterThanZero);
+
+    let past_deadline = Window {
+        start: -300,
+        end: -200,
+        deadline: -100,
+    };
+    let result = create_round(&mut ctx, &company, SOL, 10, past_deadline);
+    assert_error(result, ErrorCode::RepaymentDeadlineInThePast);
+
+    let inverted = Window {
+        start: 100,
+        end: 50,
+        deadline: 1_000,
+    };
+    let result = create_round(&mut ctx, &company, SOL, 10, inverted);
+    assert_error(result, ErrorCode::InvalidFundingWindow);
+
+    let ends_after_deadline = Window {
+        start: 0,
+        end: 2_000,
+        deadline: 1_000,
+    };
+    let result = create_round(&mut ctx, &company, SOL, 10, ends_after_deadline);
+    assert_error(result, ErrorCode::InvalidFundingWindow);
+
+    let closed = Window {
+        start: -200,
+        end: -100,
+        deadline: 1_000,
+    };
+    let result = create_round(&mut ctx, &company, SOL, 10, closed);
+    assert_error(result, ErrorCode::FundingWindowEndInThePast);
+}
+
+#[test]
+fn create_funding_round_rejects_long_labels() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let mut ix = ctx.create_funding_round_ix(&company, 0, SOL, 10, Window::default());
+    // the label is the first argument, a borsh string right after the discriminator
+    let label = "l".repeat(37);
+    let mut data = ix.data[..8].to_vec();
+    data.extend_from_slice(&(label.len() as u32).to_le_bytes());
+    data.extend_from_slice(label.as_bytes());
+    data.extend_from_slice(&ix.data[8 + 4 + "round-0".len()..]);
+    ix.data = data;
+
+    let result = ctx.process(ix, &[owner]);
+
+    assert_error(result, ErrorCode::FundingRoundIdTooLong);
+}
+
+#[test]
+fn create_funding_round_caps_unproven_companies() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin;
+    ctx.update_config(admin, KEEPER_REWARD_BPS, SOL / 2)
+        .unwrap();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+
+    let result = create_round(&mut ctx, &company, SOL, 10, Window::default());
+    assert_error(result, ErrorCode::TargetAmountExceedsReputationCap);
+
+    create_round(&mut ctx, &company, SOL / 2, 10, Window::default()).unwrap();
+}
+
+#[test]
+fn create_funding_round_requires_the_owner() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let stranger = ctx.user();
+
+    let result = create_round(
+        &mut ctx,
+        &Company {
+            owner: stranger,
+            ..company
+        },
+        SOL,
+        10,
+        Window::default(),
+    );
+
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+}
+
+#[test]
+fn fund_company_records_the_position_and_portfolio() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    let vault_before = ctx.svm.lamports(&round.vault);
+
+    ctx.fund(investor, &round, SOL / 4).unwrap();
+    ctx.fund(investor, &round, SOL / 4).unwrap();
+
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_before + SOL / 2);
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).total_raised,
+        SOL / 2
+    );
+    let position: InvestorPosition =
+        ctx.account(&investor_position_address(&round.address, &investor));
+    assert_eq!(position.investor, investor);
+    assert_eq!(position.funding_round, round.address);
+    assert_eq!(position.amount, SOL / 2);
+    assert_eq!(position.portfolio_index, 0);
+
+    let portfolio: InvestorPortfolio = ctx.account(&investor_portfolio_address(&investor));
+    assert_eq!(portfolio.position_count, 1);
+    let entry: PortfolioEntry = ctx.account(&portfolio_entry_address(&investor, 0));
+    assert_eq!(entry.funding_round, round.address);
+    assert_eq!(
+        entry.investor_position,
+        investor_position_address(&round.address, &investor)
+    );
+}
+
+#[test]
+fn fund_company_lists_each_round_once_in_the_portfolio() {
+    let mut ctx = TestContext::new();
+    let first = ctx.create_company_with_round(Window::default());
+    let second = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+
+    ctx.fund(investor, &first, SOL / 2).unwrap();
+    ctx.fund(investor, &second, SOL / 2).unwrap();
+    ctx.fund(investor, &first, SOL / 2).unwrap();
+
+    let portfolio: InvestorPortfolio = ctx.account(&investor_portfolio_address(&investor));
+    assert_eq!(portfolio.position_count, 2);
+    let entry: PortfolioEntry = ctx.account(&portfolio_entry_address(&investor, 1));
+    assert_eq!(entry.funding_round, second.address);
+}
+
+#[test]
+fn fund_company_only_inside_the_funding_window() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window {
+        start: 100,
+        end: 1_000,
+        deadline: 2_000,
+    });
+    let investor = ctx.user();
+
+    let result = ctx.fund(investor, &round, SOL);
+    assert_error(result, ErrorCode::FundingWindowNotOpen);
+
+    ctx.svm.warp(100);
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+
+    ctx.svm.warp(900);
+    let result = ctx.fund(investor, &round, SOL / 2);
+    assert_error(result, ErrorCode::FundingWindowClosed);
+}
+
+#[test]
+fn fund_company_validates_the_deposit() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+
+    let result = ctx.fund(investor, &round, 0);
+    assert_error(result, ErrorCode::DepositAmountMustBeGreaterThanZero);
+
+    // the round raises its target and no more
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let result = ctx.fund(investor, &round, SOL / 2 + 1);
+    assert_error(result, ErrorCode::ContributionExceedsTarget);
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    assert_eq!(ctx.account::<FundingRound>(&round.address).total_raised, SOL);
+
+    let result = ctx.fund(
+        investor,
+        &Round {
+            vault: other.vault,
+            ..round
+        },
+        SOL,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.fund(
+        investor,
+        &Round {
+            company: other.company,
+            ..round
+        },
+        SOL,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    ctx.cancel(&round).unwrap();
+    let result = ctx.fund(investor, &round, SOL);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn contribution_limits_bound_each_investor() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let owner = round.company.owner;
+    let investor = ctx.user();
+
+    set_contribution_limits(&mut ctx, owner, &round, SOL / 10, SOL / 2).unwrap();
+
+    let result = ctx.fund(investor, &round, SOL / 20);
+    assert_error(result, ErrorCode::ContributionBelowMinimum);
+
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let result = ctx.fund(investor, &round, SOL / 10);
+    assert_error(result, ErrorCode::ContributionExceedsMaximum);
+}
+
+#[test]
+fn contribution_limits_are_validated() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+    let owner = round.company.owner;
+
+    let result = set_contribution_limits(&mut ctx, owner, &round, SOL, SOL / 2);
+    assert_error(result, ErrorCode::InvalidContributionLimits);
+
+    let stranger = ctx.user();
+    let result = set_contribution_limits(&mut ctx, stranger, &round, 0, 0);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = set_contribution_limits(
+        &mut ctx,
+        owner,
+        &Round {
+            address: other.address,
+            ..round
+        },
+        0,
+        0,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let result = set_contribution_limits(&mut ctx, owner, &round, 0, SOL);
+    assert_error(result, ErrorCode::FundingRoundHasDeposits);
+
+    ctx.cancel(&other).unwrap();
+    let result = set_contribution_limits(&mut ctx, other.company.owner, &other, 0, SOL);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn finalize_round_marks_fully_raised_rounds_funded() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+
+    let result = ctx.finalize(&round);
+    assert_error(result, ErrorCode::FundingWindowStillOpen);
+
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Funded);
+    assert!(funding_round.is_active);
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_eq!(profile.active_round_count, 1);
+
+    let result = ctx.finalize(&round);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
+
+#[test]
+fn finalize_round_fails_underfunded_rounds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    ctx.svm.warp(Window::default().end);
+
+    ctx.finalize(&round).unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Failed);
+    assert!(!funding_round.is_active);
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_eq!(profile.active_round_count, 0);
+
+    // the company is free to raise again
+    let next = ctx.create_round(&round.company, Window::default());
+    assert_eq!(next.index, 1);
+}
+
+#[test]
+fn finalize_round_checks_the_company() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+
+    let result = ctx.finalize(&Round {
+        company: other.company,
+        ..round
+    });
+
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+}
+
+#[test]
+fn withdraw_funds_moves_capital_to_the_treasury() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let treasury_before = ctx.svm.lamports(&round.company.treasury);
+
+    ctx.withdraw(&round, SOL).unwrap();
+
+    assert_eq!(
+        ctx.svm.lamports(&round.company.treasury),
+        treasury_before + SOL
+    );
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).total_withdrawn,
+        SOL
+    );
+}
+
+#[test]
+fn withdraw_funds_validates_the_withdrawal() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let (other, _) = ctx.create_funded_round();
+
+    let result = ctx.withdraw(&round, 0);
+    assert_error(result, ErrorCode::WithdrawalAmountMustBeGreaterThanZero);
+
+    let result = ctx.withdraw(&round, 2 * SOL);
+    assert_error(result, ErrorCode::InsufficientVaultFunds);
+
+    let result = ctx.withdraw(
+        &Round {
+            vault: other.vault,
+            ..round
+        },
+        SOL,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let stranger = ctx.user();
+    let company = Company {
+        owner: stranger,
+        ..round.company
+    };
+    let result = ctx.withdraw(&Round { company, ..round }, SOL);
+    assert_error(result, ErrorCode::UnauthorizedVaultAccess);
+
+    let company = Company {
+        treasury: other.company.treasury,
+        ..round.company
+    };
+    let result = ctx.withdraw(&Round { company, ..round }, SOL);
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
+
+#[test]
+fn withdraw_funds_keeps_failed_rounds_for_refunds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+
+    // the round may still fail while it is open
+    let result = ctx.withdraw(&round, SOL / 2);
+    assert_error(result, ErrorCode::FundingRoundNotFunded);
+
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    let result = ctx.withdraw(&round, SOL / 2);
+    assert_error(result, ErrorCode::FundingRoundNotFunded);
+}
+
+#[test]
+fn withdraw_funds_is_closed_once_the_round_is_repaid() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL / 2).unwrap();
+    ctx.svm.airdrop(round.company.treasury, SOL);
+    ctx.repay(&round, SOL + SOL / 10).unwrap();
+
+    let result = ctx.withdraw(&round, SOL / 2);
+    assert_error(result, ErrorCode::FundingRoundNotFunded);
+    ctx.claim_repayment(investor, &round).unwrap();
+}
+
+#[test]
+fn cancel_funding_round_refunds_investors() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+    let owner_before = ctx.svm.lamports(&round.company.owner);
+    let repayment_vault_rent = ctx.svm.lamports(&round.repayment_vault);
+
+    ctx.cancel(&round).unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Cancelled);
+    assert!(!funding_round.is_active);
+    assert_eq!(
+        ctx.account::<CompanyProfile>(&round.company.profile)
+            .active_round_count,
+        0
+    );
+    assert_eq!(
+        ctx.svm.lamports(&round.company.owner),
+        owner_before + repayment_vault_rent
+    );
+    assert!(ctx.svm.account(&round.repayment_vault).is_none());
+
+    let investor_before = ctx.svm.lamports(&investor);
+    ctx.claim_refund(investor, &round).unwrap();
+    assert_eq!(ctx.svm.lamports(&investor), investor_before + SOL / 2);
+
+    let result = ctx.claim_refund(investor, &round);
+    assert_error(result, ErrorCode::NothingToRefund);
+
+    // cancelling closed the repayment vault, so the round cannot be cancelled again
+    let result = ctx.cancel(&round);
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::AccountNotInitialized);
+}
+
+#[test]
+fn cancel_funding_round_is_blocked_once_capital_left_the_vault() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL / 2).unwrap();
+
+    let result = ctx.cancel(&round);
+
+    assert_error(result, ErrorCode::FundsAlreadyWithdrawn);
+}
+
+#[test]
+fn cancel_funding_round_checks_owner_and_vault() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+
+    let stranger = ctx.user();
+    let result = ctx.cancel(&Round {
+        company: Company {
+            owner: stranger,
+            ..round.company
+        },
+        ..round
+    });
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = ctx.cancel(&Round {
+        address: other.address,
+        ..round
+    });
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    let result = ctx.cancel(&Round {
+        repayment_vault: other.repayment_vault,
+        ..round
+    });
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
+
+#[test]
+fn claim_refund_returns_each_deposit_of_a_failed_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let first = ctx.user();
+    let second = ctx.user();
+    ctx.fund(first, &round, SOL / 4).unwrap();
+    ctx.fund(second, &round, SOL / 4).unwrap();
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+
+    for investor in [first, second] {
+        let before = ctx.svm.lamports(&investor);
+        ctx.claim_refund(investor, &round).unwrap();
+
+        assert_eq!(ctx.svm.lamports(&investor), before + SOL / 4);
+        assert!(
+            ctx.account::<InvestorPosition>(&investor_position_address(&round.address, &investor))
+                .refunded
+        );
+    }
+}
+
+#[test]
+fn claim_refund_requires_a_refundable_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL / 2).unwrap();
+
+    let result = ctx.claim_refund(investor, &round);
+    assert_error(result, ErrorCode::FundingRoundNotRefundable);
+
+    ctx.cancel(&round).unwrap();
+    let result = ctx.claim_refund(
+        investor,
+        &Round {
+            vault: other.vault,
+            ..round
+        },
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
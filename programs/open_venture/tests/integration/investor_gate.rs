@@ -0,0 +1,223 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    entrypoint::ProgramResult,
+    hash::{hash, hashv},
+};
+use anchor_lang::system_program;
+use open_venture::{error::ErrorCode, FundingRound, InvestorAttestation};
+
+use crate::context::*;
+
+pub fn set_investor_gate(
+    ctx: &mut TestContext,
+    signer: Pubkey,
+    round: &Round,
+    allowlist_root: Option<[u8; 32]>,
+    kyc_verifier: Option<Pubkey>,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::SetInvestorGate {
+            owner: signer,
+            company_profile: round.company.profile,
+            funding_round: round.address,
+        },
+        open_venture::instruction::SetInvestorGate {
+            allowlist_root,
+            kyc_verifier,
+        },
+    );
+    ctx.process(ix, &[signer])
+}
+
+pub fn issue_attestation(
+    ctx: &mut TestContext,
+    verifier: Pubkey,
+    investor: Pubkey,
+    expires_at: i64,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::IssueAttestation {
+            verifier,
+            investor,
+            attestation: investor_attestation_address(&verifier, &investor),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::IssueAttestation { expires_at },
+    );
+    ctx.process(ix, &[verifier])
+}
+
+pub fn revoke_attestation(ctx: &mut TestContext, verifier: Pubkey, investor: Pubkey) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::RevokeAttestation {
+            verifier,
+            attestation: investor_attestation_address(&verifier, &investor),
+        },
+        open_venture::instruction::RevokeAttestation {},
+    );
+    ctx.process(ix, &[verifier])
+}
+
+pub fn fund_with_attestation(
+    ctx: &mut TestContext,
+    investor: Pubkey,
+    round: &Round,
+    verifier: Pubkey,
+) -> ProgramResult {
+    let attestation = investor_attestation_address(&verifier, &investor);
+    let ix = ctx.fund_ix(investor, round, SOL / 2, Vec::new(), Some(attestation));
+    ctx.process(ix, &[investor])
+}
+
+fn leaf(investor: &Pubkey) -> [u8; 32] {
+    hash(investor.as_ref()).to_bytes()
+}
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&a, &b]).to_bytes()
+    } else {
+        hashv(&[&b, &a]).to_bytes()
+    }
+}
+
+#[test]
+fn allowlisted_investors_fund_with_a_proof() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let allowed = ctx.user();
+    let also_allowed = ctx.user();
+    let stranger = ctx.user();
+    let root = parent(leaf(&allowed), leaf(&also_allowed));
+
+    set_investor_gate(&mut ctx, round.company.owner, &round, Some(root), None).unwrap();
+    assert_eq!(
+        ctx.account::<FundingRound>(&round.address).allowlist_root,
+        Some(root)
+    );
+
+    let ix = ctx.fund_ix(allowed, &round, SOL / 2, vec![leaf(&also_allowed)], None);
+    ctx.process(ix, &[allowed]).unwrap();
+
+    let ix = ctx.fund_ix(stranger, &round, SOL / 2, vec![leaf(&also_allowed)], None);
+    assert_error(ctx.process(ix, &[stranger]), ErrorCode::InvestorNotEligible);
+    let result = ctx.fund(stranger, &round, SOL / 2);
+    assert_error(result, ErrorCode::InvestorNotEligible);
+}
+
+#[test]
+fn attested_investors_fund_kyc_gated_rounds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let verifier = ctx.user();
+    let investor = ctx.user();
+    set_investor_gate(&mut ctx, round.company.owner, &round, None, Some(verifier)).unwrap();
+
+    let result = ctx.fund(investor, &round, SOL / 2);
+    assert_error(result, ErrorCode::InvestorNotEligible);
+
+    issue_attestation(&mut ctx, verifier, investor, 0).unwrap();
+    let attestation: InvestorAttestation =
+        ctx.account(&investor_attestation_address(&verifier, &investor));
+    assert_eq!(attestation.verifier, verifier);
+    assert_eq!(attestation.investor, investor);
+    assert_eq!(attestation.issued_at, ctx.svm.now());
+
+    fund_with_attestation(&mut ctx, investor, &round, verifier).unwrap();
+}
+
+#[test]
+fn attestations_from_other_verifiers_are_ignored() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let verifier = ctx.user();
+    let other_verifier = ctx.user();
+    let investor = ctx.user();
+    set_investor_gate(&mut ctx, round.company.owner, &round, None, Some(verifier)).unwrap();
+    issue_attestation(&mut ctx, other_verifier, investor, 0).unwrap();
+
+    let result = fund_with_attestation(&mut ctx, investor, &round, other_verifier);
+
+    assert_error(result, ErrorCode::InvestorNotEligible);
+}
+
+#[test]
+fn attestations_expire() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let verifier = ctx.user();
+    let investor = ctx.user();
+    set_investor_gate(&mut ctx, round.company.owner, &round, None, Some(verifier)).unwrap();
+
+    let now = ctx.svm.now();
+
+    let result = issue_attestation(&mut ctx, verifier, investor, now);
+    assert_error(result, ErrorCode::AttestationExpiryInThePast);
+
+    issue_attestation(&mut ctx, verifier, investor, now + 100).unwrap();
+    fund_with_attestation(&mut ctx, investor, &round, verifier).unwrap();
+
+    ctx.svm.warp(100);
+    let result = fund_with_attestation(&mut ctx, investor, &round, verifier);
+    assert_error(result, ErrorCode::InvestorNotEligible);
+}
+
+#[test]
+fn revoked_attestations_no_longer_admit_investors() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let verifier = ctx.user();
+    let investor = ctx.user();
+    set_investor_gate(&mut ctx, round.company.owner, &round, None, Some(verifier)).unwrap();
+    issue_attestation(&mut ctx, verifier, investor, 0).unwrap();
+
+    let stranger = ctx.user();
+    let ix = instruction(
+        open_venture::accounts::RevokeAttestation {
+            verifier: stranger,
+            attestation: investor_attestation_address(&verifier, &investor),
+        },
+        open_venture::instruction::RevokeAttestation {},
+    );
+    assert_anchor_error(
+        ctx.process(ix, &[stranger]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+
+    revoke_attestation(&mut ctx, verifier, investor).unwrap();
+
+    assert!(ctx
+        .svm
+        .account(&investor_attestation_address(&verifier, &investor))
+        .is_none());
+    let result = ctx.fund(investor, &round, SOL / 2);
+    assert_error(result, ErrorCode::InvestorNotEligible);
+}
+
+#[test]
+fn investor_gate_is_set_by_the_owner_of_an_active_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+    let owner = round.company.owner;
+
+    let stranger = ctx.user();
+    let result = set_investor_gate(&mut ctx, stranger, &round, Some([1; 32]), None);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    let result = set_investor_gate(
+        &mut ctx,
+        owner,
+        &Round {
+            address: other.address,
+            ..round
+        },
+        None,
+        None,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    ctx.cancel(&round).unwrap();
+    let result = set_investor_gate(&mut ctx, owner, &round, None, None);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+}
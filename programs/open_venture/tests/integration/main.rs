@@ -0,0 +1,23 @@
+//! In-process integration tests for the program.
+//!
+//! Instructions run as transactions against a bank from `solana-program-test` (see [`svm`]). The
+//! program runs natively, so the suite runs under `cargo test` without a validator or a built
+//! `.so`, or in the SBF VM when `SBF_OUT_DIR` points at its build.
+
+mod context;
+mod svm;
+
+mod active_rounds;
+mod amendments;
+mod collateral;
+mod company;
+mod config;
+mod funding_round;
+mod investor_gate;
+mod migration;
+mod properties;
+mod repayment;
+mod compute_units;
+mod treasury;
+mod vault;
+mod verification;
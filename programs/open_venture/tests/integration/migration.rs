@@ -0,0 +1,548 @@
+use anchor_lang::prelude::{borsh, Pubkey};
+use anchor_lang::solana_program::{entrypoint::ProgramResult, hash::hash};
+use anchor_lang::system_program;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator, Space};
+use open_venture::{
+    error::ErrorCode, CompanyProfile, CompanyReputation, FundingRound, FundingRoundStatus,
+    ProgramConfig, RoundVault, VaultKind, ANCHOR_DISCRIMINATOR, COMPANY_PROFILE_RESERVED_SPACE,
+    COMPANY_PROFILE_VERSION, FUNDING_ROUND_RESERVED_SPACE, FUNDING_ROUND_VERSION,
+    LEGACY_FUNDING_ROUND_SPACE,
+};
+
+use crate::amendments::{amend, approve, request_extension};
+use crate::company::update_metadata;
+use crate::context::*;
+use crate::funding_round::set_contribution_limits;
+use crate::investor_gate::set_investor_gate;
+use crate::svm::rent_exempt;
+
+/// Size of the fields appended by versioning: `version`, the round index fields, the stored
+/// bumps, the active round fields, the treasury total and `reserved`.
+pub const COMPANY_PROFILE_VERSIONED_FIELDS: usize =
+    1 + 8 + 1 + 2 + 2 + 8 + COMPANY_PROFILE_RESERVED_SPACE;
+pub const FUNDING_ROUND_VERSIONED_FIELDS: usize = 1 + 8 + 1 + 5 + FUNDING_ROUND_RESERVED_SPACE;
+
+pub fn migrate_company_profile(
+    ctx: &mut TestContext,
+    authority: Pubkey,
+    company: &Company,
+) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::MigrateCompanyProfile {
+            authority,
+            config: config_address(),
+            company_profile: company.profile,
+            company_reputation: company.reputation,
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::MigrateCompanyProfile {},
+    );
+    ctx.process(ix, &[authority])
+}
+
+pub fn migrate_funding_round(ctx: &mut TestContext, authority: Pubkey, round: &Round) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::MigrateFundingRound {
+            authority,
+            config: config_address(),
+            company_profile: round.company.profile,
+            funding_round: round.address,
+            vault: round.vault,
+            repayment_vault: round.repayment_vault,
+            treasury_ledger: treasury_ledger_address(&round.address),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::MigrateFundingRound {},
+    );
+    ctx.process(ix, &[authority])
+}
+
+/// Rewrites an account the way a program version from before versioning laid it out: without
+/// the trailing `versioned_fields` bytes and sized to fit its data exactly.
+pub fn make_legacy<T: AccountSerialize + AccountDeserialize>(
+    ctx: &mut TestContext,
+    address: &Pubkey,
+    versioned_fields: usize,
+    update: impl FnOnce(&mut T),
+) {
+    let mut state: T = ctx.account(address);
+    update(&mut state);
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    data.truncate(data.len() - versioned_fields);
+    assert!(T::try_deserialize(&mut data.as_slice()).is_err());
+    ctx.svm
+        .set_account(*address, rent_exempt(data, open_venture::ID));
+}
+
+/// A company profile as laid out by the first program version.
+#[derive(AnchorSerialize)]
+struct BaselineCompanyProfile {
+    owner: Pubkey,
+    name: String,
+    bio: String,
+    active_funding_round: Option<Pubkey>,
+}
+
+const BASELINE_COMPANY_PROFILE_SPACE: usize = 32 + 4 + 32 + 4 + 280 + 1 + 32;
+
+/// A funding round as laid out by the first program version.
+#[derive(AnchorSerialize)]
+struct BaselineFundingRound {
+    id: String,
+    company: Pubkey,
+    target_amount: u64,
+    interest_rate: u64,
+    repayment_deadline: u64,
+    is_active: bool,
+}
+
+/// Writes `state` the way the first program version stored it: behind the current
+/// discriminator, in an account of that version's fixed size.
+fn write_baseline<T: AnchorSerialize>(
+    ctx: &mut TestContext,
+    address: &Pubkey,
+    discriminator: &[u8],
+    state: &T,
+    space: usize,
+) {
+    let mut data = discriminator.to_vec();
+    state.serialize(&mut data).unwrap();
+    data.resize(ANCHOR_DISCRIMINATOR + space, 0);
+    ctx.svm.set_account(*address, rent_exempt(data, open_venture::ID));
+}
+
+/// Rewrites a round created by the test context as a round of the first program version at its
+/// `hash(id)` seeds, without a treasury ledger and with legacy vaults holding `raised` and `repaid`.
+fn make_baseline_round(ctx: &mut TestContext, round: Round, raised: u64, repaid: u64) -> Round {
+    let round = move_to_legacy_seeds(ctx, round);
+    let funding_round: FundingRound = ctx.account(&round.address);
+    let baseline = BaselineFundingRound {
+        id: funding_round.id,
+        company: funding_round.company,
+        target_amount: funding_round.target_amount,
+        interest_rate: funding_round.interest_rate,
+        repayment_deadline: funding_round.repayment_deadline,
+        is_active: true,
+    };
+    write_baseline(ctx, &round.address, FundingRound::DISCRIMINATOR, &baseline, LEGACY_FUNDING_ROUND_SPACE);
+    make_legacy_vault(ctx, &round.vault, raised);
+    make_legacy_vault(ctx, &round.repayment_vault, repaid);
+    ctx.svm.remove_account(&treasury_ledger_address(&round.address));
+    round
+}
+
+/// Replaces a vault with one as created before vaults had a header: zero-space, holding
+/// `balance` on top of its rent.
+pub fn make_legacy_vault(ctx: &mut TestContext, address: &Pubkey, balance: u64) {
+    let mut account = rent_exempt(Vec::new(), open_venture::ID);
+    account.lamports += balance;
+    ctx.svm.set_account(*address, account);
+}
+
+#[test]
+fn migrate_company_profile_grows_legacy_profiles() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    make_legacy::<CompanyProfile>(
+        &mut ctx,
+        &company.profile,
+        COMPANY_PROFILE_VERSIONED_FIELDS,
+        |profile| {
+            profile.version = 0;
+            profile.registered = false;
+        },
+    );
+
+    migrate_company_profile(&mut ctx, owner, &company).unwrap();
+
+    let account = ctx.svm.account(&company.profile).unwrap();
+    assert_eq!(
+        account.data.len(),
+        ANCHOR_DISCRIMINATOR + CompanyProfile::INIT_SPACE
+    );
+    assert_eq!(
+        account.lamports,
+        rent_exempt(account.data.clone(), open_venture::ID).lamports
+    );
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    assert_eq!(profile.version, COMPANY_PROFILE_VERSION);
+    assert_eq!(profile.owner, owner);
+    assert_eq!(profile.round_count, 0);
+    assert_stored_profile_bumps(&profile, &company);
+
+    let result = migrate_company_profile(&mut ctx, owner, &company);
+    assert_error(result, ErrorCode::AccountAlreadyMigrated);
+}
+
+#[test]
+fn migrate_company_profile_by_owner_or_admin() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    make_legacy::<CompanyProfile>(
+        &mut ctx,
+        &company.profile,
+        COMPANY_PROFILE_VERSIONED_FIELDS,
+        |profile| {
+            profile.version = 0;
+        },
+    );
+
+    let stranger = ctx.user();
+    let result = migrate_company_profile(&mut ctx, stranger, &company);
+    assert_error(result, ErrorCode::UnauthorizedMigration);
+
+    let admin = ctx.admin;
+    migrate_company_profile(&mut ctx, admin, &company).unwrap();
+}
+
+#[test]
+fn migrate_funding_round_grows_legacy_rounds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let round = move_to_legacy_seeds(&mut ctx, round);
+    make_legacy::<FundingRound>(
+        &mut ctx,
+        &round.address,
+        FUNDING_ROUND_VERSIONED_FIELDS,
+        |round| {
+            round.version = 0;
+        },
+    );
+    make_legacy_vault(&mut ctx, &round.vault, SOL / 2);
+    // drained when the round was cancelled, so the runtime reclaimed it
+    ctx.svm.remove_account(&round.repayment_vault);
+
+    let stranger = ctx.user();
+    let result = migrate_funding_round(&mut ctx, stranger, &round);
+    assert_error(result, ErrorCode::UnauthorizedMigration);
+
+    let other = ctx.create_company_with_round(Window::default());
+    let result = migrate_funding_round(
+        &mut ctx,
+        round.company.owner,
+        &Round {
+            company: other.company,
+            ..round
+        },
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+
+    let account = ctx.svm.account(&round.address).unwrap();
+    assert_eq!(
+        account.data.len(),
+        ANCHOR_DISCRIMINATOR + FundingRound::INIT_SPACE
+    );
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.version, FUNDING_ROUND_VERSION);
+    assert_eq!(funding_round.company, round.company.profile);
+    assert_eq!(funding_round.target_amount, SOL);
+    let seed = hash(funding_round.id.as_bytes());
+    assert_stored_bumps(&funding_round, &round, seed.as_ref());
+    let vault: RoundVault = ctx.account(&round.vault);
+    assert_eq!(vault.funding_round, round.address);
+    assert_eq!(vault.kind, VaultKind::Funding);
+    assert_eq!(vault.tracked_balance, SOL / 2);
+    let account = ctx.svm.account(&round.vault).unwrap();
+    assert_eq!(
+        account.lamports,
+        rent_exempt(account.data.clone(), open_venture::ID).lamports + SOL / 2
+    );
+    assert!(ctx.svm.account(&round.repayment_vault).is_none());
+
+    let result = migrate_funding_round(&mut ctx, round.company.owner, &round);
+    assert_error(result, ErrorCode::AccountAlreadyMigrated);
+}
+
+#[test]
+fn migrate_rejects_accounts_of_other_programs() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let wallet = ctx.user();
+
+    let result = migrate_company_profile(
+        &mut ctx,
+        owner,
+        &Company {
+            owner,
+            profile: wallet,
+            treasury: wallet,
+            reputation: company_reputation_address(&wallet),
+        },
+    );
+
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintOwner);
+}
+
+fn assert_stored_profile_bumps(profile: &CompanyProfile, company: &Company) {
+    let owner = company.owner;
+    assert_eq!(
+        profile.bump,
+        bump(&company.profile, &[b"company_profile", owner.as_ref(), hash(profile.name.as_bytes()).as_ref()]),
+    );
+    assert_eq!(
+        profile.treasury_bump,
+        bump(&company.treasury, &[b"company_treasury", owner.as_ref(), company.profile.as_ref()]),
+    );
+}
+
+/// Checks the round's stored bumps against its PDAs, which are seeded by `round_seed`.
+fn assert_stored_bumps(funding_round: &FundingRound, round: &Round, round_seed: &[u8]) {
+    let profile = round.company.profile;
+    assert_eq!(funding_round.bump, bump(&round.address, &[b"funding_round", profile.as_ref(), round_seed]));
+    assert_eq!(funding_round.vault_bump, bump(&round.vault, &[b"funding_round_vault", profile.as_ref(), round_seed]));
+    assert_eq!(
+        funding_round.repayment_vault_bump,
+        bump(&round.repayment_vault, &[b"funding_round_repayment", profile.as_ref(), round_seed]),
+    );
+    assert_eq!(
+        funding_round.collateral_vault_bump,
+        bump(&collateral_vault_address(&round.address), &[b"collateral_vault", round.address.as_ref()]),
+    );
+}
+
+/// Moves a round and its vaults to the addresses seeded by `hash(id)`, where rounds created
+/// before rounds were numbered live.
+pub fn move_to_legacy_seeds(ctx: &mut TestContext, round: Round) -> Round {
+    let id = ctx.account::<FundingRound>(&round.address).id;
+    let seed = hash(id.as_bytes());
+    let derive = |prefix: &[u8]| {
+        Pubkey::find_program_address(&[prefix, round.company.profile.as_ref(), seed.as_ref()], &open_venture::ID).0
+    };
+    let legacy = Round {
+        address: derive(b"funding_round"),
+        vault: derive(b"funding_round_vault"),
+        repayment_vault: derive(b"funding_round_repayment"),
+        ..round
+    };
+    for (from, to) in [
+        (round.address, legacy.address),
+        (round.vault, legacy.vault),
+        (round.repayment_vault, legacy.repayment_vault),
+    ] {
+        let account = ctx.svm.account(&from).unwrap();
+        ctx.svm.remove_account(&from);
+        ctx.svm.set_account(to, account);
+    }
+    legacy
+}
+
+#[test]
+fn create_stores_canonical_bumps() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_stored_profile_bumps(&profile, &round.company);
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_stored_bumps(&funding_round, &round, &round.index.to_le_bytes());
+    let reputation: CompanyReputation = ctx.account(&round.company.reputation);
+    assert_eq!(
+        reputation.bump,
+        bump(&round.company.reputation, &[b"company_reputation", round.company.profile.as_ref()]),
+    );
+    let config: ProgramConfig = ctx.account(&config_address());
+    assert_eq!(config.bump, bump(&config_address(), &[b"program_config"]));
+}
+
+#[test]
+fn rounds_without_stored_bumps_must_be_migrated() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    // a round written by the previous layout version: same size, no stored bumps
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.version = 1;
+        funding_round.bump = 0;
+        funding_round.vault_bump = 0;
+        funding_round.repayment_vault_bump = 0;
+        funding_round.collateral_vault_bump = 0;
+    });
+
+    let result = ctx.withdraw(&round, SOL);
+    assert_error(result, ErrorCode::AccountNotMigrated);
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_stored_bumps(&funding_round, &round, &round.index.to_le_bytes());
+    ctx.withdraw(&round, SOL).unwrap();
+}
+
+#[test]
+fn rounds_must_be_migrated_before_they_are_managed() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    let owner = round.company.owner;
+    let deadline = ctx.account::<FundingRound>(&round.address).repayment_deadline;
+    amend(&mut ctx, owner, &round, SOL, 15, deadline).unwrap();
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.version = FUNDING_ROUND_VERSION - 1;
+    });
+
+    let results = [
+        amend(&mut ctx, owner, &round, SOL, 20, deadline),
+        approve(&mut ctx, investor, &round, 0),
+        request_extension(&mut ctx, owner, &round, deadline + 60, 0, 0),
+        ctx.finalize(&round),
+        ctx.mark_defaulted(&round),
+        ctx.deposit_collateral(&round, SOL),
+        set_contribution_limits(&mut ctx, owner, &round, 0, 0),
+        set_investor_gate(&mut ctx, owner, &round, None, None),
+    ];
+    for result in results {
+        assert_error(result, ErrorCode::AccountNotMigrated);
+    }
+
+    migrate_funding_round(&mut ctx, owner, &round).unwrap();
+    approve(&mut ctx, investor, &round, 0).unwrap();
+}
+
+#[test]
+fn migrate_company_profile_counts_the_active_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    // a profile written by the previous layout version, which tracked its one active round by key
+    ctx.update_account::<CompanyProfile>(&round.company.profile, |profile| {
+        profile.version = 2;
+        profile.active_funding_round = Some(round.address);
+        profile.active_round_count = 0;
+        profile.max_active_rounds = 0;
+    });
+
+    let result = ctx.cancel(&round);
+    assert_error(result, ErrorCode::AccountNotMigrated);
+
+    migrate_company_profile(&mut ctx, round.company.owner, &round.company).unwrap();
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_eq!(profile.active_funding_round, None);
+    assert_eq!(profile.active_round_count, 1);
+    assert_eq!(profile.max_active_rounds, 1);
+
+    ctx.cancel(&round).unwrap();
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_eq!(profile.active_round_count, 0);
+}
+
+#[test]
+fn update_company_metadata_grows_full_baseline_profiles() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let name = "n".repeat(32);
+    ctx.create_company_profile(owner, &name, &"b".repeat(280)).unwrap();
+    let profile_address = company_profile_address(&owner, &name);
+    let company = Company {
+        owner,
+        profile: profile_address,
+        treasury: company_treasury_address(&owner, &profile_address),
+        reputation: company_reputation_address(&profile_address),
+    };
+    // a name and bio of the longest length leave no room for the metadata fields
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    let baseline = BaselineCompanyProfile {
+        owner,
+        name: profile.name,
+        bio: profile.bio,
+        active_funding_round: None,
+    };
+    write_baseline(
+        &mut ctx,
+        &company.profile,
+        CompanyProfile::DISCRIMINATOR,
+        &baseline,
+        BASELINE_COMPANY_PROFILE_SPACE,
+    );
+
+    let stranger = ctx.user();
+    let result = update_metadata(&mut ctx, stranger, &company, "", None);
+    assert_error(result, ErrorCode::OwnerMustBeTheSameAsCompanyProfileOwner);
+
+    update_metadata(&mut ctx, owner, &company, "https://acme.example/meta.json", Some(*b"KE")).unwrap();
+    assert_eq!(
+        ctx.svm.account(&company.profile).unwrap().data.len(),
+        ANCHOR_DISCRIMINATOR + CompanyProfile::INIT_SPACE
+    );
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    assert_eq!(profile.bio, "b".repeat(280));
+    assert_eq!(profile.metadata_uri, "https://acme.example/meta.json");
+    assert_eq!(profile.country_code, Some(*b"KE"));
+    assert_eq!(profile.version, 0);
+
+    // the profile still needs migrating before it can manage rounds
+    migrate_company_profile(&mut ctx, owner, &company).unwrap();
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    assert_eq!(profile.version, COMPANY_PROFILE_VERSION);
+    assert_eq!(profile.metadata_uri, "https://acme.example/meta.json");
+}
+
+#[test]
+fn migrated_baseline_companies_can_open_rounds() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let profile: CompanyProfile = ctx.account(&company.profile);
+    let baseline = BaselineCompanyProfile {
+        owner,
+        name: profile.name,
+        bio: profile.bio,
+        active_funding_round: None,
+    };
+    write_baseline(
+        &mut ctx,
+        &company.profile,
+        CompanyProfile::DISCRIMINATOR,
+        &baseline,
+        BASELINE_COMPANY_PROFILE_SPACE,
+    );
+    // the first program version kept no reputation
+    ctx.svm.remove_account(&company.reputation);
+
+    migrate_company_profile(&mut ctx, owner, &company).unwrap();
+    let reputation: CompanyReputation = ctx.account(&company.reputation);
+    assert_eq!(reputation.company, company.profile);
+    assert_eq!(reputation.rounds_completed, 0);
+
+    let round = ctx.create_round(&company, Window::default());
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Open);
+}
+
+#[test]
+fn migrate_funding_round_closes_the_funding_of_baseline_rounds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let round = make_baseline_round(&mut ctx, round, SOL / 2, 0);
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Funded);
+    assert_eq!(funding_round.total_raised, SOL / 2);
+    assert_eq!(funding_round.funding_end, ctx.svm.now() as u64);
+    assert!(funding_round.is_active);
+
+    // its investors have no positions to refund, so it can't be failed by finalizing it
+    let result = ctx.finalize(&round);
+    assert_error(result, ErrorCode::FundingRoundNotActive);
+    ctx.withdraw(&round, SOL / 2).unwrap();
+}
+
+#[test]
+fn migrate_funding_round_completes_repaid_baseline_rounds() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let repayment = SOL + SOL / 10;
+    let round = make_baseline_round(&mut ctx, round, 0, repayment);
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Repaid);
+    assert_eq!(funding_round.total_raised, 0);
+    assert_eq!(funding_round.total_repaid, repayment);
+    assert!(!funding_round.is_active);
+    let profile: CompanyProfile = ctx.account(&round.company.profile);
+    assert_eq!(profile.active_round_count, 0);
+    let repayment_vault: RoundVault = ctx.account(&round.repayment_vault);
+    assert_eq!(repayment_vault.tracked_balance, repayment);
+}
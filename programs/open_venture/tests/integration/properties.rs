@@ -0,0 +1,395 @@
+//! Property-based tests: random sequences of round operations, checked against invariants
+//! on the round's accounting after every step.
+//!
+//! A failing case is shrunk to a minimal sequence and saved under `proptest-regressions/`,
+//! where it is replayed first on the next run.
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_lang::solana_program::{entrypoint::ProgramResult, program_error::ProgramError};
+use anchor_lang::{AnchorDeserialize, Space};
+use open_venture::{
+    FundingRound, FundingRoundStatus, InvestorPosition, RoundVault, ANCHOR_DISCRIMINATOR,
+};
+use proptest::prelude::*;
+
+use crate::context::*;
+use crate::svm::RuntimeError;
+
+const INVESTORS: usize = 3;
+const SCENARIO_CASES: u32 = 64;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Fund { investor: usize, amount: u64 },
+    /// Deposits whatever is left of the round's target, so rounds get funded.
+    FundRest { investor: usize },
+    Withdraw { amount: u64 },
+    /// Repays what the round owes, or an arbitrary amount when `amount` is set.
+    Repay { amount: Option<u64> },
+    ClaimRepayment { investor: usize },
+    ClaimRefund { investor: usize },
+    Cancel,
+    Finalize,
+    Liquidate,
+    MarkDefaulted,
+    Warp { seconds: i64 },
+    /// Transfers lamports straight into a vault, outside the program.
+    Donate { repayment: bool, amount: u64 },
+    Sweep { repayment: bool },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let investor = 0..INVESTORS;
+    prop_oneof![
+        4 => (investor.clone(), 1..=SOL).prop_map(|(investor, amount)| Action::Fund { investor, amount }),
+        1 => investor.clone().prop_map(|investor| Action::FundRest { investor }),
+        2 => (0..=2 * SOL).prop_map(|amount| Action::Withdraw { amount }),
+        2 => proptest::option::weighted(0.2, 0..=3 * SOL).prop_map(|amount| Action::Repay { amount }),
+        3 => investor.clone().prop_map(|investor| Action::ClaimRepayment { investor }),
+        3 => investor.prop_map(|investor| Action::ClaimRefund { investor }),
+        1 => Just(Action::Cancel),
+        3 => Just(Action::Finalize),
+        1 => Just(Action::Liquidate),
+        1 => Just(Action::MarkDefaulted),
+        3 => (0..600_000i64).prop_map(|seconds| Action::Warp { seconds }),
+        1 => (any::<bool>(), 1..=SOL).prop_map(|(repayment, amount)| Action::Donate { repayment, amount }),
+        1 => any::<bool>().prop_map(|repayment| Action::Sweep { repayment }),
+    ]
+}
+
+/// What investors have put in and taken out, as observed from their wallets.
+#[derive(Default)]
+struct Ledger {
+    deposited: [u64; INVESTORS],
+    refunded: [u64; INVESTORS],
+    claimed: [u64; INVESTORS],
+}
+
+struct Scenario {
+    ctx: TestContext,
+    round: Round,
+    investors: [Pubkey; INVESTORS],
+    donor: Pubkey,
+    keeper: Pubkey,
+    ledger: Ledger,
+    total_lamports: u128,
+}
+
+impl Scenario {
+    /// Opens a round for `target_amount`, backed by `collateral` lamports when it isn't zero.
+    fn new(target_amount: u64, interest_rate: u64, collateral: u64) -> Self {
+        let mut ctx = TestContext::new();
+        let owner = ctx.user();
+        let company = ctx.create_company(owner);
+        let ix = ctx.create_funding_round_ix(&company, 0, target_amount, interest_rate, Window::default());
+        ctx.process(ix, &[owner]).unwrap();
+        let round = Round {
+            company,
+            index: 0,
+            address: funding_round_address(&company.profile, 0),
+            vault: funding_round_vault_address(&company.profile, 0),
+            repayment_vault: repayment_vault_address(&company.profile, 0),
+        };
+        if collateral > 0 {
+            ctx.deposit_collateral(&round, collateral).unwrap();
+        }
+        let investors = [ctx.user(), ctx.user(), ctx.user()];
+        let donor = ctx.user();
+        let keeper = ctx.user();
+        // the company needs more than it raised to pay interest
+        ctx.svm.airdrop(company.treasury, 3 * SOL);
+        let total_lamports = ctx.svm.total_lamports();
+        Self {
+            ctx,
+            round,
+            investors,
+            donor,
+            keeper,
+            ledger: Ledger::default(),
+            total_lamports,
+        }
+    }
+
+    fn funding_round(&self) -> FundingRound {
+        self.ctx.account(&self.round.address)
+    }
+
+    fn run(&mut self, action: &Action) -> ProgramResult {
+        let round = self.round;
+        match *action {
+            Action::Fund { investor, amount } => {
+                let result = self.ctx.fund(self.investors[investor], &round, amount);
+                if result.is_ok() {
+                    self.ledger.deposited[investor] += amount;
+                }
+                result
+            }
+            Action::FundRest { investor } => {
+                let funding_round = self.funding_round();
+                let amount = funding_round.target_amount.saturating_sub(funding_round.total_raised);
+                self.run(&Action::Fund { investor, amount })
+            }
+            Action::Withdraw { amount } => self.ctx.withdraw(&round, amount),
+            Action::Repay { amount } => {
+                let amount = match amount {
+                    Some(amount) => amount,
+                    None => self.funding_round().total_repayment_due().unwrap(),
+                };
+                self.ctx.repay(&round, amount)
+            }
+            Action::ClaimRepayment { investor } => {
+                let wallet = self.investors[investor];
+                let before = self.ctx.svm.lamports(&wallet);
+                let result = self.ctx.claim_repayment(wallet, &round);
+                self.ledger.claimed[investor] += self.ctx.svm.lamports(&wallet) - before;
+                result
+            }
+            Action::ClaimRefund { investor } => {
+                let wallet = self.investors[investor];
+                let before = self.ctx.svm.lamports(&wallet);
+                let result = self.ctx.claim_refund(wallet, &round);
+                self.ledger.refunded[investor] += self.ctx.svm.lamports(&wallet) - before;
+                result
+            }
+            Action::Cancel => self.ctx.cancel(&round),
+            Action::Finalize => self.ctx.finalize(&round),
+            Action::Liquidate => self.ctx.liquidate(self.keeper, &round, None),
+            Action::MarkDefaulted => self.ctx.mark_defaulted(&round),
+            Action::Warp { seconds } => {
+                self.ctx.svm.warp(seconds);
+                Ok(())
+            }
+            Action::Donate { repayment, amount } => {
+                let vault = if repayment { round.repayment_vault } else { round.vault };
+                // a closed vault would be recreated as a system account, which isn't a vault
+                if self.ctx.svm.account(&vault).is_some() {
+                    let mut donor = self.ctx.svm.account(&self.donor).unwrap();
+                    donor.lamports -= amount;
+                    self.ctx.svm.set_account(self.donor, donor);
+                    self.ctx.svm.airdrop(vault, amount);
+                }
+                Ok(())
+            }
+            Action::Sweep { repayment } => {
+                let vault = if repayment { round.repayment_vault } else { round.vault };
+                let ix = instruction(
+                    open_venture::accounts::SweepVault {
+                        company_profile: round.company.profile,
+                        funding_round: round.address,
+                        vault,
+                        company_treasury: round.company.treasury,
+                        treasury_ledger: treasury_ledger_address(&round.address),
+                    },
+                    open_venture::instruction::SweepVault {},
+                );
+                self.ctx.process(ix, &[])
+            }
+        }
+    }
+
+    /// Drives the round to a terminal status: an open round is finalized once its funding
+    /// window closes, and a funded one defaults once its grace period has passed.
+    fn settle(&mut self) {
+        let round = self.round;
+        let funding_round = self.funding_round();
+        if funding_round.status == FundingRoundStatus::Open {
+            self.ctx.svm.warp((funding_round.funding_end as i64 - self.ctx.svm.now()).max(0) + 1);
+            self.ctx.finalize(&round).unwrap();
+        }
+        let funding_round = self.funding_round();
+        if funding_round.status == FundingRoundStatus::Funded {
+            let grace_period_end = funding_round.repayment_deadline + LIQUIDATION_GRACE_PERIOD;
+            self.ctx.svm.warp((grace_period_end as i64 - self.ctx.svm.now()).max(0) + 1);
+            let result = if funding_round.collateral_amount > 0 {
+                self.ctx.liquidate(self.keeper, &round, None)
+            } else {
+                self.ctx.mark_defaulted(&round)
+            };
+            result.unwrap();
+        }
+    }
+
+    fn claim_everything(&mut self) -> Result<(), TestCaseError> {
+        for investor in 0..INVESTORS {
+            for action in [Action::ClaimRefund { investor }, Action::ClaimRepayment { investor }] {
+                let result = self.run(&action);
+                prop_assert!(!is_runtime_violation(&result), "{action:?} broke the runtime rules: {result:?}");
+                self.check_invariants()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_invariants(&self) -> Result<(), TestCaseError> {
+        let funding_round = self.funding_round();
+        let ledger = &self.ledger;
+
+        prop_assert_eq!(self.ctx.svm.total_lamports(), self.total_lamports, "lamports were created or destroyed");
+
+        let deposited: u64 = ledger.deposited.iter().sum();
+        prop_assert_eq!(funding_round.total_raised, deposited);
+        prop_assert!(funding_round.total_raised <= funding_round.target_amount);
+        for (investor, wallet) in self.investors.iter().enumerate() {
+            let position = investor_position_address(&self.round.address, wallet);
+            if ledger.deposited[investor] == 0 {
+                prop_assert!(self.ctx.svm.account(&position).is_none());
+                continue;
+            }
+            let position: InvestorPosition = self.ctx.account(&position);
+            prop_assert_eq!(position.amount, ledger.deposited[investor]);
+            prop_assert_eq!(position.repayment_claimed, ledger.claimed[investor]);
+        }
+
+        // investors never get back more than the vault they are paid from received for them
+        let refunded: u64 = ledger.refunded.iter().sum();
+        let claimed: u64 = ledger.claimed.iter().sum();
+        let distributable = funding_round.distributable().unwrap();
+        prop_assert!(claimed <= distributable, "claimed {claimed} of {distributable} repaid");
+        for investor in 0..INVESTORS {
+            prop_assert!(ledger.refunded[investor] <= ledger.deposited[investor]);
+            let share = distributable as u128 * ledger.deposited[investor] as u128
+                / funding_round.total_raised.max(1) as u128;
+            prop_assert!(ledger.claimed[investor] as u128 <= share);
+        }
+        if refunded > 0 {
+            prop_assert!(matches!(
+                funding_round.status,
+                FundingRoundStatus::Failed | FundingRoundStatus::Cancelled
+            ));
+        }
+        // capital only leaves the vault of a round that closed funded, so refunds are never short
+        if funding_round.total_withdrawn > 0 {
+            prop_assert!(matches!(
+                funding_round.status,
+                FundingRoundStatus::Funded | FundingRoundStatus::Repaid | FundingRoundStatus::Defaulted
+            ));
+        }
+
+        // vaults hold at least what they track, and track exactly what flowed through them
+        let rent = Rent::default().minimum_balance(ANCHOR_DISCRIMINATOR + RoundVault::INIT_SPACE);
+        let vault: RoundVault = self.ctx.account(&self.round.vault);
+        prop_assert!(self.ctx.svm.lamports(&self.round.vault) >= rent + vault.tracked_balance);
+        prop_assert_eq!(
+            vault.tracked_balance,
+            funding_round.total_raised - funding_round.total_withdrawn - funding_round.released_capital - refunded
+        );
+        if self.ctx.svm.account(&self.round.repayment_vault).is_some() {
+            let repayment_vault: RoundVault = self.ctx.account(&self.round.repayment_vault);
+            prop_assert!(
+                self.ctx.svm.lamports(&self.round.repayment_vault) >= rent + repayment_vault.tracked_balance
+            );
+            prop_assert_eq!(repayment_vault.tracked_balance, distributable - claimed);
+        } else {
+            prop_assert_eq!(funding_round.status, FundingRoundStatus::Cancelled);
+        }
+
+        Ok(())
+    }
+}
+
+/// Any `u64`, with the small values and the values next to `u64::MAX` where overflow hides
+/// drawn far more often than uniform sampling would.
+fn edgy_u64() -> impl Strategy<Value = u64> {
+    prop_oneof![any::<u64>(), 0..1_000u64, u64::MAX - 1_000..=u64::MAX]
+}
+
+/// Errors the runtime raises when an instruction breaks its rules rather than the
+/// program rejecting it.
+fn is_runtime_violation(result: &ProgramResult) -> bool {
+    matches!(result, Err(ProgramError::Custom(code)) if *code >= RuntimeError::LOWEST_CODE)
+}
+
+/// Each case starts a bank of its own, so scenarios run fewer cases than proptest's default
+/// unless `PROPTEST_CASES` asks for more.
+fn scenario_config() -> ProptestConfig {
+    let cases = std::env::var("PROPTEST_CASES")
+        .ok()
+        .and_then(|cases| cases.parse().ok())
+        .unwrap_or(SCENARIO_CASES);
+    ProptestConfig::with_cases(cases)
+}
+
+proptest! {
+    #![proptest_config(scenario_config())]
+
+    #[test]
+    fn round_accounting_holds_for_any_sequence(
+        target_amount in SOL / 10..=2 * SOL,
+        interest_rate in 1..=50u64,
+        collateral in prop_oneof![Just(0), 1..=SOL],
+        actions in proptest::collection::vec(action(), 1..60),
+    ) {
+        let mut scenario = Scenario::new(target_amount, interest_rate, collateral);
+        for action in &actions {
+            let result = scenario.run(action);
+            prop_assert!(!is_runtime_violation(&result), "{action:?} broke the runtime rules: {result:?}");
+            scenario.check_invariants()?;
+        }
+    }
+
+    #[test]
+    fn deposits_can_be_claimed_once_the_round_ends(
+        target_amount in SOL / 10..=2 * SOL,
+        interest_rate in 1..=50u64,
+        collateral in prop_oneof![Just(0), 1..=SOL],
+        actions in proptest::collection::vec(action(), 0..30),
+    ) {
+        let mut scenario = Scenario::new(target_amount, interest_rate, collateral);
+        for action in &actions {
+            let _ = scenario.run(action);
+        }
+        scenario.settle();
+        scenario.claim_everything()?;
+
+        // what investors are owed has all been paid out, short of the rounding on each share;
+        // capital left in the vault of a repaid round is the company's, as investors were repaid
+        let rounding = INVESTORS as u64;
+        let funding_round = scenario.funding_round();
+        let vault: RoundVault = scenario.ctx.account(&scenario.round.vault);
+        if funding_round.status != FundingRoundStatus::Repaid {
+            prop_assert!(vault.tracked_balance < rounding, "{} lamports stuck in the vault", vault.tracked_balance);
+        }
+        if scenario.ctx.svm.account(&scenario.round.repayment_vault).is_some() {
+            let repayment_vault: RoundVault = scenario.ctx.account(&scenario.round.repayment_vault);
+            prop_assert!(
+                repayment_vault.tracked_balance < rounding,
+                "{} lamports stuck in the repayment vault",
+                repayment_vault.tracked_balance
+            );
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn total_repayment_due_covers_principal_and_interest(
+        target_amount in edgy_u64(),
+        interest_rate in edgy_u64(),
+        extension_fees in edgy_u64(),
+    ) {
+        // all-zero bytes are a valid encoding of a round
+        let mut funding_round =
+            FundingRound::deserialize(&mut &[0u8; FundingRound::INIT_SPACE][..]).unwrap();
+        funding_round.target_amount = target_amount;
+        funding_round.interest_rate = interest_rate;
+        funding_round.extension_fees = extension_fees;
+
+        // near `u64::MAX` the product overflows even a u128
+        let with_interest = (100 + interest_rate as u128).checked_mul(target_amount as u128);
+        let expected = match with_interest {
+            Some(with_interest)
+                if interest_rate <= u64::MAX - 100 && with_interest <= u64::MAX as u128 =>
+            {
+                u64::try_from(with_interest / 100 + extension_fees as u128).ok()
+            }
+            _ => None,
+        };
+        match funding_round.total_repayment_due() {
+            Ok(due) => {
+                prop_assert_eq!(Some(due), expected);
+                prop_assert!(due >= target_amount);
+            }
+            Err(_) => prop_assert_eq!(expected, None),
+        }
+    }
+}
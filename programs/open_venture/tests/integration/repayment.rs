@@ -0,0 +1,313 @@
+use open_venture::{
+    error::ErrorCode, CompanyProfile, CompanyReputation, FundingRound, FundingRoundStatus, RoundVault,
+};
+
+use crate::context::*;
+
+const DAY: i64 = 86_400;
+/// Amount owed on the default round: 1 SOL at 10% interest.
+const REPAYMENT: u64 = SOL + SOL / 10;
+
+/// Moves the raised capital to the treasury and tops it up with the interest owed.
+fn prepare_repayment(ctx: &mut TestContext, round: &Round) {
+    ctx.withdraw(round, SOL).unwrap();
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+}
+
+#[test]
+fn repay_funding_round_pays_investors_back_with_interest() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    prepare_repayment(&mut ctx, &round);
+    let treasury_before = ctx.svm.lamports(&round.company.treasury);
+
+    ctx.repay(&round, REPAYMENT).unwrap();
+
+    assert_eq!(
+        ctx.svm.lamports(&round.company.treasury),
+        treasury_before - REPAYMENT
+    );
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Repaid);
+    assert!(!funding_round.is_active);
+    assert_eq!(funding_round.total_repaid, REPAYMENT);
+    assert_eq!(
+        ctx.account::<CompanyProfile>(&round.company.profile)
+            .active_round_count,
+        0
+    );
+    let reputation: CompanyReputation = ctx.account(&round.company.reputation);
+    assert_eq!(reputation.rounds_completed, 1);
+    assert_eq!(reputation.total_raised, SOL);
+    assert_eq!(reputation.total_repaid, REPAYMENT);
+    assert_eq!(reputation.days_late, 0);
+
+    let investor_before = ctx.svm.lamports(&investor);
+    ctx.claim_repayment(investor, &round).unwrap();
+    assert_eq!(ctx.svm.lamports(&investor), investor_before + REPAYMENT);
+
+    let result = ctx.claim_repayment(investor, &round);
+    assert_error(result, ErrorCode::NothingToClaim);
+}
+
+#[test]
+fn claim_repayment_is_pro_rata() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let first = ctx.user();
+    let second = ctx.user();
+    ctx.fund(first, &round, SOL / 4).unwrap();
+    ctx.fund(second, &round, SOL * 3 / 4).unwrap();
+    ctx.svm.warp(Window::default().end);
+    ctx.finalize(&round).unwrap();
+    prepare_repayment(&mut ctx, &round);
+    ctx.repay(&round, REPAYMENT).unwrap();
+
+    let before = ctx.svm.lamports(&first);
+    ctx.claim_repayment(first, &round).unwrap();
+
+    assert_eq!(ctx.svm.lamports(&first), before + REPAYMENT / 4);
+}
+
+#[test]
+fn late_repayments_count_days_late() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    prepare_repayment(&mut ctx, &round);
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    ctx.svm.warp(deadline as i64 - ctx.svm.now() + 2 * DAY + 60);
+
+    ctx.repay(&round, REPAYMENT).unwrap();
+
+    assert_eq!(
+        ctx.account::<CompanyReputation>(&round.company.reputation)
+            .days_late,
+        2
+    );
+}
+
+#[test]
+fn repaid_companies_can_raise_more() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin;
+    ctx.update_config(admin, KEEPER_REWARD_BPS, SOL).unwrap();
+    let (round, _) = ctx.create_funded_round();
+    prepare_repayment(&mut ctx, &round);
+    ctx.repay(&round, REPAYMENT).unwrap();
+
+    let index = ctx
+        .account::<CompanyProfile>(&round.company.profile)
+        .round_count;
+    let ix = ctx.create_funding_round_ix(
+        &round.company,
+        index,
+        SOL + REPAYMENT,
+        10,
+        Window::default(),
+    );
+    ctx.process(ix, &[round.company.owner]).unwrap();
+}
+
+#[test]
+fn repay_funding_round_validates_the_repayment() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let (other, _) = ctx.create_funded_round();
+
+    let result = ctx.repay(&round, SOL);
+    assert_error(result, ErrorCode::RepaymentAmountExceedsTargetAmount);
+
+    let result = ctx.repay(&round, REPAYMENT);
+    assert_error(result, ErrorCode::InsufficientVaultFunds);
+
+    prepare_repayment(&mut ctx, &round);
+    let result = ctx.repay(
+        &Round {
+            repayment_vault: other.repayment_vault,
+            ..round
+        },
+        REPAYMENT,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.repay(
+        &Round {
+            address: other.address,
+            ..round
+        },
+        REPAYMENT,
+    );
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    // repaying out of another company's treasury
+    let result = ctx.repay(
+        &Round {
+            company: Company {
+                treasury: other.company.treasury,
+                ..round.company
+            },
+            ..round
+        },
+        REPAYMENT,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let stranger = ctx.user();
+    let result = ctx.repay(
+        &Round {
+            company: Company {
+                owner: stranger,
+                ..round.company
+            },
+            ..round
+        },
+        REPAYMENT,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintHasOne);
+
+    ctx.repay(&round, REPAYMENT).unwrap();
+    let result = ctx.repay(&round, REPAYMENT);
+    assert_error(result, ErrorCode::FundingRoundNotFunded);
+}
+
+#[test]
+fn repay_funding_round_requires_a_funded_round() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    ctx.svm.airdrop(round.company.treasury, REPAYMENT);
+
+    // the round is still open, so its investors could not yet have been lent anything
+    let result = ctx.repay(&round, REPAYMENT);
+    assert_error(result, ErrorCode::FundingRoundNotFunded);
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Open);
+    assert_eq!(funding_round.total_repaid, 0);
+}
+
+#[test]
+fn claim_repayment_requires_a_settled_round() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    let (other, _) = ctx.create_funded_round();
+
+    let result = ctx.claim_repayment(investor, &round);
+    assert_error(result, ErrorCode::NothingToClaim);
+
+    prepare_repayment(&mut ctx, &round);
+    ctx.repay(&round, REPAYMENT).unwrap();
+    let result = ctx.claim_repayment(
+        investor,
+        &Round {
+            repayment_vault: other.repayment_vault,
+            ..round
+        },
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
+
+#[test]
+fn mark_round_defaulted_after_the_grace_period() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL).unwrap();
+
+    let result = ctx.mark_defaulted(&round);
+    assert_error(result, ErrorCode::FundingRoundNotInDefault);
+
+    let deadline = ctx
+        .account::<FundingRound>(&round.address)
+        .repayment_deadline;
+    ctx.svm.warp(deadline as i64 - ctx.svm.now() + 1);
+    let result = ctx.mark_defaulted(&round);
+    assert_error(result, ErrorCode::LiquidationGracePeriodActive);
+
+    ctx.svm.warp(LIQUIDATION_GRACE_PERIOD as i64);
+    ctx.mark_defaulted(&round).unwrap();
+
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Defaulted);
+    assert!(!funding_round.is_active);
+    assert_eq!(
+        ctx.account::<CompanyProfile>(&round.company.profile)
+            .active_round_count,
+        0
+    );
+    let reputation: CompanyReputation = ctx.account(&round.company.reputation);
+    assert_eq!(reputation.defaults, 1);
+    assert_eq!(reputation.total_raised, SOL);
+
+    // nothing was repaid or liquidated, so there is nothing for investors to claim
+    let result = ctx.claim_repayment(investor, &round);
+    assert_error(result, ErrorCode::NothingToClaim);
+
+    let result = ctx.mark_defaulted(&round);
+    assert_error(result, ErrorCode::FundingRoundNotInDefault);
+}
+
+#[test]
+fn mark_round_defaulted_checks_the_company() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    let other = ctx.create_company_with_round(Window::default());
+    ctx.svm.warp(1_000_000 + LIQUIDATION_GRACE_PERIOD as i64);
+
+    let result = ctx.mark_defaulted(&Round {
+        vault: other.vault,
+        ..round
+    });
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.mark_defaulted(&Round {
+        repayment_vault: other.repayment_vault,
+        ..round
+    });
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    let result = ctx.mark_defaulted(&Round {
+        company: other.company,
+        ..round
+    });
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+}
+
+#[test]
+fn mark_round_defaulted_releases_the_unwithdrawn_capital() {
+    let mut ctx = TestContext::new();
+    let window = Window::default();
+    let round = ctx.create_company_with_round(window);
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+
+    // a round still open past its deadline has lent nothing, so it is finalized rather than defaulted
+    ctx.svm.warp(window.deadline + LIQUIDATION_GRACE_PERIOD as i64 + 1);
+    let result = ctx.mark_defaulted(&round);
+    assert_error(result, ErrorCode::FundingRoundNotInDefault);
+    ctx.finalize(&round).unwrap();
+    ctx.withdraw(&round, SOL / 4).unwrap();
+
+    let vault_before = ctx.svm.lamports(&round.vault);
+    let repayment_vault_before = ctx.svm.lamports(&round.repayment_vault);
+    ctx.mark_defaulted(&round).unwrap();
+
+    let unwithdrawn = SOL - SOL / 4;
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_before - unwithdrawn);
+    assert_eq!(ctx.account::<RoundVault>(&round.vault).tracked_balance, 0);
+    assert_eq!(
+        ctx.svm.lamports(&round.repayment_vault),
+        repayment_vault_before + unwithdrawn
+    );
+    let funding_round: FundingRound = ctx.account(&round.address);
+    assert_eq!(funding_round.status, FundingRoundStatus::Defaulted);
+    assert_eq!(funding_round.released_capital, unwithdrawn);
+
+    // investors claim what the company left in the vault through their repayment claims
+    let investor_before = ctx.svm.lamports(&investor);
+    ctx.claim_repayment(investor, &round).unwrap();
+    assert_eq!(ctx.svm.lamports(&investor), investor_before + unwithdrawn);
+    let result = ctx.claim_repayment(investor, &round);
+    assert_error(result, ErrorCode::NothingToClaim);
+}
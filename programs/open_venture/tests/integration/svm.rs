@@ -0,0 +1,426 @@
+//! The runtime the tests run the program in: a bank from `solana-program-test`, with the system
+//! program, the SPL token program and `open_venture` deployed behind the upgradeable loader.
+//!
+//! By default `open_venture` runs as a native builtin, so `cargo test` needs no SBF toolchain.
+//! The runtime still executes each instruction: the system program runs as the validator's
+//! builtin, the SPL token program as its SBF release, and the program's changes to its accounts
+//! go through the same checks, so ownership, rent exemption and data length violations fail as
+//! they would on-chain. With `SBF_OUT_DIR` pointing at a build of the program, e.g.
+//! `target/deploy` after `anchor build`, the tests run that `.so` in the SBF VM instead.
+//!
+//! [`Svm`] wraps the bank in the blocking API the tests use. Every address it hands out has a
+//! keypair, and a transaction is signed by the instruction's signers and paid for by a separate
+//! fee payer, so fees never show up in the balances the tests check.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction::SystemError,
+};
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token::{
+    self,
+    state::{Account as TokenAccountState, AccountState, Mint},
+};
+pub use solana_account::Account;
+use solana_account::AccountSharedData;
+use solana_keypair::Keypair;
+use solana_program_test::{processor, tokio::runtime::Runtime, ProgramTest, ProgramTestContext};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+/// Directory holding the program's SBF build, which the tests run instead of the native code.
+pub const SBF_OUT_DIR: &str = "SBF_OUT_DIR";
+/// Size of the upgradeable loader's ProgramData header, ahead of the program's ELF.
+const PROGRAM_DATA_METADATA_SIZE: usize = 45;
+
+/// Program ID of the upgradeable loader's ProgramData account for `open_venture`.
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[open_venture::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+/// What an instruction consumed, as reported by the runtime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Compute units the transaction consumed. Run natively, the program's own code is not
+    /// metered, only its syscalls and the programs it invokes.
+    pub compute_units: u64,
+}
+
+/// Rules the runtime enforces on an instruction once the program returns, which it reports as
+/// instruction errors of their own. They surface as the custom codes at the top of the range,
+/// which the program does not use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum RuntimeError {
+    UnbalancedInstruction = u32::MAX,
+    ReadonlyDataModified = u32::MAX - 1,
+    ModifiedProgramId = u32::MAX - 2,
+    ExternalAccountDataModified = u32::MAX - 3,
+    ExternalAccountLamportSpend = u32::MAX - 4,
+    InvalidRealloc = u32::MAX - 5,
+    InsufficientFundsForRent = u32::MAX - 6,
+}
+
+impl RuntimeError {
+    /// The lowest code a runtime error is reported with.
+    pub const LOWEST_CODE: u32 = RuntimeError::InsufficientFundsForRent as u32;
+}
+
+impl From<RuntimeError> for ProgramError {
+    fn from(error: RuntimeError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+/// Runs `open_venture` as a native builtin.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // `entry` ties the slice to the lifetime of the accounts it holds, which the builtin
+    // interface does not promise, so the slice is leaked to outlive them
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    open_venture::entry(program_id, accounts, data)
+}
+
+pub struct Svm {
+    context: ProgramTestContext,
+    runtime: Runtime,
+    /// The clock as the tests see it, restored whenever the bank moves to a new slot.
+    clock: Clock,
+    keypairs: HashMap<Pubkey, Keypair>,
+    address_count: u64,
+    /// Every account the tests funded, wrote or passed to an instruction.
+    addresses: BTreeSet<Pubkey>,
+    /// Signatures sent since the bank last moved to a new slot, which it would reject as
+    /// already processed were they sent again.
+    signatures: HashSet<[u8; 64]>,
+    usage: Vec<([u8; 8], Usage)>,
+}
+
+impl Svm {
+    /// Starts a bank with the system, token and `open_venture` programs deployed, the latter
+    /// without an upgrade authority until [`Svm::set_upgrade_authority`] sets one.
+    pub fn new() -> Self {
+        let runtime = solana_program_test::tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let elf = std::env::var_os(SBF_OUT_DIR).map(|dir| {
+            let path = std::path::Path::new(&dir).join("open_venture.so");
+            std::fs::read(&path).unwrap_or_else(|error| panic!("reading {}: {error}", path.display()))
+        });
+
+        let mut program_test = ProgramTest::default();
+        // program-test logs every program it deploys, unless `RUST_LOG` asks for more
+        solana_logger::setup_with_default("error");
+        program_test.prefer_bpf(false);
+        if elf.is_none() {
+            // the runtime hands an instruction to the loader that owns the program account, so
+            // the native program stands in for the upgradeable loader and the program account
+            // keeps its deployed layout, which is how the program finds its upgrade authority
+            program_test.add_program(
+                "open_venture",
+                BPF_LOADER_UPGRADEABLE_ID,
+                processor!(process_instruction),
+            );
+        }
+        program_test.add_genesis_account(open_venture::ID, program_account());
+        program_test.add_genesis_account(
+            program_data_address(),
+            program_data(None, elf.as_deref().unwrap_or_default()),
+        );
+
+        let context = runtime.block_on(program_test.start_with_context());
+        let clock = Clock {
+            unix_timestamp: 1_700_000_000,
+            ..runtime
+                .block_on(context.banks_client.get_sysvar::<Clock>())
+                .unwrap()
+        };
+        context.set_sysvar(&clock);
+        Self {
+            context,
+            runtime,
+            clock,
+            keypairs: HashMap::new(),
+            address_count: 0,
+            addresses: BTreeSet::new(),
+            signatures: HashSet::new(),
+            usage: Vec::new(),
+        }
+    }
+
+    /// Makes `upgrade_authority` the upgrade authority of `open_venture`.
+    pub fn set_upgrade_authority(&mut self, upgrade_authority: Pubkey) {
+        let mut program_data = self.account(&program_data_address()).unwrap();
+        program_data.data[..PROGRAM_DATA_METADATA_SIZE]
+            .copy_from_slice(&program_data_metadata(Some(upgrade_authority)));
+        self.context
+            .set_account(&program_data_address(), &AccountSharedData::from(program_data));
+    }
+
+    pub fn now(&self) -> i64 {
+        self.clock.unix_timestamp
+    }
+
+    /// Moves the clock forward by `seconds`.
+    pub fn warp(&mut self, seconds: i64) {
+        self.clock.unix_timestamp += seconds;
+        self.next_slot();
+    }
+
+    /// Moves the bank to the next slot, which brings a new blockhash, keeping the clock's time.
+    fn next_slot(&mut self) {
+        self.context.warp_to_slot(self.clock.slot + 1).unwrap();
+        self.clock = Clock {
+            unix_timestamp: self.clock.unix_timestamp,
+            ..self
+                .runtime
+                .block_on(self.context.banks_client.get_sysvar::<Clock>())
+                .unwrap()
+        };
+        self.context.set_sysvar(&self.clock);
+        self.signatures.clear();
+    }
+
+    /// A new address with a keypair the runtime signs for. Keypairs are numbered per runtime,
+    /// so a test gets the same addresses, and the same PDA bumps, however many tests ran
+    /// before it.
+    pub fn new_address(&mut self) -> Pubkey {
+        self.address_count += 1;
+        let keypair = Keypair::new_from_array(hash(&self.address_count.to_le_bytes()).to_bytes());
+        let address = keypair.pubkey();
+        self.keypairs.insert(address, keypair);
+        address
+    }
+
+    pub fn airdrop(&mut self, address: Pubkey, lamports: u64) {
+        let mut account = self.account(&address).unwrap_or(Account {
+            owner: system_program::ID,
+            ..Account::default()
+        });
+        account.lamports += lamports;
+        self.set_account(address, account);
+    }
+
+    /// Creates or overwrites an account, outside of any instruction.
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.addresses.insert(address);
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    pub fn remove_account(&mut self, address: &Pubkey) {
+        self.context.set_account(address, &AccountSharedData::default());
+    }
+
+    pub fn account(&self, address: &Pubkey) -> Option<Account> {
+        self.runtime
+            .block_on(self.context.banks_client.get_account(*address))
+            .unwrap()
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.account(address).map_or(0, |account| account.lamports)
+    }
+
+    /// Lamports held across every account the tests touched, which only airdrops change as
+    /// the fee payer is left out.
+    pub fn total_lamports(&self) -> u128 {
+        self.addresses
+            .iter()
+            .map(|address| self.lamports(address) as u128)
+            .sum()
+    }
+
+    /// Usage of every instruction processed successfully, keyed by its discriminator.
+    pub fn usage(&self) -> &[([u8; 8], Usage)] {
+        &self.usage
+    }
+
+    /// Deserializes an Anchor account, panicking when it does not exist.
+    pub fn anchor_account<T: anchor_lang::AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self
+            .account(address)
+            .unwrap_or_else(|| panic!("account {address} does not exist"));
+        T::try_deserialize(&mut account.data.as_slice()).expect("account deserializes")
+    }
+
+    /// Creates a mint for token collateral and returns its address.
+    pub fn create_mint(&mut self) -> Pubkey {
+        let mint = self.new_address();
+        let mut data = vec![0; Mint::LEN];
+        Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: u64::MAX / 2,
+            ..Mint::default()
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(mint, rent_exempt(data, spl_token::ID));
+        mint
+    }
+
+    /// Creates a token account holding `amount` of `mint` for `owner`.
+    pub fn create_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let token_account = self.new_address();
+        let mut data = vec![0; TokenAccountState::LEN];
+        TokenAccountState {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccountState::default()
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(token_account, rent_exempt(data, spl_token::ID));
+        token_account
+    }
+
+    pub fn token_balance(&self, token_account: &Pubkey) -> u64 {
+        let account = self.account(token_account).expect("token account exists");
+        TokenAccountState::unpack(&account.data).unwrap().amount
+    }
+
+    /// Executes `instruction` as a transaction signed by `signers`. Account changes are only
+    /// committed when the instruction succeeds.
+    pub fn process(&mut self, instruction: Instruction, signers: &[Pubkey]) -> ProgramResult {
+        let mut keypairs = vec![&self.context.payer];
+        for meta in instruction.accounts.iter().filter(|meta| meta.is_signer) {
+            if !signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if !keypairs.iter().any(|keypair| keypair.pubkey() == meta.pubkey) {
+                let keypair = self.keypairs.get(&meta.pubkey).unwrap_or_else(|| {
+                    panic!("{} is not an address from `new_address`", meta.pubkey)
+                });
+                keypairs.push(keypair);
+            }
+        }
+        let transaction = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&instruction),
+            Some(&self.context.payer.pubkey()),
+            &keypairs,
+            self.context.last_blockhash,
+        );
+        if !self.signatures.insert(transaction.signatures[0].into()) {
+            // the same transaction was sent before, so it needs a new blockhash
+            self.next_slot();
+            return self.process(instruction, signers);
+        }
+        self.addresses
+            .extend(instruction.accounts.iter().map(|meta| meta.pubkey));
+
+        let processed = self
+            .runtime
+            .block_on(
+                self.context
+                    .banks_client
+                    .process_transaction_with_metadata(transaction),
+            )
+            .unwrap();
+        let logs = processed
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.log_messages.join("\n"))
+            .unwrap_or_default();
+        processed
+            .result
+            .map_err(|error| program_error(error, &logs))?;
+        if let (Some(discriminator), Some(metadata)) = (instruction.data.get(..8), processed.metadata) {
+            let usage = Usage {
+                compute_units: metadata.compute_units_consumed,
+            };
+            self.usage.push((discriminator.try_into().unwrap(), usage));
+        }
+        Ok(())
+    }
+}
+
+/// The error the failed instruction returned, with the runtime's own rules mapped to
+/// [`RuntimeError`]. Failures the tests never expect, such as a panic in the program, fail the
+/// test along with the transaction's logs.
+fn program_error(error: TransactionError, logs: &str) -> ProgramError {
+    match error {
+        TransactionError::InstructionError(_, error) => match error {
+            InstructionError::UnbalancedInstruction => RuntimeError::UnbalancedInstruction.into(),
+            InstructionError::ReadonlyDataModified => RuntimeError::ReadonlyDataModified.into(),
+            InstructionError::ModifiedProgramId => RuntimeError::ModifiedProgramId.into(),
+            InstructionError::ExternalAccountDataModified => {
+                RuntimeError::ExternalAccountDataModified.into()
+            }
+            InstructionError::ExternalAccountLamportSpend => {
+                RuntimeError::ExternalAccountLamportSpend.into()
+            }
+            InstructionError::InvalidRealloc => RuntimeError::InvalidRealloc.into(),
+            error => ProgramError::try_from(error)
+                .unwrap_or_else(|error| panic!("instruction failed with {error}:\n{logs}")),
+        },
+        TransactionError::InsufficientFundsForRent { .. } => {
+            RuntimeError::InsufficientFundsForRent.into()
+        }
+        error => panic!("transaction failed with {error}:\n{logs}"),
+    }
+}
+
+/// `UpgradeableLoaderState::Program` pointing at the program's ProgramData account.
+fn program_account() -> Account {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(program_data_address().as_ref());
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        executable: true,
+        rent_epoch: 0,
+    }
+}
+
+/// `UpgradeableLoaderState::ProgramData` followed by the program's ELF.
+fn program_data(upgrade_authority: Option<Pubkey>, elf: &[u8]) -> Account {
+    let mut data = program_data_metadata(upgrade_authority).to_vec();
+    data.extend_from_slice(elf);
+    rent_exempt(data, BPF_LOADER_UPGRADEABLE_ID)
+}
+
+fn program_data_metadata(upgrade_authority: Option<Pubkey>) -> [u8; PROGRAM_DATA_METADATA_SIZE] {
+    let mut metadata = [0; PROGRAM_DATA_METADATA_SIZE];
+    metadata[..4].copy_from_slice(&3u32.to_le_bytes());
+    // deployed at slot 0
+    if let Some(upgrade_authority) = upgrade_authority {
+        metadata[12] = 1;
+        metadata[13..].copy_from_slice(upgrade_authority.as_ref());
+    }
+    metadata
+}
+
+pub fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// The system program's error for creating an account that already exists.
+pub fn account_already_in_use() -> ProgramError {
+    ProgramError::Custom(SystemError::AccountAlreadyInUse as u32)
+}
+
@@ -0,0 +1,179 @@
+use open_venture::{error::ErrorCode, CompanyProfile, FundingRound, FundingRoundStatus, TreasuryLedger};
+
+use crate::active_rounds::set_limit;
+use crate::context::*;
+use crate::migration::migrate_funding_round;
+use crate::vault::sweep;
+
+/// Amount owed on the default round: 1 SOL at 10% interest.
+const REPAYMENT: u64 = SOL + SOL / 10;
+
+fn treasury_outstanding(ctx: &TestContext, company: &Company) -> u64 {
+    ctx.account::<CompanyProfile>(&company.profile).treasury_outstanding
+}
+
+/// Opens two rounds for one company, raises each one's target and moves it to the treasury.
+fn withdraw_two_rounds(ctx: &mut TestContext) -> (Round, Round) {
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    set_limit(ctx, owner, &company, 2).unwrap();
+    let window = Window::default();
+    let first = ctx.create_round(&company, window);
+    let second = ctx.create_round(&company, window);
+    let investor = ctx.user();
+    ctx.fund(investor, &first, SOL).unwrap();
+    ctx.fund(investor, &second, SOL).unwrap();
+    ctx.svm.warp(window.end);
+    for round in [&first, &second] {
+        ctx.finalize(round).unwrap();
+        ctx.withdraw(round, SOL).unwrap();
+    }
+    (first, second)
+}
+
+#[test]
+fn treasury_ledger_records_the_round_flows() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.company, round.company.profile);
+    assert_eq!(ledger.funding_round, round.address);
+    assert_eq!(ledger.outstanding(), 0);
+
+    ctx.withdraw(&round, SOL / 4).unwrap();
+    ctx.withdraw(&round, SOL - SOL / 4).unwrap();
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.withdrawn, SOL);
+    assert_eq!(ledger.outstanding(), SOL);
+    assert_eq!(treasury_outstanding(&ctx, &round.company), SOL);
+
+    ctx.svm.airdrop(round.vault, SOL / 2);
+    sweep(&mut ctx, &round, round.vault).unwrap();
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+    ctx.repay(&round, REPAYMENT).unwrap();
+
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.withdrawn, SOL);
+    assert_eq!(ledger.repaid, REPAYMENT);
+    assert_eq!(ledger.swept, SOL / 2);
+    assert_eq!(ledger.outstanding(), 0);
+    assert_eq!(treasury_outstanding(&ctx, &round.company), 0);
+}
+
+#[test]
+fn repay_cannot_spend_capital_of_other_rounds() {
+    let mut ctx = TestContext::new();
+    let (first, second) = withdraw_two_rounds(&mut ctx);
+    assert_eq!(treasury_outstanding(&ctx, &first.company), 2 * SOL);
+
+    // the treasury holds enough for the repayment only by counting the second round's capital
+    let result = ctx.repay(&first, REPAYMENT);
+    assert_error(result, ErrorCode::TreasuryFundsCommittedToOtherRounds);
+
+    ctx.svm.airdrop(first.company.treasury, SOL / 10);
+    ctx.repay(&first, REPAYMENT).unwrap();
+    assert_eq!(treasury_outstanding(&ctx, &first.company), SOL);
+
+    let result = ctx.repay(&second, REPAYMENT);
+    assert_error(result, ErrorCode::InsufficientVaultFunds);
+    ctx.svm.airdrop(second.company.treasury, SOL / 10);
+    ctx.repay(&second, REPAYMENT).unwrap();
+    assert_eq!(treasury_outstanding(&ctx, &second.company), 0);
+}
+
+#[test]
+fn defaulted_rounds_do_not_hold_back_the_treasury() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    set_limit(&mut ctx, owner, &company, 2).unwrap();
+    let window = Window::default();
+    let defaulting = ctx.create_round(&company, window);
+    let repaying = ctx.create_round(&company, Window { deadline: 2 * window.deadline, ..window });
+    let investor = ctx.user();
+    for round in [&defaulting, &repaying] {
+        ctx.fund(investor, round, SOL).unwrap();
+    }
+    ctx.svm.warp(window.end);
+    for round in [&defaulting, &repaying] {
+        ctx.finalize(round).unwrap();
+        ctx.withdraw(round, SOL).unwrap();
+    }
+    ctx.svm.warp(window.deadline - window.end + LIQUIDATION_GRACE_PERIOD as i64 + 1);
+    ctx.mark_defaulted(&defaulting).unwrap();
+
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&defaulting.address));
+    assert_eq!(ledger.written_off, SOL);
+    assert_eq!(ledger.outstanding(), 0);
+    assert_eq!(treasury_outstanding(&ctx, &company), SOL);
+
+    // the defaulted round's capital is never repaid, so it no longer reserves the treasury
+    ctx.repay(&repaying, REPAYMENT).unwrap();
+    assert_eq!(treasury_outstanding(&ctx, &company), 0);
+}
+
+#[test]
+fn migrate_funding_round_opens_the_ledger_from_the_round_totals() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL).unwrap();
+    // a round written by the previous layout version, before the treasury kept ledgers
+    ctx.svm.remove_account(&treasury_ledger_address(&round.address));
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.version = 3;
+        funding_round.treasury_ledger_bump = 0;
+    });
+    ctx.update_account::<CompanyProfile>(&round.company.profile, |profile| {
+        profile.treasury_outstanding = 0;
+    });
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.company, round.company.profile);
+    assert_eq!(ledger.funding_round, round.address);
+    assert_eq!(ledger.withdrawn, SOL);
+    assert_eq!(ledger.repaid, 0);
+    assert_eq!(treasury_outstanding(&ctx, &round.company), SOL);
+
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+    ctx.repay(&round, REPAYMENT).unwrap();
+    assert_eq!(treasury_outstanding(&ctx, &round.company), 0);
+}
+
+#[test]
+fn migrate_funding_round_writes_off_a_defaulted_round() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL).unwrap();
+    ctx.svm.remove_account(&treasury_ledger_address(&round.address));
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.version = 3;
+        funding_round.treasury_ledger_bump = 0;
+        funding_round.status = FundingRoundStatus::Defaulted;
+    });
+    ctx.update_account::<CompanyProfile>(&round.company.profile, |profile| {
+        profile.treasury_outstanding = 0;
+    });
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.withdrawn, SOL);
+    assert_eq!(ledger.written_off, SOL);
+    assert_eq!(treasury_outstanding(&ctx, &round.company), 0);
+}
+
+#[test]
+fn migrate_funding_round_keeps_an_existing_ledger() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    ctx.withdraw(&round, SOL).unwrap();
+    ctx.update_account::<FundingRound>(&round.address, |funding_round| {
+        funding_round.version = 1;
+    });
+
+    migrate_funding_round(&mut ctx, round.company.owner, &round).unwrap();
+    let ledger: TreasuryLedger = ctx.account(&treasury_ledger_address(&round.address));
+    assert_eq!(ledger.withdrawn, SOL);
+    assert_eq!(treasury_outstanding(&ctx, &round.company), SOL);
+}
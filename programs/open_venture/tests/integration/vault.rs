@@ -0,0 +1,123 @@
+use anchor_lang::prelude::{Pubkey, Rent};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::Space;
+use open_venture::{error::ErrorCode, RoundVault, VaultKind, ANCHOR_DISCRIMINATOR};
+
+use crate::context::*;
+
+const REPAYMENT: u64 = SOL + SOL / 10;
+
+pub fn sweep(ctx: &mut TestContext, round: &Round, vault: Pubkey) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::SweepVault {
+            company_profile: round.company.profile,
+            funding_round: round.address,
+            vault,
+            company_treasury: round.company.treasury,
+            treasury_ledger: treasury_ledger_address(&round.address),
+        },
+        open_venture::instruction::SweepVault {},
+    );
+    let keeper = ctx.user();
+    ctx.process(ix, &[keeper])
+}
+
+fn vault_rent() -> u64 {
+    Rent::default().minimum_balance(ANCHOR_DISCRIMINATOR + RoundVault::INIT_SPACE)
+}
+
+#[test]
+fn vaults_track_what_the_program_moves() {
+    let mut ctx = TestContext::new();
+    let (round, investor) = ctx.create_funded_round();
+
+    let vault: RoundVault = ctx.account(&round.vault);
+    assert_eq!(vault.funding_round, round.address);
+    assert_eq!(vault.kind, VaultKind::Funding);
+    assert_eq!(vault.tracked_balance, SOL);
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_rent() + SOL);
+    let repayment_vault: RoundVault = ctx.account(&round.repayment_vault);
+    assert_eq!(repayment_vault.funding_round, round.address);
+    assert_eq!(repayment_vault.kind, VaultKind::Repayment);
+    assert_eq!(repayment_vault.tracked_balance, 0);
+
+    ctx.withdraw(&round, SOL).unwrap();
+    assert_eq!(ctx.account::<RoundVault>(&round.vault).tracked_balance, 0);
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_rent());
+
+    ctx.svm.airdrop(round.company.treasury, SOL / 10);
+    ctx.repay(&round, REPAYMENT).unwrap();
+    assert_eq!(
+        ctx.account::<RoundVault>(&round.repayment_vault).tracked_balance,
+        REPAYMENT
+    );
+
+    ctx.claim_repayment(investor, &round).unwrap();
+    assert_eq!(ctx.account::<RoundVault>(&round.repayment_vault).tracked_balance, 0);
+    assert_eq!(ctx.svm.lamports(&round.repayment_vault), vault_rent());
+}
+
+#[test]
+fn stray_transfers_cannot_be_withdrawn_but_can_be_swept() {
+    let mut ctx = TestContext::new();
+    let (round, _) = ctx.create_funded_round();
+    ctx.svm.airdrop(round.vault, SOL / 10);
+
+    let result = ctx.withdraw(&round, SOL + 1);
+    assert_error(result, ErrorCode::InsufficientVaultFunds);
+
+    let treasury_before = ctx.svm.lamports(&round.company.treasury);
+    sweep(&mut ctx, &round, round.vault).unwrap();
+    assert_eq!(
+        ctx.svm.lamports(&round.company.treasury),
+        treasury_before + SOL / 10
+    );
+    assert_eq!(ctx.svm.lamports(&round.vault), vault_rent() + SOL);
+    assert_eq!(ctx.account::<RoundVault>(&round.vault).tracked_balance, SOL);
+
+    let result = sweep(&mut ctx, &round, round.vault);
+    assert_error(result, ErrorCode::NothingToSweep);
+
+    // investor funds stay put for the company to withdraw
+    ctx.withdraw(&round, SOL).unwrap();
+}
+
+#[test]
+fn sweep_vault_pays_the_round_company() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let other = ctx.create_company_with_round(Window::default());
+    ctx.svm.airdrop(round.repayment_vault, SOL);
+
+    let result = sweep(&mut ctx, &round, other.repayment_vault);
+    assert_error(result, ErrorCode::FundingRoundCompanyMismatch);
+
+    let result = sweep(
+        &mut ctx,
+        &Round {
+            company: Company {
+                treasury: other.company.treasury,
+                ..round.company
+            },
+            ..round
+        },
+        round.repayment_vault,
+    );
+    assert_anchor_error(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+
+    sweep(&mut ctx, &round, round.repayment_vault).unwrap();
+}
+
+#[test]
+fn sweep_vault_reports_a_deficit() {
+    let mut ctx = TestContext::new();
+    let round = ctx.create_company_with_round(Window::default());
+    let investor = ctx.user();
+    ctx.fund(investor, &round, SOL).unwrap();
+    ctx.update_account::<RoundVault>(&round.vault, |vault| {
+        vault.tracked_balance = 2 * SOL;
+    });
+
+    let result = sweep(&mut ctx, &round, round.vault);
+    assert_error(result, ErrorCode::VaultBalanceDeficit);
+}
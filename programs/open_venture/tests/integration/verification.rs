@@ -0,0 +1,199 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{entrypoint::ProgramResult};
+use anchor_lang::system_program;
+use open_venture::{error::ErrorCode, CompanyVerification, CompanyVerifier};
+
+use crate::context::*;
+
+pub fn add_verifier(ctx: &mut TestContext, signer: Pubkey, authority: Pubkey) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::AddCompanyVerifier {
+            admin: signer,
+            config: config_address(),
+            authority,
+            company_verifier: company_verifier_address(&authority),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::AddCompanyVerifier {},
+    );
+    ctx.process(ix, &[signer])
+}
+
+pub fn remove_verifier(ctx: &mut TestContext, signer: Pubkey, authority: Pubkey) -> ProgramResult {
+    let ix = instruction(
+        open_venture::accounts::RemoveCompanyVerifier {
+            admin: signer,
+            config: config_address(),
+            company_verifier: company_verifier_address(&authority),
+        },
+        open_venture::instruction::RemoveCompanyVerifier {},
+    );
+    ctx.process(ix, &[signer])
+}
+
+/// Verifies `company`, passing the verifier's delegation only when it exists.
+pub fn verify(
+    ctx: &mut TestContext,
+    verifier: Pubkey,
+    company: &Company,
+    legal_name: &str,
+    jurisdiction: &str,
+) -> ProgramResult {
+    let company_verifier = company_verifier_address(&verifier);
+    let ix = instruction(
+        open_venture::accounts::VerifyCompany {
+            verifier,
+            config: config_address(),
+            company_verifier: ctx.svm.account(&company_verifier).map(|_| company_verifier),
+            company_profile: company.profile,
+            company_verification: company_verification_address(&company.profile),
+            system_program: system_program::ID,
+        },
+        open_venture::instruction::VerifyCompany {
+            legal_name: legal_name.to_string(),
+            jurisdiction: jurisdiction.to_string(),
+            registration_hash: [9; 32],
+        },
+    );
+    ctx.process(ix, &[verifier])
+}
+
+pub fn revoke(ctx: &mut TestContext, verifier: Pubkey, company: &Company) -> ProgramResult {
+    let company_verifier = company_verifier_address(&verifier);
+    let ix = instruction(
+        open_venture::accounts::RevokeCompanyVerification {
+            verifier,
+            config: config_address(),
+            company_verifier: ctx.svm.account(&company_verifier).map(|_| company_verifier),
+            company_verification: company_verification_address(&company.profile),
+        },
+        open_venture::instruction::RevokeCompanyVerification {},
+    );
+    ctx.process(ix, &[verifier])
+}
+
+#[test]
+fn admin_verifies_companies() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let admin = ctx.admin;
+
+    verify(&mut ctx, admin, &company, "Acme Holdings Ltd", "KE").unwrap();
+
+    let verification: CompanyVerification =
+        ctx.account(&company_verification_address(&company.profile));
+    assert_eq!(verification.company, company.profile);
+    assert_eq!(verification.verifier, admin);
+    assert_eq!(verification.legal_name, "Acme Holdings Ltd");
+    assert_eq!(verification.jurisdiction, "KE");
+    assert_eq!(verification.registration_hash, [9; 32]);
+    assert_eq!(verification.verified_at, ctx.svm.now());
+    assert_eq!(
+        verification.bump,
+        bump(
+            &company_verification_address(&company.profile),
+            &[b"company_verification", company.profile.as_ref()]
+        )
+    );
+}
+
+#[test]
+fn delegated_verifiers_verify_and_revoke() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let admin = ctx.admin;
+    let verifier = ctx.user();
+
+    add_verifier(&mut ctx, admin, verifier).unwrap();
+    let delegation: CompanyVerifier = ctx.account(&company_verifier_address(&verifier));
+    assert_eq!(delegation.authority, verifier);
+    assert_eq!(
+        delegation.bump,
+        bump(&company_verifier_address(&verifier), &[b"company_verifier", verifier.as_ref()])
+    );
+
+    verify(&mut ctx, verifier, &company, "Acme Holdings Ltd", "KE").unwrap();
+    revoke(&mut ctx, verifier, &company).unwrap();
+
+    assert!(ctx
+        .svm
+        .account(&company_verification_address(&company.profile))
+        .is_none());
+}
+
+#[test]
+fn only_admin_manages_verifiers() {
+    let mut ctx = TestContext::new();
+    let admin = ctx.admin;
+    let stranger = ctx.user();
+    let verifier = ctx.user();
+
+    let result = add_verifier(&mut ctx, stranger, verifier);
+    assert_error(result, ErrorCode::UnauthorizedAdmin);
+
+    add_verifier(&mut ctx, admin, verifier).unwrap();
+    let result = remove_verifier(&mut ctx, stranger, verifier);
+    assert_error(result, ErrorCode::UnauthorizedAdmin);
+}
+
+#[test]
+fn removed_verifiers_can_no_longer_verify() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let admin = ctx.admin;
+    let verifier = ctx.user();
+    add_verifier(&mut ctx, admin, verifier).unwrap();
+
+    remove_verifier(&mut ctx, admin, verifier).unwrap();
+
+    assert!(ctx
+        .svm
+        .account(&company_verifier_address(&verifier))
+        .is_none());
+    let result = verify(&mut ctx, verifier, &company, "Acme Holdings Ltd", "KE");
+    assert_error(result, ErrorCode::UnauthorizedVerifier);
+}
+
+#[test]
+fn verification_requires_an_authorized_verifier() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let admin = ctx.admin;
+
+    let result = verify(&mut ctx, owner, &company, "Acme Holdings Ltd", "KE");
+    assert_error(result, ErrorCode::UnauthorizedVerifier);
+
+    verify(&mut ctx, admin, &company, "Acme Holdings Ltd", "KE").unwrap();
+    let result = revoke(&mut ctx, owner, &company);
+    assert_error(result, ErrorCode::UnauthorizedVerifier);
+}
+
+#[test]
+fn verification_validates_identity() {
+    let mut ctx = TestContext::new();
+    let owner = ctx.user();
+    let company = ctx.create_company(owner);
+    let admin = ctx.admin;
+
+    let result = verify(&mut ctx, admin, &company, "", "KE");
+    assert_error(result, ErrorCode::LegalNameRequired);
+
+    let result = verify(&mut ctx, admin, &company, &"n".repeat(65), "KE");
+    assert_error(result, ErrorCode::LegalNameTooLong);
+
+    let result = verify(&mut ctx, admin, &company, "Acme Holdings Ltd", "");
+    assert_error(result, ErrorCode::JurisdictionRequired);
+
+    let result = verify(
+        &mut ctx,
+        admin,
+        &company,
+        "Acme Holdings Ltd",
+        &"j".repeat(33),
+    );
+    assert_error(result, ErrorCode::JurisdictionTooLong);
+}
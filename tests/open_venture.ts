@@ -15,7 +15,34 @@ describe("open_venture", () => {
 
   const program = anchor.workspace.openVenture as Program<OpenVenture>;
 
+  // repayment deadline extensions up to a week, once per round, are granted without a vote
+  const MAX_AUTO_EXTENSION = 7 * 24 * 60 * 60;
+  const MAX_AUTO_EXTENSIONS = 1;
+  // collateral can be liquidated two seconds after the deadline, with a 1% keeper reward
+  const LIQUIDATION_GRACE_PERIOD = 2;
+  const KEEPER_REWARD_BPS = 100;
+  const MAX_ACTIVE_ROUNDS = 3;
+
   before(async () => {
+    // the provider wallet deployed the program, so it is the upgrade authority and becomes the admin
+    await program.methods
+      .initializeConfig(
+        new anchor.BN(MAX_AUTO_EXTENSION),
+        MAX_AUTO_EXTENSIONS,
+        new anchor.BN(LIQUIDATION_GRACE_PERIOD),
+        KEEPER_REWARD_BPS,
+        new anchor.BN(0),
+        MAX_ACTIVE_ROUNDS
+      )
+      .accounts({
+        admin: program.provider.publicKey,
+        config: getProgramConfigAddress(program.programId),
+        program: program.programId,
+        programData: getProgramDataAddress(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .rpc();
+
     // airdrop some SOL
     await airdrop(owner1.publicKey, new anchor.BN(1_000_000_000));
 
@@ -36,6 +63,7 @@ describe("open_venture", () => {
       .createCompanyProfile(companyName, companyBio)
       .accounts({
         owner: owner1.publicKey,
+        companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
         companyProfile: companyProfileAddress,
         companyTreasury: companyTreasuryAddress,
         systemProgram: anchor.web3.SystemProgram.programId,
@@ -66,6 +94,7 @@ describe("open_venture", () => {
         .createCompanyProfile(companyName, companyBio)
         .accounts({
           owner: owner1.publicKey,
+          companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
           companyProfile: companyProfileAddress,
           companyTreasury: companyTreasuryAddress,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -100,6 +129,7 @@ describe("open_venture", () => {
           .createCompanyProfile(companyName, companyBio)
           .accounts({
             owner: owner1.publicKey,
+            companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
             companyProfile: companyProfileAddress,
             companyTreasury: companyTreasuryAddress,
             systemProgram: anchor.web3.SystemProgram.programId,
@@ -134,6 +164,7 @@ describe("open_venture", () => {
           .createCompanyProfile(companyName, companyBio)
           .accounts({
             owner: owner1.publicKey,
+            companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
             companyProfile: companyProfileAddress,
             systemProgram: anchor.web3.SystemProgram.programId,
           } as any)
@@ -170,6 +201,7 @@ describe("open_venture", () => {
           .createCompanyProfile(companyName, companyBio)
           .accounts({
             owner: owner1.publicKey,
+            companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
             companyProfile: companyProfileAddress,
             systemProgram: anchor.web3.SystemProgram.programId,
           } as any)
@@ -204,6 +236,7 @@ describe("open_venture", () => {
           .createCompanyProfile(companyName, companyBio)
           .accounts({
             owner: owner1.publicKey,
+            companyIndex: await getNextCompanyIndexAddress(owner1.publicKey),
             companyProfile: companyProfileAddress,
             systemProgram: anchor.web3.SystemProgram.programId,
           } as any)
@@ -234,6 +267,8 @@ describe("open_venture", () => {
     let bobsTargetAmount: anchor.BN;
     let bobsInterestRate: anchor.BN;
     let bobsRepaymentDeadline: anchor.BN;
+    let bobsFundingStart: anchor.BN;
+    let bobsFundingEnd: anchor.BN;
     let bobsFundingRoundAddress: PublicKey;
     let bobsVaultAddress: PublicKey;
     let bobsRepaymentVaultAddress: PublicKey;
@@ -268,6 +303,7 @@ describe("open_venture", () => {
         .createCompanyProfile(bobsCompanyName, bobsCompanyBio)
         .accounts({
           owner: bob.publicKey,
+          companyIndex: await getNextCompanyIndexAddress(bob.publicKey),
           companyProfile: bobsCompanyProfileAddress,
           companyTreasury: bobsCompanyTreasuryAddress,
           systemProgram: anchor.web3.SystemProgram.programId,
@@ -282,19 +318,21 @@ describe("open_venture", () => {
       bobsRepaymentDeadline = new anchor.BN(
         Math.floor(Date.now() / 1000) + 1_000_000
       );
+      bobsFundingStart = new anchor.BN(Math.floor(Date.now() / 1000) - 60);
+      bobsFundingEnd = new anchor.BN(Math.floor(Date.now() / 1000) + 500_000);
       bobsFundingRoundAddress = getFundingRoundAddress(
         bobsCompanyProfileAddress,
-        bobsRoundId,
+        0,
         program.programId
       );
       bobsVaultAddress = getFundingRoundVaultAddress(
         bobsCompanyProfileAddress,
-        bobsRoundId,
+        0,
         program.programId
       );
       bobsRepaymentVaultAddress = getFundingRoundRepaymentVaultAddress(
         bobsCompanyProfileAddress,
-        bobsRoundId,
+        0,
         program.programId
       );
 
@@ -303,7 +341,9 @@ describe("open_venture", () => {
           bobsRoundId,
           bobsTargetAmount,
           bobsInterestRate,
-          bobsRepaymentDeadline
+          bobsRepaymentDeadline,
+          bobsFundingStart,
+          bobsFundingEnd
         )
         .accounts({
           owner: bob.publicKey,
@@ -311,16 +351,22 @@ describe("open_venture", () => {
           fundingRound: bobsFundingRoundAddress,
           vault: bobsVaultAddress,
           repaymentVault: bobsRepaymentVaultAddress,
+          treasuryLedger: getTreasuryLedgerAddress(bobsFundingRoundAddress, program.programId),
+          collateralVault: getCollateralVaultAddress(bobsFundingRoundAddress, program.programId),
+          companyReputation: getCompanyReputationAddress(bobsCompanyProfileAddress, program.programId),
+          config: getProgramConfigAddress(program.programId),
           systemProgram: anchor.web3.SystemProgram.programId,
         } as any)
         .signers([bob])
         .rpc();
 
         // investor invests in bob's funding round
-        await program.methods.fundCompany(new anchor.BN(500_000_000)).accounts({
+        await program.methods.fundCompany(new anchor.BN(500_000_000), []).accounts({
           investor: investor.publicKey,
           companyProfile: bobsCompanyProfileAddress,
           fundingRound: bobsFundingRoundAddress,
+          portfolioEntry: await getNextPortfolioEntryAddress(investor.publicKey, bobsFundingRoundAddress),
+          attestation: null,
           vault: bobsVaultAddress,
           systemProgram: anchor.web3.SystemProgram.programId,
         } as any)
@@ -333,19 +379,23 @@ describe("open_venture", () => {
         bobsFundingRoundAddress
       );
       assert.equal(fundingRound.id, bobsRoundId);
+      assert.ok(fundingRound.index.eqn(0));
       assert.ok(fundingRound.company.equals(bobsCompanyProfileAddress));
       assert.ok(fundingRound.targetAmount.eq(bobsTargetAmount));
       assert.ok(fundingRound.interestRate.eq(bobsInterestRate));
       assert.ok(fundingRound.repaymentDeadline.eq(bobsRepaymentDeadline));
       assert.strictEqual(fundingRound.isActive, true);
+      assert.ok(fundingRound.fundingStart.eq(bobsFundingStart));
+      assert.ok(fundingRound.fundingEnd.eq(bobsFundingEnd));
+      assert.deepStrictEqual(fundingRound.status, { open: {} });
 
       const companyProfile = await program.account.companyProfile.fetch(
         bobsCompanyProfileAddress
       );
-      assert.ok(
-        companyProfile.activeFundingRound !== null &&
-          companyProfile.activeFundingRound.equals(bobsFundingRoundAddress),
-        "company should track the active funding round"
+      assert.strictEqual(
+        companyProfile.activeRoundCount,
+        1,
+        "company should count the active funding round"
       );
     });
 
@@ -354,17 +404,17 @@ describe("open_venture", () => {
       const duplicateRoundId = `${bobsRoundId}-dup`;
       const duplicateFundingRoundAddress = getFundingRoundAddress(
         bobsCompanyProfileAddress,
-        duplicateRoundId,
+        1,
         program.programId
       );
       const duplicateVaultAddress = getFundingRoundVaultAddress(
         bobsCompanyProfileAddress,
-        duplicateRoundId,
+        1,
         program.programId
       );
       const duplicateRepaymentVaultAddress = getFundingRoundRepaymentVaultAddress(
         bobsCompanyProfileAddress,
-        duplicateRoundId,
+        1,
         program.programId
       );
 
@@ -374,7 +424,9 @@ describe("open_venture", () => {
             duplicateRoundId,
             bobsTargetAmount,
             bobsInterestRate,
-            bobsRepaymentDeadline
+            bobsRepaymentDeadline,
+            bobsFundingStart,
+            bobsFundingEnd
           )
           .accounts({
             owner: bob.publicKey,
@@ -382,6 +434,10 @@ describe("open_venture", () => {
             fundingRound: duplicateFundingRoundAddress,
             vault: duplicateVaultAddress,
             repaymentVault: duplicateRepaymentVaultAddress,
+            treasuryLedger: getTreasuryLedgerAddress(duplicateFundingRoundAddress, program.programId),
+            collateralVault: getCollateralVaultAddress(duplicateFundingRoundAddress, program.programId),
+            companyReputation: getCompanyReputationAddress(bobsCompanyProfileAddress, program.programId),
+            config: getProgramConfigAddress(program.programId),
             systemProgram: anchor.web3.SystemProgram.programId,
           } as any)
           .signers([bob])
@@ -392,8 +448,8 @@ describe("open_venture", () => {
         if (parsed) {
           assert.strictEqual(
             parsed.error.errorCode.code,
-            "ActiveFundingRoundExists",
-            "expected duplicate funding round attempt to fail with ActiveFundingRoundExists"
+            "ActiveRoundLimitReached",
+            "expected duplicate funding round attempt to fail with ActiveRoundLimitReached"
           );
           return;
         }
@@ -412,11 +468,13 @@ describe("open_venture", () => {
       );
 
       await program.methods
-        .fundCompany(depositAmount)
+        .fundCompany(depositAmount, [])
         .accounts({
           investor: investor.publicKey,
           companyProfile: bobsCompanyProfileAddress,
           fundingRound: bobsFundingRoundAddress,
+          portfolioEntry: await getNextPortfolioEntryAddress(investor.publicKey, bobsFundingRoundAddress),
+          attestation: null,
           vault: bobsVaultAddress,
           systemProgram: anchor.web3.SystemProgram.programId,
         } as any)
@@ -432,54 +490,39 @@ describe("open_venture", () => {
       );
     });
 
-    it("allows the company owner to withdraw funds from the funding round vault", async () => {
-      // bob wants to withdraw 0.15 sol that has already been invested
+    it("does not let the company owner withdraw before the round is funded", async () => {
+      // bob wants to withdraw 0.15 sol that has already been invested, but the round is still open
       const withdrawalAmount = new anchor.BN(150_000_000);
-      const initialVaultBalance = await program.provider.connection.getBalance(bobsVaultAddress);
-      const initialTreasuryBalance = await program.provider.connection.getBalance(bobsCompanyTreasuryAddress);
-      // withdraw
-      await program.methods.withdrawFunds(withdrawalAmount).accounts({
+      const withdrawal = program.methods.withdrawFunds(withdrawalAmount).accounts({
         owner: bob.publicKey,
         companyProfile: bobsCompanyProfileAddress,
         fundingRound: bobsFundingRoundAddress,
         vault: bobsVaultAddress,
         companyTreasury: bobsCompanyTreasuryAddress,
+        treasuryLedger: getTreasuryLedgerAddress(bobsFundingRoundAddress, program.programId),
         systemProgram: anchor.web3.SystemProgram.programId,
       } as any)
       .signers([bob])
       .rpc();
-      const finalVaultBalance = await program.provider.connection.getBalance(bobsVaultAddress);
-      const finalTreasuryBalance = await program.provider.connection.getBalance(bobsCompanyTreasuryAddress);
 
-      assert.strictEqual(
-        BigInt(finalVaultBalance),
-        BigInt(initialVaultBalance) - BigInt(withdrawalAmount.toNumber())
-      );
-      assert.strictEqual(
-        BigInt(finalTreasuryBalance),
-        BigInt(initialTreasuryBalance) + BigInt(withdrawalAmount.toNumber())
-      );
+      await expectAnchorError(withdrawal, "FundingRoundNotFunded");
     });
 
-    it("allows the company owner to repay the funding round with interest", async () => {
+    it("does not let the company owner repay before the round is funded", async () => {
       // compute total repayment (target * (1 + interest/100))
       const hundred = new anchor.BN(100);
       const totalWithInterest = bobsTargetAmount
         .mul(hundred.add(bobsInterestRate))
         .div(hundred);
-      const totalWithInterestBigInt = BigInt(totalWithInterest.toString());
 
       // ensure treasury has enough lamports to repay
       await airdrop(bobsCompanyTreasuryAddress, totalWithInterest);
 
-      const initialTreasuryBalance = await program.provider.connection.getBalance(
-        bobsCompanyTreasuryAddress
-      );
       const initialRepaymentBalance = await program.provider.connection.getBalance(
         bobsRepaymentVaultAddress
       );
 
-      await program.methods
+      const repayment = program.methods
         .repayFundingRound(totalWithInterest)
         .accounts({
           owner: bob.publicKey,
@@ -487,134 +530,1767 @@ describe("open_venture", () => {
           fundingRound: bobsFundingRoundAddress,
           companyTreasury: bobsCompanyTreasuryAddress,
           repaymentVault: bobsRepaymentVaultAddress,
+          treasuryLedger: getTreasuryLedgerAddress(bobsFundingRoundAddress, program.programId),
+          companyReputation: getCompanyReputationAddress(bobsCompanyProfileAddress, program.programId),
         } as any)
         .signers([bob])
         .rpc();
 
-      const finalTreasuryBalance = await program.provider.connection.getBalance(
-        bobsCompanyTreasuryAddress
-      );
+      await expectAnchorError(repayment, "FundingRoundNotFunded");
       const finalRepaymentBalance = await program.provider.connection.getBalance(
         bobsRepaymentVaultAddress
       );
+      assert.strictEqual(finalRepaymentBalance, initialRepaymentBalance);
+    });
+  });
 
-      assert.strictEqual(
-        BigInt(initialTreasuryBalance) - BigInt(finalTreasuryBalance),
-        totalWithInterestBigInt
+  describe("contribution limits", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    let investor: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(5_000_000_000));
+
+      round = await createCompanyWithFundingRound(alice);
+      await program.methods
+        .setContributionLimits(new anchor.BN(100_000_000), new anchor.BN(300_000_000))
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+        } as any)
+        .signers([alice])
+        .rpc();
+    });
+
+    it("stores the contribution limits on the funding round", async () => {
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.minContribution.eq(new anchor.BN(100_000_000)));
+      assert.ok(fundingRound.maxContribution.eq(new anchor.BN(300_000_000)));
+    });
+
+    it("rejects deposits below the minimum contribution", async () => {
+      await expectAnchorError(
+        fund(investor, round, new anchor.BN(99_999_999)),
+        "ContributionBelowMinimum"
+      );
+    });
+
+    it("tracks each investor's running total against the maximum contribution", async () => {
+      await fund(investor, round, new anchor.BN(200_000_000));
+      await fund(investor, round, new anchor.BN(100_000_000));
+
+      const position = await program.account.investorPosition.fetch(
+        getInvestorPositionAddress(round.fundingRound, investor.publicKey, program.programId)
+      );
+      assert.ok(position.amount.eq(new anchor.BN(300_000_000)));
+
+      await expectAnchorError(
+        fund(investor, round, new anchor.BN(100_000_000)),
+        "ContributionExceedsMaximum"
+      );
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.totalRaised.eq(new anchor.BN(300_000_000)));
+    });
+
+    it("rejects changing the limits once the round has deposits", async () => {
+      await fund(investor, round, new anchor.BN(100_000_000));
+
+      await expectAnchorError(
+        program.methods
+          .setContributionLimits(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            owner: alice.publicKey,
+            companyProfile: round.companyProfile,
+            fundingRound: round.fundingRound,
+          } as any)
+          .signers([alice])
+          .rpc(),
+        "FundingRoundHasDeposits"
+      );
+    });
+  });
+
+  describe("investor gating", () => {
+    let alice: anchor.web3.Keypair;
+    let verifier: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    let allowed: anchor.web3.Keypair;
+    let stranger: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      verifier = anchor.web3.Keypair.generate();
+      allowed = anchor.web3.Keypair.generate();
+      stranger = anchor.web3.Keypair.generate();
+      for (const wallet of [alice, verifier, allowed, stranger]) {
+        await airdrop(wallet.publicKey, new anchor.BN(2_000_000_000));
+      }
+      round = await createCompanyWithFundingRound(alice);
+    });
+
+    const setInvestorGate = (allowlistRoot: number[] | null, kycVerifier: PublicKey | null) =>
+      program.methods
+        .setInvestorGate(allowlistRoot, kycVerifier)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    it("accepts allowlisted investors with a valid merkle proof", async () => {
+      const members = [allowed.publicKey, anchor.web3.Keypair.generate().publicKey];
+      const { root, proofs } = buildAllowlist(members);
+      await setInvestorGate(root, null);
+
+      await fund(allowed, round, new anchor.BN(100_000_000), proofs[0]);
+
+      await expectAnchorError(
+        fund(stranger, round, new anchor.BN(100_000_000), proofs[0]),
+        "InvestorNotEligible"
+      );
+    });
+
+    it("accepts investors holding an attestation from the configured verifier", async () => {
+      await setInvestorGate(null, verifier.publicKey);
+      const attestation = getInvestorAttestationAddress(
+        verifier.publicKey,
+        allowed.publicKey,
+        program.programId
+      );
+      await program.methods
+        .issueAttestation(new anchor.BN(0))
+        .accounts({
+          verifier: verifier.publicKey,
+          investor: allowed.publicKey,
+          attestation,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([verifier])
+        .rpc();
+
+      await fund(allowed, round, new anchor.BN(100_000_000), [], attestation);
+
+      await expectAnchorError(
+        fund(stranger, round, new anchor.BN(100_000_000)),
+        "InvestorNotEligible"
+      );
+    });
+
+    it("rejects investors whose attestation was revoked", async () => {
+      await setInvestorGate(null, verifier.publicKey);
+      const attestation = getInvestorAttestationAddress(
+        verifier.publicKey,
+        allowed.publicKey,
+        program.programId
+      );
+      await program.methods
+        .issueAttestation(new anchor.BN(0))
+        .accounts({
+          verifier: verifier.publicKey,
+          investor: allowed.publicKey,
+          attestation,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([verifier])
+        .rpc();
+      await program.methods
+        .revokeAttestation()
+        .accounts({ verifier: verifier.publicKey, attestation } as any)
+        .signers([verifier])
+        .rpc();
+
+      await expectAnchorError(
+        fund(allowed, round, new anchor.BN(100_000_000)),
+        "InvestorNotEligible"
+      );
+    });
+  });
+
+  describe("funding window", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(5_000_000_000));
+    });
+
+    it("rejects deposits before the funding window opens", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, {
+        start: now + 10_000,
+        end: now + 20_000,
+      });
+
+      await expectAnchorError(
+        fund(investor, round, new anchor.BN(100_000_000)),
+        "FundingWindowNotOpen"
+      );
+    });
+
+    it("cannot finalize a round while its funding window is open", async () => {
+      const round = await createCompanyWithFundingRound(alice);
+
+      await expectAnchorError(finalizeRound(round), "FundingWindowStillOpen");
+    });
+
+    it("marks a fully raised round as funded once the window closes", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+      await sleep(5_000);
+
+      await expectAnchorError(
+        fund(investor, round, new anchor.BN(100_000_000)),
+        "FundingWindowClosed"
+      );
+      await finalizeRound(round);
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepStrictEqual(fundingRound.status, { funded: {} });
+      assert.strictEqual(fundingRound.isActive, true);
+    });
+
+    it("lets the company owner withdraw once the round is funded", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+      const withdrawalAmount = new anchor.BN(150_000_000);
+
+      await expectAnchorError(withdrawFunds(alice, round, withdrawalAmount), "FundingRoundNotFunded");
+      await sleep(5_000);
+      await finalizeRound(round);
+
+      const initialVaultBalance = await program.provider.connection.getBalance(round.vault);
+      const initialTreasuryBalance = await program.provider.connection.getBalance(round.companyTreasury);
+      await withdrawFunds(alice, round, withdrawalAmount);
+      const finalVaultBalance = await program.provider.connection.getBalance(round.vault);
+      const finalTreasuryBalance = await program.provider.connection.getBalance(round.companyTreasury);
+
+      assert.strictEqual(finalVaultBalance, initialVaultBalance - withdrawalAmount.toNumber());
+      assert.strictEqual(finalTreasuryBalance, initialTreasuryBalance + withdrawalAmount.toNumber());
+    });
+
+    it("fails an underfunded round and refunds its investors", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      const depositAmount = new anchor.BN(400_000_000);
+      await fund(investor, round, depositAmount);
+      await sleep(5_000);
+      await finalizeRound(round);
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepStrictEqual(fundingRound.status, { failed: {} });
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      assert.strictEqual(companyProfile.activeRoundCount, 0);
+
+      const investorPosition = getInvestorPositionAddress(
+        round.fundingRound,
+        investor.publicKey,
+        program.programId
       );
+      const claimRefund = () =>
+        program.methods
+          .claimRefund()
+          .accounts({
+            investor: investor.publicKey,
+            fundingRound: round.fundingRound,
+            investorPosition,
+            vault: round.vault,
+          } as any)
+          .signers([investor])
+          .rpc();
+
+      const initialVaultBalance = await program.provider.connection.getBalance(round.vault);
+      await claimRefund();
+      const finalVaultBalance = await program.provider.connection.getBalance(round.vault);
+      assert.strictEqual(initialVaultBalance - finalVaultBalance, depositAmount.toNumber());
+
+      await expectAnchorError(claimRefund(), "NothingToRefund");
+    });
+  });
+
+  describe("round cancellation", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(5_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+      await fund(investor, round, new anchor.BN(300_000_000));
+    });
+
+    const cancel = () =>
+      program.methods
+        .cancelFundingRound()
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          repaymentVault: round.repaymentVault,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    it("opens the company's next round at the following index", async () => {
+      await cancel();
+
+      const nextRound = await createNextFundingRound(alice, round, 1);
+      const fundingRound = await program.account.fundingRound.fetch(nextRound.fundingRound);
+      assert.ok(fundingRound.index.eqn(1));
+      assert.strictEqual(fundingRound.id, "round-1");
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      assert.ok(companyProfile.roundCount.eqn(2));
+      assert.strictEqual(companyProfile.activeRoundCount, 1);
+    });
+
+    it("cancels the round, closes the repayment vault and refunds investors", async () => {
+      await cancel();
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepStrictEqual(fundingRound.status, { cancelled: {} });
+      assert.strictEqual(fundingRound.isActive, false);
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      assert.strictEqual(companyProfile.activeRoundCount, 0);
       assert.strictEqual(
-        BigInt(finalRepaymentBalance) - BigInt(initialRepaymentBalance),
-        totalWithInterestBigInt
+        await program.provider.connection.getAccountInfo(round.repaymentVault),
+        null
+      );
+
+      const initialInvestorBalance = await program.provider.connection.getBalance(
+        investor.publicKey
+      );
+      await program.methods
+        .claimRefund()
+        .accounts({
+          investor: investor.publicKey,
+          fundingRound: round.fundingRound,
+          investorPosition: getInvestorPositionAddress(
+            round.fundingRound,
+            investor.publicKey,
+            program.programId
+          ),
+          vault: round.vault,
+        } as any)
+        .signers([investor])
+        .rpc();
+      const finalInvestorBalance = await program.provider.connection.getBalance(
+        investor.publicKey
       );
+      // the investor pays the transaction fee, so allow for it
+      assert.ok(finalInvestorBalance - initialInvestorBalance > 300_000_000 - 10_000);
+    });
+
+    it("cannot cancel a round after funds have been withdrawn", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+      await sleep(5_000);
+      await finalizeRound(round);
+      await withdrawFunds(alice, round, new anchor.BN(100_000_000));
+
+      await expectAnchorError(cancel(), "FundsAlreadyWithdrawn");
     });
   });
 
-  /** Helpers */
-  const airdrop = async (publicKey: anchor.web3.PublicKey, amount: anchor.BN) => {
-    const sig = await program.provider.connection.requestAirdrop(
-      publicKey,
-      amount.toNumber() // amount in lamports
-    );
-    await program.provider.connection.confirmTransaction(sig, "confirmed");
-  };
+  describe("concurrent rounds", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
 
-  // get the PDA for a company profile
-  const getCompanyProfileAddress = (
-    owner: PublicKey,
-    name: string,
-    programID: PublicKey
-  ) => {
-    let hexString = crypto
-      .createHash("sha256")
-      .update(name, "utf-8")
-      .digest("hex");
-    let nameSeed = Uint8Array.from(Buffer.from(hexString, "hex"));
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+    });
 
-    return PublicKey.findProgramAddressSync(
-      [
-        anchor.utils.bytes.utf8.encode("company_profile"),
-        owner.toBuffer(),
-        nameSeed,
-      ],
-      programID
-    )[0];
-  };
+    const setActiveRoundLimit = (authority: anchor.web3.Keypair, maxActiveRounds: number) =>
+      program.methods
+        .setActiveRoundLimit(maxActiveRounds)
+        .accounts({
+          authority: authority.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          companyProfile: round.companyProfile,
+        } as any)
+        .signers([authority])
+        .rpc();
 
-  const getFundingRoundAddress = (
-    companyProfileAddress: PublicKey,
-    id: string,
-    programID: PublicKey
-  ) => {
-    const hexString = crypto
-      .createHash("sha256")
-      .update(id, "utf-8")
-      .digest("hex");
-    const roundIdSeed = Uint8Array.from(Buffer.from(hexString, "hex"));
+    it("opens rounds side by side once the owner raises the limit", async () => {
+      await expectAnchorError(createNextFundingRound(alice, round, 1), "ActiveRoundLimitReached");
 
-    return PublicKey.findProgramAddressSync(
-      [
-        anchor.utils.bytes.utf8.encode("funding_round"),
-        companyProfileAddress.toBuffer(),
-        roundIdSeed,
-      ],
-      programID
-    )[0];
-  };
+      await setActiveRoundLimit(alice, 2);
+      await createNextFundingRound(alice, round, 1);
 
-  const getFundingRoundVaultAddress = (
-    companyProfileAddress: PublicKey,
-    id: string,
-    programID: PublicKey
-  ) => {
-    const hexString = crypto
-      .createHash("sha256")
-      .update(id, "utf-8")
-      .digest("hex");
-    const roundIdSeed = Uint8Array.from(Buffer.from(hexString, "hex"));
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      assert.strictEqual(companyProfile.maxActiveRounds, 2);
+      assert.strictEqual(companyProfile.activeRoundCount, 2);
+      await expectAnchorError(createNextFundingRound(alice, round, 2), "ActiveRoundLimitReached");
+    });
 
-    return PublicKey.findProgramAddressSync(
-      [
-        anchor.utils.bytes.utf8.encode("funding_round_vault"),
-        companyProfileAddress.toBuffer(),
-        roundIdSeed,
-      ],
-      programID
-    )[0];
-  };
+    it("keeps the limit within the program's limit and away from strangers", async () => {
+      const mallory = anchor.web3.Keypair.generate();
+      await airdrop(mallory.publicKey, new anchor.BN(1_000_000_000));
 
-  const getFundingRoundRepaymentVaultAddress = (
-    companyProfileAddress: PublicKey,
-    id: string,
-    programID: PublicKey
-  ) => {
-    const hexString = crypto
-      .createHash("sha256")
-      .update(id, "utf-8")
-      .digest("hex");
-    const roundIdSeed = Uint8Array.from(Buffer.from(hexString, "hex"));
+      await expectAnchorError(setActiveRoundLimit(alice, MAX_ACTIVE_ROUNDS + 1), "InvalidActiveRoundLimit");
+      await expectAnchorError(setActiveRoundLimit(mallory, 2), "UnauthorizedRoundLimit");
+    });
+  });
 
-    return PublicKey.findProgramAddressSync(
-      [
-        anchor.utils.bytes.utf8.encode("funding_round_repayment"),
-        companyProfileAddress.toBuffer(),
-        roundIdSeed,
-      ],
-      programID
-    )[0];
-  };
+  describe("round amendments", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    let roundAmendments: PublicKey;
+    let newDeadline: anchor.BN;
 
-  const getCompanyTreasuryAddress = (
-    owner: PublicKey,
-    companyProfileAddress: PublicKey,
-    programID: PublicKey
-  ) => {
-    return PublicKey.findProgramAddressSync(
-      [
-        anchor.utils.bytes.utf8.encode("company_treasury"),
-        owner.toBuffer(),
-        companyProfileAddress.toBuffer(),
-      ],
-      programID
-    )[0];
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+      roundAmendments = getRoundAmendmentsAddress(round.fundingRound, program.programId);
+      newDeadline = new anchor.BN(Math.floor(Date.now() / 1000) + 2_000_000);
+    });
+
+    const amend = async (targetAmount: anchor.BN, interestRate: anchor.BN) =>
+      program.methods
+        .amendFundingRound(targetAmount, interestRate, newDeadline)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          roundAmendments,
+          ...(await getProposalAccounts(round.fundingRound, roundAmendments)),
+          companyReputation: round.companyReputation,
+          config: getProgramConfigAddress(program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    const approve = (investor: anchor.web3.Keypair, index: number) =>
+      program.methods
+        .approveAmendment(index)
+        .accounts({
+          investor: investor.publicKey,
+          fundingRound: round.fundingRound,
+          amendment: getAmendmentAddress(round.fundingRound, index, program.programId),
+          investorPosition: getInvestorPositionAddress(
+            round.fundingRound,
+            investor.publicKey,
+            program.programId
+          ),
+          amendmentApproval: getAmendmentApprovalAddress(
+            round.fundingRound,
+            index,
+            investor.publicKey,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([investor])
+        .rpc();
+
+    it("applies amendments immediately before the first deposit", async () => {
+      await amend(new anchor.BN(2_000_000_000), new anchor.BN(12));
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.targetAmount.eq(new anchor.BN(2_000_000_000)));
+      assert.ok(fundingRound.interestRate.eq(new anchor.BN(12)));
+      assert.ok(fundingRound.repaymentDeadline.eq(newDeadline));
+
+      const amendments = await program.account.roundAmendments.fetch(roundAmendments);
+      assert.strictEqual(amendments.amendmentCount, 1);
+      const amendment = await program.account.amendment.fetch(
+        getAmendmentAddress(round.fundingRound, 0, program.programId)
+      );
+      assert.deepStrictEqual(amendment.status, { applied: {} });
+    });
+
+    it("requires approval from a majority of invested capital after deposits", async () => {
+      const majority = anchor.web3.Keypair.generate();
+      const minority = anchor.web3.Keypair.generate();
+      await airdrop(majority.publicKey, new anchor.BN(2_000_000_000));
+      await airdrop(minority.publicKey, new anchor.BN(2_000_000_000));
+      await fund(majority, round, new anchor.BN(600_000_000));
+      await fund(minority, round, new anchor.BN(400_000_000));
+
+      await amend(new anchor.BN(1_000_000_000), new anchor.BN(8));
+      let fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.interestRate.eq(new anchor.BN(10)), "terms must not change yet");
+
+      await approve(minority, 0);
+      fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.interestRate.eq(new anchor.BN(10)), "minority cannot apply terms");
+
+      await approve(majority, 0);
+      fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.interestRate.eq(new anchor.BN(8)));
+
+      const amendment = await program.account.amendment.fetch(
+        getAmendmentAddress(round.fundingRound, 0, program.programId)
+      );
+      assert.deepStrictEqual(amendment.status, { applied: {} });
+      assert.ok(amendment.votingBase.eq(new anchor.BN(1_000_000_000)));
+      assert.ok(amendment.approvedAmount.eq(new anchor.BN(1_000_000_000)));
+    });
+
+    it("does not let non-investors approve amendments", async () => {
+      const investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(2_000_000_000));
+      await fund(investor, round, new anchor.BN(100_000_000));
+      await amend(new anchor.BN(1_000_000_000), new anchor.BN(8));
+
+      const outsider = anchor.web3.Keypair.generate();
+      await airdrop(outsider.publicKey, new anchor.BN(1_000_000_000));
+      try {
+        await approve(outsider, 0);
+      } catch (error) {
+        // the outsider has no investor position account for the round
+        return;
+      }
+      assert.fail("expected approval from a non-investor to fail");
+    });
+  });
+
+  describe("repayment deadline extensions", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    let roundAmendments: PublicKey;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(2_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+      roundAmendments = getRoundAmendmentsAddress(round.fundingRound, program.programId);
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+    });
+
+    const requestExtension = async (deadline: anchor.BN, fee: anchor.BN, rateBump: anchor.BN) =>
+      program.methods
+        .requestExtension(deadline, fee, rateBump)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          roundAmendments,
+          ...(await getProposalAccounts(round.fundingRound, roundAmendments)),
+          config: getProgramConfigAddress(program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    it("grants short extensions automatically and adds the fee to the amount due", async () => {
+      const before = await program.account.fundingRound.fetch(round.fundingRound);
+      const newDeadline = before.repaymentDeadline.add(new anchor.BN(24 * 60 * 60));
+      await requestExtension(newDeadline, new anchor.BN(5_000_000), new anchor.BN(1));
+
+      const after = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(after.repaymentDeadline.eq(newDeadline));
+      assert.ok(after.interestRate.eq(before.interestRate.add(new anchor.BN(1))));
+      assert.ok(after.extensionFees.eq(new anchor.BN(5_000_000)));
+      assert.strictEqual(after.autoExtensions, 1);
+    });
+
+    it("requires investor approval beyond the automatic limits", async () => {
+      const before = await program.account.fundingRound.fetch(round.fundingRound);
+      const newDeadline = before.repaymentDeadline.add(
+        new anchor.BN(MAX_AUTO_EXTENSION + 1)
+      );
+      await requestExtension(newDeadline, new anchor.BN(0), new anchor.BN(2));
+
+      let after = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(after.repaymentDeadline.eq(before.repaymentDeadline));
+
+      await program.methods
+        .approveAmendment(0)
+        .accounts({
+          investor: investor.publicKey,
+          fundingRound: round.fundingRound,
+          amendment: getAmendmentAddress(round.fundingRound, 0, program.programId),
+          investorPosition: getInvestorPositionAddress(
+            round.fundingRound,
+            investor.publicKey,
+            program.programId
+          ),
+          amendmentApproval: getAmendmentApprovalAddress(
+            round.fundingRound,
+            0,
+            investor.publicKey,
+            program.programId
+          ),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([investor])
+        .rpc();
+
+      after = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(after.repaymentDeadline.eq(newDeadline));
+      assert.strictEqual(after.autoExtensions, 0);
+    });
+
+    it("rejects extensions that do not move the deadline later", async () => {
+      const before = await program.account.fundingRound.fetch(round.fundingRound);
+      await expectAnchorError(
+        requestExtension(before.repaymentDeadline, new anchor.BN(0), new anchor.BN(0)),
+        "ExtensionMustExtendDeadline"
+      );
+    });
+  });
+
+  describe("collateral", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+    const collateralAmount = new anchor.BN(500_000_000);
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(3_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(3_000_000_000));
+    });
+
+    const depositCollateral = (round: FundingRoundFixture, amount: anchor.BN) =>
+      program.methods
+        .depositCollateral(amount)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          collateralVault: getCollateralVaultAddress(round.fundingRound, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    it("locks SOL collateral and releases it to the owner after full repayment", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      await depositCollateral(round, collateralAmount);
+      const collateralVault = getCollateralVaultAddress(round.fundingRound, program.programId);
+
+      let fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.ok(fundingRound.collateralAmount.eq(collateralAmount));
+      assert.strictEqual(fundingRound.collateralMint, null);
+
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+      await sleep(5_000);
+      await finalizeRound(round);
+      const totalDue = new anchor.BN(1_100_000_000);
+      await airdrop(round.companyTreasury, totalDue);
+      await program.methods
+        .repayFundingRound(totalDue)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          companyTreasury: round.companyTreasury,
+          repaymentVault: round.repaymentVault,
+          treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+          companyReputation: round.companyReputation,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+      await program.methods
+        .releaseCollateral()
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          collateralVault,
+          ownerTokenAccount: null,
+          tokenProgram: null,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+      fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepStrictEqual(fundingRound.status, { repaid: {} });
+      assert.ok(fundingRound.collateralAmount.eqn(0));
+      assert.strictEqual(await program.provider.connection.getAccountInfo(collateralVault), null);
+
+      // the only investor is owed the full repayment
+      const initialInvestorBalance = await program.provider.connection.getBalance(
+        investor.publicKey
+      );
+      await program.methods
+        .claimRepayment()
+        .accounts({
+          investor: investor.publicKey,
+          fundingRound: round.fundingRound,
+          investorPosition: getInvestorPositionAddress(
+            round.fundingRound,
+            investor.publicKey,
+            program.programId
+          ),
+          repaymentVault: round.repaymentVault,
+        } as any)
+        .signers([investor])
+        .rpc();
+      const finalInvestorBalance = await program.provider.connection.getBalance(
+        investor.publicKey
+      );
+      assert.ok(finalInvestorBalance - initialInvestorBalance > 1_100_000_000 - 10_000);
+    });
+
+    it("cannot lock collateral once investors have deposited", async () => {
+      const round = await createCompanyWithFundingRound(alice);
+      await fund(investor, round, new anchor.BN(100_000_000));
+
+      await expectAnchorError(
+        depositCollateral(round, collateralAmount),
+        "FundingRoundHasDeposits"
+      );
+    });
+
+    it("liquidates defaulted SOL collateral into the repayment vault for investors", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, {
+        end: now + 2,
+        deadline: now + 3,
+      });
+      await depositCollateral(round, collateralAmount);
+      const other = anchor.web3.Keypair.generate();
+      await airdrop(other.publicKey, new anchor.BN(3_000_000_000));
+      await fund(investor, round, new anchor.BN(750_000_000));
+      await fund(other, round, new anchor.BN(250_000_000));
+
+      const keeper = anchor.web3.Keypair.generate();
+      await airdrop(keeper.publicKey, new anchor.BN(1_000_000_000));
+      const liquidate = () =>
+        program.methods
+          .liquidateCollateral()
+          .accounts({
+            keeper: keeper.publicKey,
+            companyProfile: round.companyProfile,
+            fundingRound: round.fundingRound,
+            collateralVault: getCollateralVaultAddress(round.fundingRound, program.programId),
+            vault: round.vault,
+            repaymentVault: round.repaymentVault,
+            treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+            companyReputation: round.companyReputation,
+            config: getProgramConfigAddress(program.programId),
+            keeperTokenAccount: null,
+            tokenProgram: null,
+          } as any)
+          .signers([keeper])
+          .rpc();
+
+      await expectAnchorError(liquidate(), "FundingRoundNotInDefault");
+      await sleep(7_000);
+      await finalizeRound(round);
+
+      const initialKeeperBalance = await program.provider.connection.getBalance(
+        keeper.publicKey
+      );
+      await liquidate();
+      const finalKeeperBalance = await program.provider.connection.getBalance(
+        keeper.publicKey
+      );
+      // 1% of the collateral, less the transaction fee
+      assert.ok(finalKeeperBalance - initialKeeperBalance > 5_000_000 - 10_000);
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepStrictEqual(fundingRound.status, { defaulted: {} });
+      assert.ok(fundingRound.liquidationProceeds.eq(new anchor.BN(495_000_000)));
+      // none of the raised capital was withdrawn, so all of it goes back to investors
+      assert.ok(fundingRound.releasedCapital.eq(new anchor.BN(1_000_000_000)));
+      await expectAnchorError(liquidate(), "NoCollateral");
+
+      const claimRepayment = (wallet: anchor.web3.Keypair) =>
+        program.methods
+          .claimRepayment()
+          .accounts({
+            investor: wallet.publicKey,
+            fundingRound: round.fundingRound,
+            investorPosition: getInvestorPositionAddress(
+              round.fundingRound,
+              wallet.publicKey,
+              program.programId
+            ),
+            repaymentVault: round.repaymentVault,
+          } as any)
+          .signers([wallet])
+          .rpc();
+
+      const initialBalance = await program.provider.connection.getBalance(investor.publicKey);
+      await claimRepayment(investor);
+      const finalBalance = await program.provider.connection.getBalance(investor.publicKey);
+      // 75% of the liquidation proceeds and of the released capital, less the transaction fee
+      assert.ok(finalBalance - initialBalance > 1_121_250_000 - 10_000);
+
+      await expectAnchorError(claimRepayment(investor), "NothingToClaim");
+    });
+  });
+
+  describe("company reputation", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(2_000_000_000));
+    });
+
+    const setUnprovenRoundCap = (cap: anchor.BN) =>
+      program.methods
+        .updateConfig(
+          new anchor.BN(MAX_AUTO_EXTENSION),
+          MAX_AUTO_EXTENSIONS,
+          new anchor.BN(LIQUIDATION_GRACE_PERIOD),
+          KEEPER_REWARD_BPS,
+          cap,
+          MAX_ACTIVE_ROUNDS
+        )
+        .accounts({
+          admin: program.provider.publicKey,
+          config: getProgramConfigAddress(program.programId),
+        } as any)
+        .rpc();
+
+    it("records repaid rounds on the company's track record", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, { end: now + 3 });
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+      await sleep(5_000);
+      await finalizeRound(round);
+      const totalDue = new anchor.BN(1_100_000_000);
+      await airdrop(round.companyTreasury, totalDue);
+      await program.methods
+        .repayFundingRound(totalDue)
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          companyTreasury: round.companyTreasury,
+          repaymentVault: round.repaymentVault,
+          treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+          companyReputation: round.companyReputation,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+      const reputation = await program.account.companyReputation.fetch(round.companyReputation);
+      assert.ok(reputation.company.equals(round.companyProfile));
+      assert.strictEqual(reputation.roundsCompleted, 1);
+      assert.strictEqual(reputation.defaults, 0);
+      assert.ok(reputation.totalRaised.eq(new anchor.BN(1_000_000_000)));
+      assert.ok(reputation.totalRepaid.eq(totalDue));
+      assert.ok(reputation.daysLate.eqn(0));
+    });
+
+    it("records uncollateralized defaults once the grace period has passed", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const round = await createCompanyWithFundingRound(alice, {
+        end: now + 2,
+        deadline: now + 3,
+      });
+      await fund(investor, round, new anchor.BN(1_000_000_000));
+
+      const markDefaulted = () =>
+        program.methods
+          .markRoundDefaulted()
+          .accounts({
+            companyProfile: round.companyProfile,
+            fundingRound: round.fundingRound,
+            vault: round.vault,
+            repaymentVault: round.repaymentVault,
+            treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+            companyReputation: round.companyReputation,
+            config: getProgramConfigAddress(program.programId),
+          } as any)
+          .rpc();
+
+      await expectAnchorError(markDefaulted(), "FundingRoundNotInDefault");
+      await sleep(7_000);
+      // still open, so it has lent nothing and is finalized rather than defaulted
+      await expectAnchorError(markDefaulted(), "FundingRoundNotInDefault");
+      await finalizeRound(round);
+      await markDefaulted();
+
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.deepEqual(fundingRound.status, { defaulted: {} });
+      // the company never withdrew, so all of the capital is released to investors
+      assert.ok(fundingRound.releasedCapital.eq(new anchor.BN(1_000_000_000)));
+      const reputation = await program.account.companyReputation.fetch(round.companyReputation);
+      assert.strictEqual(reputation.roundsCompleted, 0);
+      assert.strictEqual(reputation.defaults, 1);
+      assert.ok(reputation.totalRaised.eq(new anchor.BN(1_000_000_000)));
+    });
+
+    it("caps the round size of companies without a track record", async () => {
+      await setUnprovenRoundCap(new anchor.BN(500_000_000));
+      try {
+        await expectAnchorError(
+          createCompanyWithFundingRound(alice),
+          "TargetAmountExceedsReputationCap"
+        );
+      } finally {
+        await setUnprovenRoundCap(new anchor.BN(0));
+      }
+    });
+  });
+
+  describe("company verification", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    const registrationHash = Array.from(
+      crypto.createHash("sha256").update("REG-0042").digest()
+    );
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+    });
+
+    const verify = (verifier: anchor.web3.Keypair | null, companyVerifier: PublicKey | null) => {
+      const builder = program.methods
+        .verifyCompany("Alice Holdings Ltd", "KE", registrationHash)
+        .accounts({
+          verifier: verifier ? verifier.publicKey : program.provider.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          companyVerifier,
+          companyProfile: round.companyProfile,
+          companyVerification: getCompanyVerificationAddress(round.companyProfile, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any);
+      return verifier ? builder.signers([verifier]).rpc() : builder.rpc();
+    };
+
+    it("lets the admin verify a company", async () => {
+      await verify(null, null);
+
+      const verification = await program.account.companyVerification.fetch(
+        getCompanyVerificationAddress(round.companyProfile, program.programId)
+      );
+      assert.ok(verification.company.equals(round.companyProfile));
+      assert.ok(verification.verifier.equals(program.provider.publicKey));
+      assert.strictEqual(verification.legalName, "Alice Holdings Ltd");
+      assert.strictEqual(verification.jurisdiction, "KE");
+      assert.deepEqual(verification.registrationHash, registrationHash);
+
+      const verified = await program.account.companyVerification.all();
+      assert.ok(verified.some((v) => v.account.company.equals(round.companyProfile)));
+    });
+
+    it("rejects verification from anyone who is not a verifier", async () => {
+      await expectAnchorError(verify(alice, null), "UnauthorizedVerifier");
+    });
+
+    it("lets a delegated verifier verify and revoke until removed", async () => {
+      const delegate = anchor.web3.Keypair.generate();
+      await airdrop(delegate.publicKey, new anchor.BN(1_000_000_000));
+      const companyVerifier = getCompanyVerifierAddress(delegate.publicKey, program.programId);
+      await program.methods
+        .addCompanyVerifier()
+        .accounts({
+          admin: program.provider.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          authority: delegate.publicKey,
+          companyVerifier,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .rpc();
+
+      await verify(delegate, companyVerifier);
+      const companyVerification = getCompanyVerificationAddress(round.companyProfile, program.programId);
+      const revoke = () =>
+        program.methods
+          .revokeCompanyVerification()
+          .accounts({
+            verifier: delegate.publicKey,
+            config: getProgramConfigAddress(program.programId),
+            companyVerifier,
+            companyVerification,
+          } as any)
+          .signers([delegate])
+          .rpc();
+      await revoke();
+      assert.strictEqual(
+        await program.provider.connection.getAccountInfo(companyVerification),
+        null
+      );
+
+      await program.methods
+        .removeCompanyVerifier()
+        .accounts({
+          admin: program.provider.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          companyVerifier,
+        } as any)
+        .rpc();
+      await expectAnchorError(verify(delegate, null), "UnauthorizedVerifier");
+    });
+  });
+
+  describe("company metadata", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+    const documentHash = Array.from(
+      crypto.createHash("sha256").update("pitch deck v1").digest()
+    );
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+    });
+
+    const updateMetadata = (countryCode: number[] | null, owner = alice) =>
+      program.methods
+        .updateCompanyMetadata(
+          "https://example.com/alice.json",
+          { fintech: {} },
+          countryCode,
+          documentHash
+        )
+        .accounts({
+          owner: owner.publicKey,
+          companyProfile: round.companyProfile,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([owner])
+        .rpc();
+
+    it("lets the owner attach structured metadata", async () => {
+      await updateMetadata(Array.from(Buffer.from("KE")));
+
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      assert.strictEqual(companyProfile.metadataUri, "https://example.com/alice.json");
+      assert.deepEqual(companyProfile.category, { fintech: {} });
+      assert.deepEqual(companyProfile.countryCode, Array.from(Buffer.from("KE")));
+      assert.deepEqual(companyProfile.documentHash, documentHash);
+    });
+
+    it("rejects malformed country codes", async () => {
+      await expectAnchorError(
+        updateMetadata(Array.from(Buffer.from("ke"))),
+        "InvalidCountryCode"
+      );
+    });
+
+    it("only lets the owner update metadata", async () => {
+      const mallory = anchor.web3.Keypair.generate();
+      await airdrop(mallory.publicKey, new anchor.BN(1_000_000_000));
+      await expectAnchorError(
+        updateMetadata(null, mallory),
+        "OwnerMustBeTheSameAsCompanyProfileOwner"
+      );
+    });
+  });
+
+  describe("account versioning", () => {
+    let alice: anchor.web3.Keypair;
+    let round: FundingRoundFixture;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      round = await createCompanyWithFundingRound(alice);
+    });
+
+    const migrateCompanyProfile = (authority: anchor.web3.Keypair) =>
+      program.methods
+        .migrateCompanyProfile()
+        .accounts({
+          authority: authority.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          companyProfile: round.companyProfile,
+          companyReputation: round.companyReputation,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([authority])
+        .rpc();
+
+    const migrateFundingRound = (authority: anchor.web3.Keypair) =>
+      program.methods
+        .migrateFundingRound()
+        .accounts({
+          authority: authority.publicKey,
+          config: getProgramConfigAddress(program.programId),
+          companyProfile: round.companyProfile,
+          fundingRound: round.fundingRound,
+          vault: round.vault,
+          repaymentVault: round.repaymentVault,
+          treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([authority])
+        .rpc();
+
+    it("creates accounts on the current layout version", async () => {
+      const companyProfile = await program.account.companyProfile.fetch(round.companyProfile);
+      const fundingRound = await program.account.fundingRound.fetch(round.fundingRound);
+      assert.strictEqual(companyProfile.version, 3);
+      assert.strictEqual(fundingRound.version, 4);
+
+      // canonical bumps are stored at creation so later instructions don't search for them
+      const roundSeed = new anchor.BN(round.roundIndex).toArrayLike(Buffer, "le", 8);
+      const [, roundBump] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("funding_round"), round.companyProfile.toBuffer(), roundSeed],
+        program.programId
+      );
+      const [, vaultBump] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("funding_round_vault"), round.companyProfile.toBuffer(), roundSeed],
+        program.programId
+      );
+      assert.strictEqual(fundingRound.bump, roundBump);
+      assert.strictEqual(fundingRound.vaultBump, vaultBump);
+
+      // vaults carry a header naming their round and the balance the program has moved into them
+      const vault = await program.account.roundVault.fetch(round.vault);
+      const repaymentVault = await program.account.roundVault.fetch(round.repaymentVault);
+      assert.ok(vault.fundingRound.equals(round.fundingRound));
+      assert.deepEqual(vault.kind, { funding: {} });
+      assert.deepEqual(repaymentVault.kind, { repayment: {} });
+      assert.strictEqual(vault.trackedBalance.toNumber(), 0);
+
+      await expectAnchorError(migrateCompanyProfile(alice), "AccountAlreadyMigrated");
+      await expectAnchorError(migrateFundingRound(alice), "AccountAlreadyMigrated");
+    });
+
+    it("only lets the owner or the admin migrate accounts", async () => {
+      const mallory = anchor.web3.Keypair.generate();
+      await airdrop(mallory.publicKey, new anchor.BN(1_000_000_000));
+
+      await expectAnchorError(migrateCompanyProfile(mallory), "UnauthorizedMigration");
+      await expectAnchorError(migrateFundingRound(mallory), "UnauthorizedMigration");
+    });
+  });
+
+  describe("owner registry", () => {
+    let alice: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+    });
+
+    const createCompany = async () => {
+      const companyName = `Co ${crypto.randomBytes(6).toString("hex")}`;
+      const companyProfile = getCompanyProfileAddress(alice.publicKey, companyName, program.programId);
+      await program.methods
+        .createCompanyProfile(companyName, "Test Bio")
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile,
+          companyIndex: await getNextCompanyIndexAddress(alice.publicKey),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        } as any)
+        .signers([alice])
+        .rpc();
+      return companyProfile;
+    };
+
+    const listCompanies = async () => {
+      const registry = await program.account.ownerRegistry.fetch(
+        getOwnerRegistryAddress(alice.publicKey, program.programId)
+      );
+      const companies: PublicKey[] = [];
+      for (let i = 0; i < registry.companyCount.toNumber(); i++) {
+        const entry = await program.account.companyIndex.fetch(
+          getCompanyIndexAddress(alice.publicKey, i, program.programId)
+        );
+        companies.push(entry.company);
+      }
+      return companies;
+    };
+
+    const closeCompany = (companyProfile: PublicKey, index: number, lastIndex: number | null) =>
+      program.methods
+        .closeCompanyProfile()
+        .accounts({
+          owner: alice.publicKey,
+          companyProfile,
+          companyTreasury: getCompanyTreasuryAddress(alice.publicKey, companyProfile, program.programId),
+          companyReputation: getCompanyReputationAddress(companyProfile, program.programId),
+          ownerRegistry: getOwnerRegistryAddress(alice.publicKey, program.programId),
+          companyIndex: getCompanyIndexAddress(alice.publicKey, index, program.programId),
+          lastCompanyIndex:
+            lastIndex === null
+              ? null
+              : getCompanyIndexAddress(alice.publicKey, lastIndex, program.programId),
+          companyVerification: getCompanyVerificationAddress(companyProfile, program.programId),
+          verifier: null,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    it("lists every company owned by a wallet", async () => {
+      const first = await createCompany();
+      const second = await createCompany();
+
+      const companies = await listCompanies();
+      assert.strictEqual(companies.length, 2);
+      assert.ok(companies[0].equals(first));
+      assert.ok(companies[1].equals(second));
+      const companyProfile = await program.account.companyProfile.fetch(first);
+      assert.strictEqual(companyProfile.registered, true);
+    });
+
+    it("swaps the last company into the slot of a closed company", async () => {
+      const first = await createCompany();
+      await createCompany();
+      const third = await createCompany();
+
+      await closeCompany(first, 0, 2);
+
+      const companies = await listCompanies();
+      assert.strictEqual(companies.length, 2);
+      assert.ok(companies[0].equals(third));
+      assert.strictEqual(await program.provider.connection.getAccountInfo(first), null);
+      assert.strictEqual(
+        await program.provider.connection.getAccountInfo(
+          getCompanyIndexAddress(alice.publicKey, 2, program.programId)
+        ),
+        null
+      );
+
+      await closeCompany(third, 0, 1);
+      await closeCompany(companies[1], 0, null);
+      const registry = await program.account.ownerRegistry.fetch(
+        getOwnerRegistryAddress(alice.publicKey, program.programId)
+      );
+      assert.ok(registry.companyCount.eqn(0));
+    });
+
+    it("cannot close a company that has opened funding rounds", async () => {
+      const round = await createCompanyWithFundingRound(alice);
+      await expectAnchorError(
+        closeCompany(round.companyProfile, 0, null),
+        "CompanyHasFundingRounds"
+      );
+    });
+  });
+
+  describe("investor portfolio", () => {
+    let alice: anchor.web3.Keypair;
+    let investor: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      alice = anchor.web3.Keypair.generate();
+      await airdrop(alice.publicKey, new anchor.BN(1_000_000_000));
+      investor = anchor.web3.Keypair.generate();
+      await airdrop(investor.publicKey, new anchor.BN(3_000_000_000));
+    });
+
+    it("lists every round an investor has put money into", async () => {
+      const first = await createCompanyWithFundingRound(alice);
+      const second = await createCompanyWithFundingRound(alice);
+      await fund(investor, first, new anchor.BN(100_000_000));
+      await fund(investor, second, new anchor.BN(200_000_000));
+      // topping up an existing position does not list it twice
+      await fund(investor, first, new anchor.BN(50_000_000));
+
+      const portfolio = await program.account.investorPortfolio.fetch(
+        getInvestorPortfolioAddress(investor.publicKey, program.programId)
+      );
+      assert.ok(portfolio.positionCount.eqn(2));
+
+      const rounds: PublicKey[] = [];
+      for (let i = 0; i < portfolio.positionCount.toNumber(); i++) {
+        const entry = await program.account.portfolioEntry.fetch(
+          getPortfolioEntryAddress(investor.publicKey, i, program.programId)
+        );
+        assert.ok(
+          entry.investorPosition.equals(
+            getInvestorPositionAddress(entry.fundingRound, investor.publicKey, program.programId)
+          )
+        );
+        rounds.push(entry.fundingRound);
+      }
+      assert.ok(rounds[0].equals(first.fundingRound));
+      assert.ok(rounds[1].equals(second.fundingRound));
+    });
+  });
+
+  /** Helpers */
+  const airdrop = async (publicKey: anchor.web3.PublicKey, amount: anchor.BN) => {
+    const sig = await program.provider.connection.requestAirdrop(
+      publicKey,
+      amount.toNumber() // amount in lamports
+    );
+    await program.provider.connection.confirmTransaction(sig, "confirmed");
+  };
+
+  // get the PDA for a company profile
+  const getCompanyProfileAddress = (
+    owner: PublicKey,
+    name: string,
+    programID: PublicKey
+  ) => {
+    let hexString = crypto
+      .createHash("sha256")
+      .update(name, "utf-8")
+      .digest("hex");
+    let nameSeed = Uint8Array.from(Buffer.from(hexString, "hex"));
+
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("company_profile"),
+        owner.toBuffer(),
+        nameSeed,
+      ],
+      programID
+    )[0];
+  };
+
+  const getFundingRoundAddress = (
+    companyProfileAddress: PublicKey,
+    index: number,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("funding_round"),
+        companyProfileAddress.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      programID
+    )[0];
+  };
+
+  const getFundingRoundVaultAddress = (
+    companyProfileAddress: PublicKey,
+    index: number,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("funding_round_vault"),
+        companyProfileAddress.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      programID
+    )[0];
+  };
+
+  const getFundingRoundRepaymentVaultAddress = (
+    companyProfileAddress: PublicKey,
+    index: number,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("funding_round_repayment"),
+        companyProfileAddress.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      programID
+    )[0];
+  };
+
+  const getCompanyTreasuryAddress = (
+    owner: PublicKey,
+    companyProfileAddress: PublicKey,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("company_treasury"),
+        owner.toBuffer(),
+        companyProfileAddress.toBuffer(),
+      ],
+      programID
+    )[0];
+  };
+
+  type FundingRoundFixture = {
+    companyProfile: PublicKey;
+    companyTreasury: PublicKey;
+    companyReputation: PublicKey;
+    roundIndex: number;
+    fundingRound: PublicKey;
+    vault: PublicKey;
+    repaymentVault: PublicKey;
+  };
+
+  // creates a company profile owned by `owner` with a fresh funding round and no deposits
+  const createCompanyWithFundingRound = async (
+    owner: anchor.web3.Keypair,
+    window: { start?: number; end?: number; deadline?: number } = {}
+  ): Promise<FundingRoundFixture> => {
+    const companyName = `Co ${crypto.randomBytes(6).toString("hex")}`;
+    const companyProfile = getCompanyProfileAddress(
+      owner.publicKey,
+      companyName,
+      program.programId
+    );
+    const companyTreasury = getCompanyTreasuryAddress(
+      owner.publicKey,
+      companyProfile,
+      program.programId
+    );
+    const companyReputation = getCompanyReputationAddress(companyProfile, program.programId);
+    await program.methods
+      .createCompanyProfile(companyName, "Test Bio")
+      .accounts({
+        owner: owner.publicKey,
+        companyIndex: await getNextCompanyIndexAddress(owner.publicKey),
+        companyProfile,
+        companyTreasury,
+        companyReputation,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    return createNextFundingRound(
+      owner,
+      { companyProfile, companyTreasury, companyReputation },
+      0,
+      window
+    );
+  };
+
+  // opens the company's funding round number `roundIndex`
+  const createNextFundingRound = async (
+    owner: anchor.web3.Keypair,
+    company: Pick<FundingRoundFixture, "companyProfile" | "companyTreasury" | "companyReputation">,
+    roundIndex: number,
+    window: { start?: number; end?: number; deadline?: number } = {}
+  ): Promise<FundingRoundFixture> => {
+    const now = Math.floor(Date.now() / 1000);
+    const { companyProfile } = company;
+    const fundingRound = getFundingRoundAddress(companyProfile, roundIndex, program.programId);
+    const vault = getFundingRoundVaultAddress(companyProfile, roundIndex, program.programId);
+    const repaymentVault = getFundingRoundRepaymentVaultAddress(
+      companyProfile,
+      roundIndex,
+      program.programId
+    );
+    await program.methods
+      .createFundingRound(
+        `round-${roundIndex}`,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(10),
+        new anchor.BN(window.deadline ?? now + 1_000_000),
+        new anchor.BN(window.start ?? now - 60),
+        new anchor.BN(window.end ?? now + 500_000)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        companyProfile,
+        fundingRound,
+        vault,
+        repaymentVault,
+        treasuryLedger: getTreasuryLedgerAddress(fundingRound, program.programId),
+        collateralVault: getCollateralVaultAddress(fundingRound, program.programId),
+        companyReputation: company.companyReputation,
+        config: getProgramConfigAddress(program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    return { ...company, roundIndex, fundingRound, vault, repaymentVault };
+  };
+
+  const fund = (
+    investor: anchor.web3.Keypair,
+    round: FundingRoundFixture,
+    amount: anchor.BN,
+    allowlistProof: number[][] = [],
+    attestation: PublicKey | null = null
+  ) =>
+    program.methods
+      .fundCompany(amount, allowlistProof)
+      .accounts({
+        investor: investor.publicKey,
+        companyProfile: round.companyProfile,
+        fundingRound: round.fundingRound,
+        portfolioEntry: await getNextPortfolioEntryAddress(investor.publicKey, round.fundingRound),
+        investorPosition: getInvestorPositionAddress(
+          round.fundingRound,
+          investor.publicKey,
+          program.programId
+        ),
+        attestation,
+        vault: round.vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([investor])
+      .rpc();
+
+  // closes the round's funding window, marking it funded or failed
+  const finalizeRound = (round: FundingRoundFixture) =>
+    program.methods
+      .finalizeRound()
+      .accounts({
+        companyProfile: round.companyProfile,
+        fundingRound: round.fundingRound,
+      } as any)
+      .rpc();
+
+  const withdrawFunds = (
+    owner: anchor.web3.Keypair,
+    round: FundingRoundFixture,
+    amount: anchor.BN
+  ) =>
+    program.methods
+      .withdrawFunds(amount)
+      .accounts({
+        owner: owner.publicKey,
+        companyProfile: round.companyProfile,
+        fundingRound: round.fundingRound,
+        vault: round.vault,
+        companyTreasury: round.companyTreasury,
+        treasuryLedger: getTreasuryLedgerAddress(round.fundingRound, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  // asserts that the transaction fails with the given anchor error code
+  const expectAnchorError = async (tx: Promise<unknown>, code: string) => {
+    try {
+      await tx;
+    } catch (error) {
+      const logs = (error as anchor.AnchorError)?.logs;
+      const parsed = logs ? anchor.AnchorError.parse(logs) : null;
+      if (parsed) {
+        assert.strictEqual(parsed.error.errorCode.code, code);
+        return;
+      }
+      throw error;
+    }
+    assert.fail(`expected transaction to fail with ${code}`);
+  };
+
+  const getInvestorPositionAddress = (
+    fundingRoundAddress: PublicKey,
+    investor: PublicKey,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("investor_position"),
+        fundingRoundAddress.toBuffer(),
+        investor.toBuffer(),
+      ],
+      programID
+    )[0];
+  };
+
+  const getInvestorAttestationAddress = (
+    verifier: PublicKey,
+    investor: PublicKey,
+    programID: PublicKey
+  ) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("investor_attestation"),
+        verifier.toBuffer(),
+        investor.toBuffer(),
+      ],
+      programID
+    )[0];
+  };
+
+  // builds a sorted-pair sha256 merkle tree over the given investors, matching the on-chain verifier
+  const buildAllowlist = (investors: PublicKey[]) => {
+    const sha256 = (...parts: Buffer[]) =>
+      crypto.createHash("sha256").update(Buffer.concat(parts)).digest();
+    const hashPair = (a: Buffer, b: Buffer) =>
+      Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a);
+
+    let level = investors.map((investor) => sha256(investor.toBuffer()));
+    const proofs: Buffer[][] = investors.map(() => []);
+    let indices = investors.map((_, i) => i);
+    while (level.length > 1) {
+      const next: Buffer[] = [];
+      for (let i = 0; i < level.length; i += 2) {
+        next.push(i + 1 < level.length ? hashPair(level[i], level[i + 1]) : level[i]);
+      }
+      indices = indices.map((index, leaf) => {
+        const sibling = index ^ 1;
+        if (sibling < level.length) {
+          proofs[leaf].push(level[sibling]);
+        }
+        return index >> 1;
+      });
+      level = next;
+    }
+
+    return {
+      root: Array.from(level[0]),
+      proofs: proofs.map((proof) => proof.map((node) => Array.from(node))),
+    };
+  };
+
+  const getRoundAmendmentsAddress = (fundingRoundAddress: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("round_amendments"), fundingRoundAddress.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getAmendmentAddress = (fundingRoundAddress: PublicKey, amendmentIndex: number, programID: PublicKey) => {
+    const index = Buffer.alloc(4);
+    index.writeUInt32LE(amendmentIndex);
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("amendment"), fundingRoundAddress.toBuffer(), index],
+      programID
+    )[0];
+  };
+
+  // accounts of the next proposal for a round: its own account and the latest one it may supersede
+  const getProposalAccounts = async (fundingRoundAddress: PublicKey, roundAmendments: PublicKey) => {
+    const amendments = await program.account.roundAmendments.fetchNullable(roundAmendments);
+    const index = amendments ? amendments.amendmentCount : 0;
+    return {
+      amendment: getAmendmentAddress(fundingRoundAddress, index, program.programId),
+      latestAmendment:
+        index > 0 ? getAmendmentAddress(fundingRoundAddress, index - 1, program.programId) : null,
+    };
+  };
+
+  const getAmendmentApprovalAddress = (
+    fundingRoundAddress: PublicKey,
+    amendmentIndex: number,
+    investor: PublicKey,
+    programID: PublicKey
+  ) => {
+    const index = Buffer.alloc(4);
+    index.writeUInt32LE(amendmentIndex);
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("amendment_approval"),
+        fundingRoundAddress.toBuffer(),
+        index,
+        investor.toBuffer(),
+      ],
+      programID
+    )[0];
+  };
+
+  const getProgramConfigAddress = (programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("program_config")],
+      programID
+    )[0];
+  };
+
+  const getProgramDataAddress = (programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [programID.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    )[0];
+  };
+
+  const getCollateralVaultAddress = (fundingRoundAddress: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("collateral_vault"), fundingRoundAddress.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getTreasuryLedgerAddress = (fundingRoundAddress: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("treasury_ledger"), fundingRoundAddress.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getCompanyReputationAddress = (companyProfileAddress: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("company_reputation"), companyProfileAddress.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getCompanyVerifierAddress = (authority: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("company_verifier"), authority.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getCompanyVerificationAddress = (companyProfileAddress: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("company_verification"), companyProfileAddress.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getOwnerRegistryAddress = (owner: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("owner_registry"), owner.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getCompanyIndexAddress = (owner: PublicKey, index: number, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("company_index"),
+        owner.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      programID
+    )[0];
+  };
+
+  // address of the registry slot the owner's next company will be listed at
+  const getNextCompanyIndexAddress = async (owner: PublicKey) => {
+    const registry = await program.account.ownerRegistry.fetchNullable(
+      getOwnerRegistryAddress(owner, program.programId)
+    );
+    const companyCount = registry ? registry.companyCount.toNumber() : 0;
+    return getCompanyIndexAddress(owner, companyCount, program.programId);
+  };
+
+  const getInvestorPortfolioAddress = (investor: PublicKey, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [anchor.utils.bytes.utf8.encode("investor_portfolio"), investor.toBuffer()],
+      programID
+    )[0];
+  };
+
+  const getPortfolioEntryAddress = (investor: PublicKey, index: number, programID: PublicKey) => {
+    return PublicKey.findProgramAddressSync(
+      [
+        anchor.utils.bytes.utf8.encode("portfolio_entry"),
+        investor.toBuffer(),
+        new anchor.BN(index).toArrayLike(Buffer, "le", 8),
+      ],
+      programID
+    )[0];
+  };
+
+  // portfolio slot of the investor's position in the round, or the next free slot for a new position
+  const getNextPortfolioEntryAddress = async (investor: PublicKey, fundingRound: PublicKey) => {
+    const position = await program.account.investorPosition.fetchNullable(
+      getInvestorPositionAddress(fundingRound, investor, program.programId)
+    );
+    if (position) {
+      return getPortfolioEntryAddress(investor, position.portfolioIndex.toNumber(), program.programId);
+    }
+    const portfolio = await program.account.investorPortfolio.fetchNullable(
+      getInvestorPortfolioAddress(investor, program.programId)
+    );
+    const positionCount = portfolio ? portfolio.positionCount.toNumber() : 0;
+    return getPortfolioEntryAddress(investor, positionCount, program.programId);
   };
 });